@@ -7,7 +7,13 @@ import type { TextUnit } from './translation';
 export enum EngineType {
   RpgMakerMv = 'RpgMakerMv',
   RpgMakerMz = 'RpgMakerMz',
+  RpgMakerVxAce = 'RpgMakerVxAce',
+  RpgMakerVx = 'RpgMakerVx',
+  RpgMakerXp = 'RpgMakerXp',
+  RpgMaker2k = 'RpgMaker2k',
   WolfRpg = 'WolfRpg',
+  RenPy = 'RenPy',
+  TyranoScript = 'TyranoScript',
   Unknown = 'Unknown',
 }
 
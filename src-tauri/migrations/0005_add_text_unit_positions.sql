@@ -0,0 +1,11 @@
+-- Record where event-based text units sit inside their command lists so the
+-- prompt builder can show the surrounding lines of the same event page.
+-- Units that are not part of an event (database fields, system terms) keep NULLs.
+
+ALTER TABLE text_units ADD COLUMN event_ref TEXT;
+ALTER TABLE text_units ADD COLUMN page_index INTEGER;
+ALTER TABLE text_units ADD COLUMN command_index INTEGER;
+ALTER TABLE text_units ADD COLUMN speaker TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_text_units_event_position
+  ON text_units (project_path, event_ref, page_index, command_index);
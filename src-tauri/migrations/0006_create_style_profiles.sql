@@ -0,0 +1,25 @@
+-- Per-project character profiles and style guide used to keep voices and
+-- house style consistent across one-line translation prompts.
+
+CREATE TABLE IF NOT EXISTS character_profiles (
+  id               INTEGER PRIMARY KEY,
+  project_path     TEXT NOT NULL,
+  name             TEXT NOT NULL,
+  gender           TEXT,
+  pronouns         TEXT,
+  speech_register  TEXT,
+  catchphrases     TEXT NOT NULL DEFAULT '[]',
+  honorific_policy TEXT,
+  notes            TEXT,
+  created_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS ux_character_profiles_project_name
+  ON character_profiles (project_path, name);
+
+CREATE TABLE IF NOT EXISTS style_guides (
+  project_path TEXT PRIMARY KEY,
+  rules        TEXT NOT NULL DEFAULT '',
+  updated_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
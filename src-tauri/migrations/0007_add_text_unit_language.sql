@@ -0,0 +1,5 @@
+-- Language detected for the source text of each unit at extraction, with the
+-- identifier's confidence. Units extracted before detection existed keep NULLs.
+
+ALTER TABLE text_units ADD COLUMN detected_language TEXT;
+ALTER TABLE text_units ADD COLUMN language_confidence REAL;
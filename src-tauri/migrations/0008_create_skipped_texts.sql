@@ -0,0 +1,16 @@
+-- Strings left out of extraction, with the rule that rejected them, so users
+-- can review what the heuristics dropped and add allow rules.
+
+CREATE TABLE IF NOT EXISTS skipped_texts (
+  id            INTEGER PRIMARY KEY,
+  project_path  TEXT NOT NULL,
+  manifest_hash TEXT,
+  file_path     TEXT NOT NULL,
+  field_type    TEXT NOT NULL,
+  source_text   TEXT NOT NULL,
+  rule          TEXT NOT NULL,
+  created_at    TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS ix_skipped_texts_project_file
+  ON skipped_texts (project_path, file_path);
@@ -1,15 +1,24 @@
 use log::{info, warn};
+use std::collections::HashMap;
 use std::path::Path;
 
 use crate::db::state::ManagedTranslationState;
 use crate::db::translation::manifest::create_or_load_project_manifest;
+use crate::db::translation::model::{
+    SkippedFile, SkippedTextRecord, TextUnitQuery, TextUnitRecord,
+};
 use crate::db::translation::repo;
 use crate::engines::factory::{
     extract_game_data_files as factory_extract_game_data_files, get_engine,
 };
+use crate::engines::rpg_maker_mv::fonts::FontCoverageReport;
 use crate::models::engine::{EngineInfo, GameDataFile};
 use crate::models::language::Language;
+use crate::models::settings::ProjectSettings;
 use crate::models::translation::{TextUnit, TranslationStatus};
+use crate::utils::text::hooks::ScriptHooks;
+use crate::utils::text::rules::{plugin_presets, project_rules, RulePreset};
+use crate::utils::text::validation::ExtractionFilter;
 // removed unused: PathBuf, SystemTime, UNIX_EPOCH
 
 /// Loads a project from the specified path.
@@ -111,12 +120,13 @@ async fn extract_text_from_files(
         get_engine(&project_info.path).map_err(|e| format!("Failed to get engine: {}", e))?;
 
     // Extract text units from files
-    let extracted_units = engine
-        .extract_text_units(&project_info)
+    let (extracted_units, skipped_texts) = engine
+        .extract_text_units_with_skipped(&project_info)
         .map_err(|e| format!("Failed to extract text units: {}", e))?;
     info!(
-        "Successfully extracted {} text units from files",
-        extracted_units.len()
+        "Successfully extracted {} text units from files ({} skipped)",
+        extracted_units.len(),
+        skipped_texts.len()
     );
 
     // Save ALL extracted units to database for complete persistence
@@ -160,6 +170,23 @@ async fn extract_text_from_files(
                 // Don't fail the extraction if database save fails - user can still work
             }
         }
+
+        // Keep the skipped strings of this extraction for review
+        let project_path = project_info.path.to_string_lossy().to_string();
+        let skipped_records: Vec<SkippedTextRecord> = skipped_texts
+            .iter()
+            .map(|skipped| SkippedTextRecord::from_skipped_text(skipped, &project_path))
+            .collect();
+        if let Err(e) = repo::replace_skipped_texts(
+            db,
+            &project_path,
+            project_info.manifest_hash.as_deref(),
+            &skipped_records,
+        )
+        .await
+        {
+            warn!("Failed to save skipped texts to database: {}", e);
+        }
     } else {
         warn!("No database state available - extracted units will not be persisted");
     }
@@ -180,6 +207,118 @@ async fn extract_text_from_files(
     Ok(extracted_units)
 }
 
+/// Extract the project again with its current settings and merge the result into
+/// the database.
+///
+/// Stored units keep their translation and status; units the extraction finds
+/// for the first time are added, and untranslated units it no longer produces
+/// (e.g. strings a new deny rule matches) are removed. The skipped texts are
+/// replaced by those of this extraction.
+pub async fn reextract_text(
+    project_info: EngineInfo,
+    db: &ManagedTranslationState,
+) -> Result<Vec<TextUnit>, String> {
+    let manifest_hash = project_info
+        .manifest_hash
+        .clone()
+        .ok_or_else(|| "Manifest hash required for re-extraction".to_string())?;
+    let engine =
+        get_engine(&project_info.path).map_err(|e| format!("Failed to get engine: {}", e))?;
+    let (extracted_units, skipped_texts) = engine
+        .extract_text_units_with_skipped(&project_info)
+        .map_err(|e| format!("Failed to extract text units: {}", e))?;
+
+    let project_path = project_info.path.to_string_lossy().to_string();
+    let query = TextUnitQuery {
+        project_path: Some(project_path.clone()),
+        manifest_hash: Some(manifest_hash.clone()),
+        ..Default::default()
+    };
+    let existing = repo::find_units(db, &query)
+        .await
+        .map_err(|e| format!("Failed to load text units from database: {}", e))?;
+
+    let (new_units, stale_ids) = merge_extracted_units(&existing, &extracted_units);
+    info!(
+        "Re-extracted {} text units: {} new, {} no longer extracted ({} skipped)",
+        extracted_units.len(),
+        new_units.len(),
+        stale_ids.len(),
+        skipped_texts.len()
+    );
+
+    let file_path = format!("{}/data", project_path);
+    let records: Vec<TextUnitRecord> = new_units
+        .into_iter()
+        .map(|unit| {
+            TextUnitRecord::from_text_unit(unit, &project_path, &file_path, Some(&manifest_hash))
+        })
+        .collect();
+    let result = repo::bulk_upsert_units(db, &records)
+        .await
+        .map_err(|e| format!("Failed to save new text units: {}", e))?;
+    if !result.errors.is_empty() {
+        return Err(format!(
+            "Failed to save new text units: {:?}",
+            result.errors
+        ));
+    }
+    repo::bulk_delete_units(db, stale_ids)
+        .await
+        .map_err(|e| format!("Failed to remove stale text units: {}", e))?;
+
+    let skipped_records: Vec<SkippedTextRecord> = skipped_texts
+        .iter()
+        .map(|skipped| SkippedTextRecord::from_skipped_text(skipped, &project_path))
+        .collect();
+    repo::replace_skipped_texts(db, &project_path, Some(&manifest_hash), &skipped_records)
+        .await
+        .map_err(|e| format!("Failed to save skipped texts: {}", e))?;
+
+    let units = load_text_with_smart_routing(project_info.clone(), Some(db)).await?;
+    if let Err(e) =
+        update_manifest_with_total_units(&project_info.path, &manifest_hash, units.len() as i64)
+            .await
+    {
+        warn!("Failed to update manifest with total text units: {}", e);
+    }
+    Ok(units)
+}
+
+/// Compare a fresh extraction with the stored records of the project.
+///
+/// Units match records by field type and source text. Returns the units without
+/// a stored record and the ids of untranslated records no unit matched.
+fn merge_extracted_units<'a>(
+    existing: &[TextUnitRecord],
+    extracted: &'a [TextUnit],
+) -> (Vec<&'a TextUnit>, Vec<i64>) {
+    let mut stored: HashMap<(&str, &str), Vec<&TextUnitRecord>> = HashMap::new();
+    for record in existing {
+        stored
+            .entry((&record.field_type, &record.source_text))
+            .or_default()
+            .push(record);
+    }
+
+    let new_units = extracted
+        .iter()
+        .filter(|unit| {
+            stored
+                .get_mut(&(unit.field_type.as_str(), unit.source_text.as_str()))
+                .and_then(|records| records.pop())
+                .is_none()
+        })
+        .collect();
+    let stale_ids = stored
+        .into_values()
+        .flatten()
+        .filter(|record| record.status == "NotTranslated")
+        .filter_map(|record| record.id)
+        .collect();
+    (new_units, stale_ids)
+}
+
 /// Load text units from database with smart status-based routing
 /// Now loads ALL units (including NotTranslated) for complete project state restoration
 async fn load_text_with_smart_routing(
@@ -321,32 +460,38 @@ pub async fn load_translations_from_database(
 
     let text_units = records
         .into_iter()
-        .map(|record| TextUnit {
-            id: record
-                .id
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| format!("{}/{}", record.file_path, record.field_type)),
-            source_text: record.source_text,
-            translated_text: record.translated_text.unwrap_or_default(),
-            field_type: record.field_type,
-            status: match record.status.as_str() {
-                "MachineTranslated" => TranslationStatus::MachineTranslated,
-                "HumanReviewed" => TranslationStatus::HumanReviewed,
-                "NotTranslated" => TranslationStatus::NotTranslated,
-                "Ignored" => TranslationStatus::Ignored,
-                _ => TranslationStatus::NotTranslated,
-            },
-            prompt_type: match record.prompt_type.as_str() {
-                "Dialogue" => PromptType::Dialogue,
-                "Character" => PromptType::Character,
-                "Class" => PromptType::Class,
-                "Skill" => PromptType::Skill,
-                "State" => PromptType::State,
-                "Equipment" => PromptType::Equipment,
-                "System" => PromptType::System,
-                "Other" => PromptType::Other,
-                _ => PromptType::Other,
-            },
+        .map(|record| {
+            let position = record.position();
+            let detected_language = record.detected_language();
+            TextUnit {
+                id: record
+                    .id
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| format!("{}/{}", record.file_path, record.field_type)),
+                source_text: record.source_text,
+                translated_text: record.translated_text.unwrap_or_default(),
+                field_type: record.field_type,
+                status: match record.status.as_str() {
+                    "MachineTranslated" => TranslationStatus::MachineTranslated,
+                    "HumanReviewed" => TranslationStatus::HumanReviewed,
+                    "NotTranslated" => TranslationStatus::NotTranslated,
+                    "Ignored" => TranslationStatus::Ignored,
+                    _ => TranslationStatus::NotTranslated,
+                },
+                prompt_type: match record.prompt_type.as_str() {
+                    "Dialogue" => PromptType::Dialogue,
+                    "Character" => PromptType::Character,
+                    "Class" => PromptType::Class,
+                    "Skill" => PromptType::Skill,
+                    "State" => PromptType::State,
+                    "Equipment" => PromptType::Equipment,
+                    "System" => PromptType::System,
+                    "Other" => PromptType::Other,
+                    _ => PromptType::Other,
+                },
+                position,
+                detected_language,
+            }
         })
         .collect::<Vec<_>>();
 
@@ -387,6 +532,16 @@ pub async fn export_translated_subset(
         .map_err(|e| e.to_string())
 }
 
+/// Report translated units with characters missing from the game font
+pub async fn check_font_coverage(
+    project_info: EngineInfo,
+    db: &crate::db::state::ManagedTranslationState,
+) -> Result<FontCoverageReport, String> {
+    crate::engines::factory::check_font_coverage(&project_info, db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // removed copy_file_create_parent; logic centralized in factory
 
 /// Helper function to update the manifest with total text units count
@@ -449,6 +604,60 @@ pub async fn update_manifest_with_translated_units(
     }
 }
 
+/// Read the per-project settings from the project manifest
+pub async fn get_project_settings(project_info: EngineInfo) -> Result<ProjectSettings, String> {
+    Ok(crate::db::translation::manifest::ProjectManifest::read_settings(&project_info.path))
+}
+
+/// Replace the per-project settings stored in the project manifest
+pub async fn update_project_settings(
+    project_info: EngineInfo,
+    settings: ProjectSettings,
+) -> Result<ProjectSettings, String> {
+    // Refuse rules that would fail every extraction and export later
+    project_rules(&settings.formatter, &project_info.path).map_err(|e| e.to_string())?;
+    ExtractionFilter::from_settings(&settings.extraction).map_err(|e| e.to_string())?;
+    ScriptHooks::for_project(&settings.scripts, &project_info.path).map_err(|e| e.to_string())?;
+
+    let mut manifest = create_or_load_project_manifest(&project_info)
+        .map_err(|e| format!("Failed to load manifest: {}", e))?;
+    manifest.settings = settings;
+    manifest
+        .save_to_project(&project_info.path)
+        .map_err(|e| format!("Failed to save manifest: {}", e))?;
+    info!("Updated project settings for {}", project_info.name);
+    Ok(manifest.settings)
+}
+
+/// Built-in formatter rule presets projects can enable for message plugins
+pub async fn list_formatter_presets() -> Result<Vec<RulePreset>, String> {
+    Ok(plugin_presets())
+}
+
+/// Strings the last extraction left out, grouped by game file
+pub async fn list_skipped_texts(
+    project_info: EngineInfo,
+    state: &ManagedTranslationState,
+) -> Result<Vec<SkippedFile>, String> {
+    let project_path = project_info.path.to_string_lossy().to_string();
+    let records = repo::find_skipped_texts(state, &project_path)
+        .await
+        .map_err(|e| format!("Failed to load skipped texts: {}", e))?;
+
+    // Records come ordered by file
+    let mut files: Vec<SkippedFile> = Vec::new();
+    for record in records {
+        match files.last_mut() {
+            Some(file) if file.file_path == record.file_path => file.texts.push(record),
+            _ => files.push(SkippedFile {
+                file_path: record.file_path.clone(),
+                texts: vec![record],
+            }),
+        }
+    }
+    Ok(files)
+}
+
 #[derive(serde::Serialize)]
 pub struct ProjectInfo {
     pub name: String,
@@ -511,3 +720,94 @@ pub async fn delete_project(
     info!("Successfully deleted project: {}", project_hash);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    fn language(id: &str, label: &str) -> Language {
+        Language {
+            id: id.to_string(),
+            label: label.to_string(),
+            native_name: label.to_string(),
+            dir: "ltr".to_string(),
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reextract_applies_filter_and_keeps_translations() {
+        let dir = TempDir::new("reextract_test");
+        let project = dir.path().join("project");
+        let items = project.join("www/data/Items.json");
+        std::fs::create_dir_all(items.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(project.join("www/js")).unwrap();
+        std::fs::write(project.join("www/js/rpg_core.js"), "").unwrap();
+        let write_items = |names: &[&str]| {
+            let mut entries = vec![serde_json::Value::Null];
+            for (index, name) in names.iter().enumerate() {
+                entries.push(serde_json::json!({
+                    "id": index + 1,
+                    "name": name,
+                    "description": "",
+                    "note": ""
+                }));
+            }
+            std::fs::write(&items, serde_json::Value::Array(entries).to_string()).unwrap();
+        };
+        write_items(&["こんにちは", "さようなら"]);
+
+        let info = load_project(
+            project.to_string_lossy().to_string(),
+            language("ja", "Japanese"),
+            language("en", "English"),
+        )
+        .await
+        .unwrap();
+        let db = ManagedTranslationState::new(dir.path().join("translations.db"));
+        assert_eq!(
+            extract_text(info.clone(), Some(&db)).await.unwrap().len(),
+            2
+        );
+
+        // Translate one line, then deny both lines and add a new one
+        let query = TextUnitQuery {
+            project_path: Some(project.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let mut record = repo::find_units(&db, &query)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|record| record.source_text.starts_with("こんにちは"))
+            .unwrap();
+        record.translated_text = Some("Hello".to_string());
+        record.status = "MachineTranslated".to_string();
+        repo::upsert_unit(&db, &record).await.unwrap();
+
+        let mut settings = get_project_settings(info.clone()).await.unwrap();
+        settings.extraction.deny = vec!["^(こんにちは|さようなら)".to_string()];
+        update_project_settings(info.clone(), settings)
+            .await
+            .unwrap();
+        write_items(&["こんにちは", "さようなら", "また明日"]);
+
+        let units = reextract_text(info.clone(), &db).await.unwrap();
+        let mut texts: Vec<(&str, &str)> = units
+            .iter()
+            .map(|unit| (unit.source_text.as_str(), unit.translated_text.as_str()))
+            .collect();
+        texts.sort();
+        assert_eq!(texts, vec![("こんにちは", "Hello"), ("また明日", "")]);
+
+        let skipped: Vec<String> = list_skipped_texts(info, &db)
+            .await
+            .unwrap()
+            .into_iter()
+            .flat_map(|file| file.texts)
+            .map(|text| text.source_text)
+            .collect();
+        assert!(skipped.iter().any(|text| text.starts_with("さようなら")));
+    }
+}
@@ -15,24 +15,29 @@ use tauri::State;
 
 // Internal command modules
 use crate::commands::{
-    engine, glossary as glossary_cmd, languages, provider, translation, updater,
+    engine, glossary as glossary_cmd, languages, profiles, provider, translation, updater,
 };
 
 // Database types
 use crate::db::{
     glossary::model::{GlossaryQuery, GlossaryTerm},
+    profiles::model::{CharacterProfile, StyleGuide},
     state::ManagedTranslationState,
+    translation::model::SkippedFile,
     ManagedGlossaryState,
 };
 
 // Core types
+use crate::engines::rpg_maker_mv::fonts::FontCoverageReport;
 use crate::llm::state::LlmState;
 use crate::models::{
     engine::{EngineInfo, GameDataFile},
     language::Language,
     provider::{LlmConfig, ModelInfo},
+    settings::ProjectSettings,
     translation::TextUnit,
 };
+use crate::utils::text::rules::RulePreset;
 
 // ============================================================================
 // PROJECT MANAGEMENT COMMANDS
@@ -66,6 +71,16 @@ pub async fn extract_text_with_merge(
     engine::extract_text(project_info, Some(&db)).await
 }
 
+/// Extract a project again with its current settings, keeping stored translations
+#[tauri::command]
+pub async fn reextract_text(
+    project_info: EngineInfo,
+    db: State<'_, ManagedTranslationState>,
+) -> Result<Vec<TextUnit>, String> {
+    debug!("Command: reextract_text - {}", project_info.name);
+    engine::reextract_text(project_info, &db).await
+}
+
 /// Extract all game data files from a project
 #[tauri::command]
 pub async fn extract_game_data_files(
@@ -104,6 +119,40 @@ pub async fn delete_project(
     engine::delete_project(&db, project_hash).await
 }
 
+/// Get the per-project settings stored in the manifest
+#[tauri::command]
+pub async fn get_project_settings(project_info: EngineInfo) -> Result<ProjectSettings, String> {
+    debug!("Command: get_project_settings - {}", project_info.name);
+    engine::get_project_settings(project_info).await
+}
+
+/// Update the per-project settings stored in the manifest
+#[tauri::command]
+pub async fn update_project_settings(
+    project_info: EngineInfo,
+    settings: ProjectSettings,
+) -> Result<ProjectSettings, String> {
+    debug!("Command: update_project_settings - {}", project_info.name);
+    engine::update_project_settings(project_info, settings).await
+}
+
+/// List the formatter rule presets available for message plugins
+#[tauri::command]
+pub async fn list_formatter_presets() -> Result<Vec<RulePreset>, String> {
+    debug!("Command: list_formatter_presets");
+    engine::list_formatter_presets().await
+}
+
+/// List the strings the last extraction skipped, grouped by file
+#[tauri::command]
+pub async fn list_skipped_texts(
+    project_info: EngineInfo,
+    db: State<'_, ManagedTranslationState>,
+) -> Result<Vec<SkippedFile>, String> {
+    debug!("Command: list_skipped_texts - {}", project_info.name);
+    engine::list_skipped_texts(project_info, &db).await
+}
+
 // ============================================================================
 // EXPORT COMMANDS
 // ============================================================================
@@ -122,6 +171,16 @@ pub async fn export_translated_subset(
     engine::export_translated_subset(project_info, &db, destination_root).await
 }
 
+/// Report translated units the game font (or the replacement font) cannot render
+#[tauri::command]
+pub async fn check_font_coverage(
+    project_info: EngineInfo,
+    db: State<'_, ManagedTranslationState>,
+) -> Result<FontCoverageReport, String> {
+    debug!("Command: check_font_coverage - {}", project_info.name);
+    engine::check_font_coverage(project_info, &db).await
+}
+
 // ============================================================================
 // TRANSLATION & LLM COMMANDS
 // ============================================================================
@@ -154,6 +213,32 @@ pub async fn translate_text_unit(
     .map_err(|e| e.to_string())
 }
 
+/// Preview the rendered prompts for a selection of units and the projected project cost
+#[tauri::command]
+pub async fn preview_translation_prompts(
+    glossary: State<'_, ManagedGlossaryState>,
+    db: State<'_, ManagedTranslationState>,
+    text_units: Vec<TextUnit>,
+    config: LlmConfig,
+    engine_info: EngineInfo,
+) -> Result<translation::PromptPreview, String> {
+    debug!(
+        "Command: preview_translation_prompts - {} units",
+        text_units.len()
+    );
+    let manifest_hash = engine_info.manifest_hash.clone();
+    translation::preview_translation_prompts(
+        &glossary,
+        &db,
+        text_units,
+        &config,
+        &engine_info,
+        manifest_hash.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // LLM PROVIDER COMMANDS
 // ============================================================================
@@ -254,6 +339,70 @@ pub async fn glossary_import_terms(
         .map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// CHARACTER PROFILE & STYLE GUIDE COMMANDS
+// ============================================================================
+
+/// List the character profiles of a project
+#[tauri::command]
+pub async fn list_character_profiles(
+    translation: State<'_, ManagedTranslationState>,
+    project_path: String,
+) -> Result<Vec<CharacterProfile>, String> {
+    debug!("Command: list_character_profiles");
+    profiles::list_profiles(&translation, project_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create or update a character profile
+#[tauri::command]
+pub async fn upsert_character_profile(
+    translation: State<'_, ManagedTranslationState>,
+    profile: CharacterProfile,
+) -> Result<i64, String> {
+    debug!("Command: upsert_character_profile - {}", profile.name);
+    profiles::upsert_profile(&translation, profile)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a character profile by ID
+#[tauri::command]
+pub async fn delete_character_profile(
+    translation: State<'_, ManagedTranslationState>,
+    id: i64,
+) -> Result<(), String> {
+    debug!("Command: delete_character_profile - {}", id);
+    profiles::delete_profile(&translation, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the style guide of a project
+#[tauri::command]
+pub async fn get_style_guide(
+    translation: State<'_, ManagedTranslationState>,
+    project_path: String,
+) -> Result<StyleGuide, String> {
+    debug!("Command: get_style_guide");
+    profiles::get_style_guide(&translation, project_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Save the style guide of a project
+#[tauri::command]
+pub async fn save_style_guide(
+    translation: State<'_, ManagedTranslationState>,
+    guide: StyleGuide,
+) -> Result<(), String> {
+    debug!("Command: save_style_guide");
+    profiles::save_style_guide(&translation, guide)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // TRANSLATION MANAGEMENT COMMANDS
 // ============================================================================
@@ -9,6 +9,7 @@ pub mod engine;
 pub mod glossary;
 pub mod handler;
 pub mod languages;
+pub mod profiles;
 pub mod provider;
 pub mod translation;
 pub mod updater;
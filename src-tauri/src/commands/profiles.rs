@@ -0,0 +1,33 @@
+use crate::core::error::AppResult;
+use crate::db::profiles::model::{CharacterProfile, StyleGuide};
+use crate::db::state::ManagedTranslationState;
+
+pub async fn list_profiles(
+    state: &ManagedTranslationState,
+    project_path: String,
+) -> AppResult<Vec<CharacterProfile>> {
+    crate::db::profiles::repo::find_profiles(state, &project_path).await
+}
+
+pub async fn upsert_profile(
+    state: &ManagedTranslationState,
+    profile: CharacterProfile,
+) -> AppResult<i64> {
+    crate::db::profiles::repo::upsert_profile(state, &profile).await
+}
+
+pub async fn delete_profile(state: &ManagedTranslationState, id: i64) -> AppResult<()> {
+    crate::db::profiles::repo::delete_profile(state, id).await
+}
+
+/// Get the project's style guide (empty when none was saved yet)
+pub async fn get_style_guide(
+    state: &ManagedTranslationState,
+    project_path: String,
+) -> AppResult<StyleGuide> {
+    crate::db::profiles::repo::find_style_guide(state, &project_path).await
+}
+
+pub async fn save_style_guide(state: &ManagedTranslationState, guide: StyleGuide) -> AppResult<()> {
+    crate::db::profiles::repo::save_style_guide(state, &guide).await
+}
@@ -1,15 +1,28 @@
 use log::{debug, error, info, warn};
+use std::sync::Arc;
 
 use crate::core::error::AppResult;
-use crate::core::provider::GenerationResponse;
-use crate::db::glossary::GlossaryQuery;
+use crate::core::provider::{GenerationResponse, TokenUsage};
+use crate::db::glossary::GlossaryTerm;
 use crate::db::state::ManagedTranslationState;
+use crate::db::translation::manifest::ProjectManifest;
+use crate::db::translation::model::{TextUnitQuery, TextUnitRecord};
 use crate::db::ManagedGlossaryState;
 use crate::llm::state::LlmState;
 use crate::models::engine::EngineInfo;
 use crate::models::provider::LlmConfig;
+use crate::models::settings::{ContextWindowSettings, FewShotSettings, PlaceholderMode};
 use crate::models::translation::{PromptType, TextUnit, TranslationStatus};
 use crate::utils::prompts::builder::PromptBuilder;
+use crate::utils::prompts::context::{ContextLine, PromptContext};
+use crate::utils::prompts::examples::{select_examples, FewShotExample};
+use crate::utils::prompts::tokens::estimate_tokens;
+use crate::utils::text::engine_processor::EngineTextProcessor;
+use crate::utils::text::hooks::ScriptHooks;
+use crate::utils::text::placeholders::{
+    check_placeholders, extract_placeholders, repair_placeholders, PlaceholderCheck,
+    PlaceholderTable,
+};
 use tauri::State;
 use tokio::time::{sleep, timeout, Duration};
 
@@ -28,6 +41,43 @@ pub struct ActualTokenUsage {
     pub model_name: String,
 }
 
+/// Rendered prompt and token estimate for one unit, as returned by the preview
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UnitPromptPreview {
+    /// Text unit ID this preview is for
+    pub text_unit_id: String,
+    /// The exact prompt the model would receive
+    pub prompt: String,
+    /// Glossary terms selected for this unit
+    pub glossary_terms: Vec<GlossaryTerm>,
+    /// Estimated prompt tokens
+    pub estimated_input_tokens: usize,
+    /// Estimated completion tokens (assumed close to the source length)
+    pub estimated_output_tokens: usize,
+}
+
+/// Projected token usage and cost for every untranslated unit of a project
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectCostEstimate {
+    /// Number of units still waiting for translation
+    pub untranslated_units: usize,
+    /// Estimated prompt tokens for all of them
+    pub estimated_input_tokens: usize,
+    /// Estimated completion tokens for all of them
+    pub estimated_output_tokens: usize,
+    /// Estimated cost from the model's pricing
+    pub estimated_cost: f64,
+    /// Currency of `estimated_cost`
+    pub currency: String,
+}
+
+/// Prompt preview for a selection of units plus the whole-project projection
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PromptPreview {
+    pub units: Vec<UnitPromptPreview>,
+    pub project_estimate: ProjectCostEstimate,
+}
+
 /// Response for text unit translation including token usage
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TranslationResult {
@@ -35,6 +85,11 @@ pub struct TranslationResult {
     pub text_unit: TextUnit,
     /// Actual token usage for this translation (if available)
     pub token_usage: Option<ActualTokenUsage>,
+    /// Set when the translation's placeholders still differ from the source after
+    /// repair and one stricter retry; the unit stays untranslated and
+    /// `text_unit.translated_text` only holds the rejected draft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder_check: Option<PlaceholderCheck>,
 }
 
 // ===== CRUD OPERATIONS (from translations.rs) =====
@@ -111,7 +166,7 @@ pub async fn translate_text_unit(
     state: State<'_, LlmState>,
     glossary: State<'_, ManagedGlossaryState>,
     db: State<'_, ManagedTranslationState>,
-    text_unit: TextUnit,
+    mut text_unit: TextUnit,
     config: LlmConfig,
     engine_info: EngineInfo,
     manifest_hash: Option<String>,
@@ -129,54 +184,66 @@ pub async fn translate_text_unit(
         _ => sleep(Duration::from_millis(200)).await, // Default: 200ms delay for local Ollama
     }
     // Build prompt at the command layer to keep service focused on generation
-    // Try to fetch glossary terms filtered by prompt type
-    let categories: Vec<String> = match text_unit.prompt_type {
-        PromptType::Dialogue | PromptType::Character => {
-            vec!["Characters".into(), "Essential Terms".into()]
-        }
-        PromptType::State | PromptType::Skill => vec![
-            "Status Effects".into(),
-            "Mechanics".into(),
-            "Essential Terms".into(),
-        ],
-        PromptType::Equipment => vec!["Mechanics".into(), "Essential Terms".into()],
-        PromptType::System | PromptType::Class | PromptType::Other => {
-            vec!["Mechanics".into(), "Essential Terms".into()]
-        }
-    };
-
-    let q = GlossaryQuery {
-        source_lang: engine_info.source_language.id.clone(),
-        target_lang: engine_info.target_language.id.clone(),
-        categories,
-        prompt_types: Vec::new(),
-        project_scope: Some(engine_info.path.to_string_lossy().to_string()),
-        limit: Some(200),
-        only_enabled: true,
-    };
-
-    let terms = match crate::db::glossary::repo::find_terms(&glossary, &q).await {
-        Ok(v) => v,
-        Err(_) => Vec::new(),
-    };
-
-    let prompt = if terms.is_empty() {
-        PromptBuilder::build_translation_prompt(&text_unit, &engine_info).await
-    } else {
-        PromptBuilder::build_translation_prompt_with_terms(&text_unit, &engine_info, &terms).await
-    };
-    let generation_result = translate_with_retry_and_usage(&*state, &prompt).await?;
+    let prepared = prepare_prompt(
+        &glossary,
+        &db,
+        &mut text_unit,
+        &engine_info,
+        manifest_hash.as_deref(),
+    )
+    .await?;
+    let generation_result = translate_with_retry_and_usage(&*state, &prepared.prompt).await?;
+    let mut usage = generation_result.token_usage;
 
     // Clean the model output to remove thinking process and extract only translation
-    let cleaned_content = clean_model_output(&generation_result.content);
+    let mut cleaned_content = prepared.finish_output(&text_unit, &generation_result.content)?;
+
+    // Placeholders must survive translation, otherwise restoring the engine codes breaks the line
+    let mut placeholder_check = None;
+    match verify_placeholders(&text_unit.source_text, &cleaned_content) {
+        Ok(verified) => cleaned_content = verified,
+        Err(check) => {
+            warn!(
+                "Unit {} lost placeholders (missing {:?}, unexpected {:?}); retrying with a stricter prompt",
+                text_unit.id, check.missing, check.unexpected
+            );
+            let strict_prompt = PromptBuilder::with_placeholder_reminder(
+                &prepared.prompt,
+                &prepared.required_tokens(&text_unit.source_text),
+            );
+            let retry = translate_with_retry_and_usage(&state, &strict_prompt).await?;
+            usage = add_token_usage(usage, retry.token_usage);
+            let retry_content = prepared.finish_output(&text_unit, &retry.content)?;
+            match verify_placeholders(&text_unit.source_text, &retry_content) {
+                Ok(verified) => cleaned_content = verified,
+                Err(check) => {
+                    warn!(
+                        "Unit {} still has placeholder problems after retry; leaving it untranslated",
+                        text_unit.id
+                    );
+                    cleaned_content = retry_content;
+                    placeholder_check = Some(check);
+                }
+            }
+        }
+    }
 
-    // Create updated text unit
+    // Create updated text unit; a draft with broken placeholders is returned for
+    // review but never stored as a translation
     let mut updated_unit = text_unit;
     updated_unit.translated_text = cleaned_content;
-    updated_unit.status = TranslationStatus::MachineTranslated;
+    updated_unit.status = if placeholder_check.is_some() {
+        TranslationStatus::NotTranslated
+    } else {
+        TranslationStatus::MachineTranslated
+    };
+    let stored_translation = match updated_unit.status {
+        TranslationStatus::NotTranslated => None,
+        _ => Some(updated_unit.translated_text.clone()),
+    };
 
     // Create token usage record if available
-    let token_usage = generation_result.token_usage.map(|usage| ActualTokenUsage {
+    let token_usage = usage.map(|usage| ActualTokenUsage {
         input_tokens: usage.input_tokens,
         output_tokens: usage.output_tokens,
         total_tokens: usage.total_tokens,
@@ -222,7 +289,7 @@ pub async fn translate_text_unit(
 
     let save_result = if let Some(mut record) = existing_record {
         // Update existing record with translation data
-        record.translated_text = Some(updated_unit.translated_text.clone());
+        record.translated_text = stored_translation;
         record.status = match updated_unit.status {
             crate::models::translation::TranslationStatus::NotTranslated => {
                 "NotTranslated".to_string()
@@ -244,12 +311,13 @@ pub async fn translate_text_unit(
             "Could not find existing record for unit {}, creating new one",
             updated_unit.id
         );
-        let text_unit_record = crate::db::translation::model::TextUnitRecord::from_text_unit(
+        let mut text_unit_record = crate::db::translation::model::TextUnitRecord::from_text_unit(
             &updated_unit,
             &project_path,
             &file_path,
             manifest_hash.as_deref(),
         );
+        text_unit_record.translated_text = stored_translation;
         crate::db::translation::repo::upsert_unit(&db, &text_unit_record).await
     };
 
@@ -280,11 +348,391 @@ pub async fn translate_text_unit(
     Ok(TranslationResult {
         text_unit: updated_unit,
         token_usage,
+        placeholder_check,
+    })
+}
+
+// ===== PROMPT PREVIEW =====
+
+/// Render the prompts for `text_units` without calling the model, and project
+/// the cost of translating the rest of the project.
+///
+/// The per-unit prompt overhead (template, glossary, context) measured on the
+/// previewed units is applied to every untranslated unit in the project.
+pub async fn preview_translation_prompts(
+    glossary: &ManagedGlossaryState,
+    db: &ManagedTranslationState,
+    text_units: Vec<TextUnit>,
+    config: &LlmConfig,
+    engine_info: &EngineInfo,
+    manifest_hash: Option<&str>,
+) -> AppResult<PromptPreview> {
+    let mut units = Vec::with_capacity(text_units.len());
+    let mut overhead_total = 0usize;
+    for mut text_unit in text_units {
+        let prepared =
+            prepare_prompt(glossary, db, &mut text_unit, engine_info, manifest_hash).await?;
+        let estimated_input_tokens = estimate_tokens(&prepared.prompt);
+        let source_tokens = estimate_tokens(&text_unit.source_text);
+        overhead_total += estimated_input_tokens.saturating_sub(source_tokens);
+        units.push(UnitPromptPreview {
+            text_unit_id: text_unit.id,
+            prompt: prepared.prompt,
+            glossary_terms: prepared.terms,
+            estimated_input_tokens,
+            estimated_output_tokens: source_tokens,
+        });
+    }
+    let overhead_per_unit = if units.is_empty() {
+        0
+    } else {
+        overhead_total / units.len()
+    };
+
+    let query = TextUnitQuery {
+        project_path: Some(engine_info.path.to_string_lossy().to_string()),
+        manifest_hash: manifest_hash.map(|s| s.to_string()),
+        status: Some("NotTranslated".to_string()),
+        ..Default::default()
+    };
+    let untranslated = crate::db::translation::repo::find_units(db, &query).await?;
+
+    let mut estimated_input_tokens = 0usize;
+    let mut estimated_output_tokens = 0usize;
+    for record in &untranslated {
+        let source_tokens = estimate_tokens(&record.source_text);
+        estimated_input_tokens += overhead_per_unit + source_tokens;
+        estimated_output_tokens += source_tokens;
+    }
+    let pricing = &config.model.pricing;
+    let estimated_cost = estimated_input_tokens as f64 / 1000.0 * pricing.input_price_per_1k
+        + estimated_output_tokens as f64 / 1000.0 * pricing.output_price_per_1k;
+
+    Ok(PromptPreview {
+        units,
+        project_estimate: ProjectCostEstimate {
+            untranslated_units: untranslated.len(),
+            estimated_input_tokens,
+            estimated_output_tokens,
+            estimated_cost,
+            currency: pricing.currency.clone(),
+        },
     })
 }
 
 // ===== HELPER FUNCTIONS =====
 
+/// Maximum number of reviewed units scored when picking few-shot examples
+const FEW_SHOT_CANDIDATE_LIMIT: i64 = 500;
+
+/// A fully rendered prompt together with the glossary terms it includes
+struct PreparedPrompt {
+    prompt: String,
+    terms: Vec<GlossaryTerm>,
+    /// Side table for the opaque placeholder mode
+    placeholders: Option<PlaceholderTable>,
+    /// Project script hooks, applied to the model output
+    hooks: Arc<ScriptHooks>,
+}
+
+impl PreparedPrompt {
+    /// Turn model output back into named placeholders (no-op in named mode)
+    fn decode_output(&self, content: &str) -> String {
+        match &self.placeholders {
+            Some(table) => EngineTextProcessor::decode_opaque_placeholders(content, table),
+            None => content.to_string(),
+        }
+    }
+
+    /// Cleaned, decoded model output after the project's `after_clean` hook
+    fn finish_output(&self, text_unit: &TextUnit, content: &str) -> AppResult<String> {
+        let cleaned = self.decode_output(&clean_model_output(content));
+        self.hooks.after_clean(text_unit, &cleaned)
+    }
+
+    /// Placeholders as the model sees them, listed in the strict retry prompt
+    fn required_tokens(&self, source_text: &str) -> Vec<String> {
+        match &self.placeholders {
+            Some(table) => table.tokens(),
+            None => extract_placeholders(source_text),
+        }
+    }
+}
+
+/// Assemble the prompt for a unit exactly as it will be sent to the model.
+///
+/// Shared by translation and preview so both always see the same prompt.
+/// Fills in the unit's position from the DB when the caller did not send it.
+/// Fails only when the project script does.
+async fn prepare_prompt(
+    glossary: &ManagedGlossaryState,
+    db: &ManagedTranslationState,
+    text_unit: &mut TextUnit,
+    engine_info: &EngineInfo,
+    manifest_hash: Option<&str>,
+) -> AppResult<PreparedPrompt> {
+    // Only glossary terms that occur in the source text (plus the always-include set)
+    let terms = match crate::db::glossary::repo::find_relevant_terms(
+        glossary,
+        &engine_info.source_language.id,
+        &engine_info.target_language.id,
+        &text_unit.source_text,
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Glossary term selection failed: {}", e);
+            Vec::new()
+        }
+    };
+
+    let settings = ProjectManifest::read_settings(&engine_info.path);
+    if text_unit.position.is_none() {
+        // Units sent without their position may still have one recorded in the DB
+        if let Ok(db_id) = text_unit.id.parse::<i64>() {
+            if let Ok(record) = crate::db::translation::repo::find_unit_by_id(db, db_id).await {
+                text_unit.position = record.position();
+            }
+        }
+    }
+    let mut context = PromptContext::for_text_unit(text_unit);
+    let (previous_lines, next_lines) =
+        load_context_lines(db, text_unit, engine_info, &settings.context_window).await;
+    context.previous_lines = previous_lines;
+    context.next_lines = next_lines;
+    load_style_profiles(db, &mut context, engine_info).await;
+    context.examples = load_reviewed_examples(
+        db,
+        text_unit,
+        engine_info,
+        manifest_hash,
+        &settings.few_shot,
+    )
+    .await;
+
+    // The project script may rewrite the source text the model sees
+    let hooks = ScriptHooks::for_project(&settings.scripts, &engine_info.path)?;
+    let mut prompt_unit = text_unit.clone();
+    prompt_unit.source_text = hooks.before_prompt(text_unit)?;
+
+    // In opaque mode the model only ever sees numbered tokens; the table maps them back.
+    // Examples, context lines and glossary entries are written in the same tokens.
+    let mut prompt_terms = terms.clone();
+    let placeholders = match settings.placeholder_mode {
+        PlaceholderMode::Named => None,
+        PlaceholderMode::Opaque => {
+            let (encoded, table) =
+                EngineTextProcessor::encode_opaque_placeholders(&prompt_unit.source_text);
+            context.opaque_placeholders = !table.is_empty();
+            prompt_unit.source_text = encoded;
+            encode_prompt_sections(&table, &mut context, &mut prompt_terms);
+            Some(table)
+        }
+    };
+
+    let prompt = if prompt_terms.is_empty() {
+        PromptBuilder::build_translation_prompt(&prompt_unit, engine_info, &context).await
+    } else {
+        PromptBuilder::build_translation_prompt_with_terms(
+            &prompt_unit,
+            engine_info,
+            &prompt_terms,
+            &context,
+        )
+        .await
+    };
+    Ok(PreparedPrompt {
+        prompt,
+        terms,
+        placeholders,
+        hooks,
+    })
+}
+
+/// Rewrite the examples, context lines and glossary entries of an opaque-mode
+/// prompt in the unit's tokens.
+fn encode_prompt_sections(
+    table: &PlaceholderTable,
+    context: &mut PromptContext,
+    terms: &mut [GlossaryTerm],
+) {
+    for line in context
+        .previous_lines
+        .iter_mut()
+        .chain(context.next_lines.iter_mut())
+    {
+        line.source_text = table.encode_related(&line.source_text);
+        line.translated_text = line
+            .translated_text
+            .as_deref()
+            .map(|text| table.encode_related(text));
+    }
+    for example in &mut context.examples {
+        example.source_text = table.encode_related(&example.source_text);
+        example.translated_text = table.encode_related(&example.translated_text);
+    }
+    for term in terms {
+        term.input = table.encode_related(&term.input);
+        term.output = table.encode_related(&term.output);
+    }
+}
+
+/// Load the neighbouring lines of a unit's event page as read-only prompt context.
+///
+/// Only dialogue units with a recorded position get context; everything else
+/// returns empty lists.
+async fn load_context_lines(
+    db: &ManagedTranslationState,
+    text_unit: &TextUnit,
+    engine_info: &EngineInfo,
+    settings: &ContextWindowSettings,
+) -> (Vec<ContextLine>, Vec<ContextLine>) {
+    let position = match &text_unit.position {
+        Some(position) if text_unit.prompt_type == PromptType::Dialogue => position,
+        _ => return (Vec::new(), Vec::new()),
+    };
+    if settings.previous_lines == 0 && settings.next_lines == 0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let to_lines = |records: Vec<TextUnitRecord>| -> Vec<ContextLine> {
+        records
+            .into_iter()
+            .map(|r| ContextLine {
+                source_text: r.source_text,
+                translated_text: r.translated_text,
+            })
+            .collect()
+    };
+
+    match crate::db::translation::repo::find_event_neighbors(
+        db,
+        &engine_info.path.to_string_lossy(),
+        &position.event_ref,
+        position.page_index as i64,
+        position.command_index as i64,
+        settings.previous_lines as i64,
+        settings.next_lines as i64,
+    )
+    .await
+    {
+        Ok((previous, next)) => (to_lines(previous), to_lines(next)),
+        Err(e) => {
+            warn!(
+                "Failed to load context lines for unit {}: {}",
+                text_unit.id, e
+            );
+            (Vec::new(), Vec::new())
+        }
+    }
+}
+
+/// Attach the project style guide and, when the speaker is known, their
+/// character profile to the prompt context.
+async fn load_style_profiles(
+    db: &ManagedTranslationState,
+    context: &mut PromptContext,
+    engine_info: &EngineInfo,
+) {
+    let project_path = engine_info.path.to_string_lossy().to_string();
+    match crate::db::profiles::repo::find_style_guide(db, &project_path).await {
+        Ok(guide) if !guide.rules.trim().is_empty() => context.style_guide = Some(guide.rules),
+        Ok(_) => {}
+        Err(e) => warn!("Failed to load style guide: {}", e),
+    }
+
+    if let Some(speaker) = context.speaker.clone() {
+        match crate::db::profiles::repo::find_profile_by_name(db, &project_path, &speaker).await {
+            Ok(profile) => context.character_profile = profile,
+            Err(e) => warn!("Failed to load character profile for {}: {}", speaker, e),
+        }
+    }
+}
+
+/// Pick few-shot examples for a unit from the project's human-reviewed translations
+/// of the same prompt type.
+async fn load_reviewed_examples(
+    db: &ManagedTranslationState,
+    text_unit: &TextUnit,
+    engine_info: &EngineInfo,
+    manifest_hash: Option<&str>,
+    settings: &FewShotSettings,
+) -> Vec<FewShotExample> {
+    if !settings.enabled || settings.max_examples == 0 {
+        return Vec::new();
+    }
+
+    let query = TextUnitQuery {
+        project_path: Some(engine_info.path.to_string_lossy().to_string()),
+        manifest_hash: manifest_hash.map(|s| s.to_string()),
+        status: Some("HumanReviewed".to_string()),
+        prompt_type: Some(TextUnitRecord::prompt_type_to_string(text_unit.prompt_type)),
+        limit: Some(FEW_SHOT_CANDIDATE_LIMIT),
+        ..Default::default()
+    };
+
+    let records = match crate::db::translation::repo::find_units(db, &query).await {
+        Ok(records) => records,
+        Err(e) => {
+            warn!("Failed to load reviewed translations for examples: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let current_id = text_unit.id.parse::<i64>().ok();
+    let candidates = records
+        .into_iter()
+        .filter(|r| current_id.is_none() || r.id != current_id)
+        .filter_map(|r| {
+            r.translated_text.map(|translated_text| FewShotExample {
+                source_text: r.source_text,
+                translated_text,
+            })
+        })
+        .collect();
+
+    let examples = select_examples(&text_unit.source_text, candidates, settings);
+    debug!(
+        "Selected {} few-shot examples for unit {}",
+        examples.len(),
+        text_unit.id
+    );
+    examples
+}
+
+/// Check a translation's placeholders against the source, repairing simple damage.
+///
+/// Returns the (possibly repaired) translation, or the remaining differences.
+fn verify_placeholders(
+    source_text: &str,
+    translated_text: &str,
+) -> Result<String, PlaceholderCheck> {
+    let check = check_placeholders(source_text, translated_text);
+    if check.is_ok() {
+        return Ok(translated_text.to_string());
+    }
+    match repair_placeholders(source_text, translated_text) {
+        Some(repaired) => {
+            debug!("Repaired placeholders: {:?}", check);
+            Ok(repaired)
+        }
+        None => Err(check),
+    }
+}
+
+/// Sum the token usage of two generations for the same unit.
+fn add_token_usage(a: Option<TokenUsage>, b: Option<TokenUsage>) -> Option<TokenUsage> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(TokenUsage {
+            input_tokens: a.input_tokens + b.input_tokens,
+            output_tokens: a.output_tokens + b.output_tokens,
+            total_tokens: a.total_tokens + b.total_tokens,
+        }),
+        (a, b) => a.or(b),
+    }
+}
+
 /// Execute a single prompt with timeout and retry/backoff using the shared service, returning token usage.
 /// Optimized for remote Ollama servers (RunPod, Vast.ai) with enhanced network latency handling.
 async fn translate_with_retry_and_usage(
@@ -1,12 +1,19 @@
+use log::warn;
 use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
-use crate::core::error::AppResult;
-use crate::models::engine::{EngineCriteria, EngineInfo};
+use crate::core::error::{AppError, AppResult};
+use crate::db::translation::manifest::ProjectManifest;
+use crate::models::engine::{EngineCriteria, EngineInfo, GameDataFile};
 use crate::models::language::Language;
-use crate::models::translation::TextUnit;
+use crate::models::translation::{SkippedText, TextUnit};
 use crate::utils::text::engine_processor::EngineTextProcessor;
+use crate::utils::text::hooks::ScriptHooks;
+use crate::utils::text::rules::project_rules;
 use crate::utils::text::types::RawTextUnit;
+use crate::utils::text::typography::Typography;
+use crate::utils::text::validation::ExtractionFilter;
 
 /// Core trait that all game engine implementations must implement.
 /// This defines the contract for interacting with different types of game projects.
@@ -27,16 +34,39 @@ pub trait Engine: Send + Sync {
     /// This method has a default implementation that automatically processes
     /// raw text units through the engine-specific text processing pipeline.
     fn extract_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<TextUnit>> {
+        Ok(self.extract_text_units_with_skipped(project_info)?.0)
+    }
+
+    /// Extract all translatable text units, along with the strings left out
+    ///
+    /// The project script's `on_extract` hook runs first, then the project's
+    /// extraction patterns ahead of the built-in heuristics; each skipped
+    /// string carries the rule that rejected it.
+    fn extract_text_units_with_skipped(
+        &self,
+        project_info: &EngineInfo,
+    ) -> AppResult<(Vec<TextUnit>, Vec<SkippedText>)> {
+        let settings = ProjectManifest::settings_for(project_info);
+        let hooks = ScriptHooks::for_project(&settings.scripts, &project_info.path)?;
+
         // Get raw text units from engine-specific implementation
-        let raw_units = self.extract_raw_text_units(project_info)?;
+        let (raw_units, mut skipped) =
+            hooks.filter_extracted(self.extract_raw_text_units(project_info)?)?;
 
         // Process through engine-specific text processing pipeline
         let target_language = &project_info.target_language.id;
-        Ok(EngineTextProcessor::process_for_extraction(
+        let rules = project_rules(&settings.formatter, &project_info.path)?;
+        let filter = ExtractionFilter::from_settings(&settings.extraction)?;
+        let (text_units, rejected) = EngineTextProcessor::partition_for_extraction(
             raw_units,
+            &project_info.source_language.id,
             target_language,
             &project_info.engine_type,
-        ))
+            &rules,
+            &filter,
+        );
+        skipped.extend(rejected);
+        Ok((text_units, skipped))
     }
 
     /// Inject translated text units back into the project files
@@ -48,8 +78,60 @@ pub trait Engine: Send + Sync {
         project_info: &EngineInfo,
         text_units: &[TextUnit],
     ) -> AppResult<()> {
+        let settings = ProjectManifest::settings_for(project_info);
+        let rules = project_rules(&settings.formatter, &project_info.path)?;
+        let hooks = ScriptHooks::for_project(&settings.scripts, &project_info.path)?;
+        let typography =
+            Typography::for_language(&project_info.target_language.id, &settings.typography);
+
+        // Source texts of the units being injected, for the round-trip check
+        // and the script's `before_inject` hook; both need a fresh extraction
+        let verify_round_trip = settings.formatter.verify_round_trip;
+        let raw_sources: HashMap<String, String> =
+            if verify_round_trip || hooks.runs_before_inject() {
+                let injected_ids: HashSet<&str> =
+                    text_units.iter().map(|unit| unit.id.as_str()).collect();
+                self.extract_raw_text_units(project_info)?
+                    .into_iter()
+                    .filter(|raw_unit| injected_ids.contains(raw_unit.id.as_str()))
+                    .map(|raw_unit| (raw_unit.id, raw_unit.source_text))
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
+        // Refuse units whose source text does not survive the formatter round
+        // trip; injecting them would corrupt the line even without translation
+        let safe_units: Vec<TextUnit> = text_units
+            .iter()
+            .filter(|unit| match raw_sources.get(&unit.id) {
+                Some(source)
+                    if verify_round_trip
+                        && !EngineTextProcessor::round_trips(
+                            source,
+                            &project_info.engine_type,
+                            &rules,
+                        ) =>
+                {
+                    warn!(
+                        "Skipping {}: source text does not round-trip through the formatter",
+                        unit.id
+                    );
+                    false
+                }
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
         // Process through engine-specific text processing pipeline
-        let raw_units = EngineTextProcessor::process_injection_pipeline(text_units, &project_info.engine_type);
+        let mut raw_units = EngineTextProcessor::process_injection_pipeline(
+            &safe_units,
+            &project_info.engine_type,
+            &rules,
+            typography.as_ref(),
+        );
+        hooks.before_inject(&mut raw_units, &raw_sources)?;
 
         // Inject raw text units using engine-specific implementation
         self.inject_raw_text_units(project_info, &raw_units)
@@ -64,6 +146,15 @@ pub trait Engine: Send + Sync {
         translated_text: &str,
     ) -> AppResult<TextUnit>;
 
+    /// Structured view of the game data files, for engines registered with
+    /// the `game_data_files` capability
+    fn extract_game_data_files(&self, project_info: &EngineInfo) -> AppResult<Vec<GameDataFile>> {
+        Err(AppError::Other(format!(
+            "{:?} does not support structured game data file extraction",
+            project_info.engine_type
+        )))
+    }
+
     /// Returns self as Any for downcasting to specific engine implementations
     #[allow(dead_code)]
     fn as_any(&self) -> &dyn Any;
@@ -0,0 +1,185 @@
+//! Relevance-based glossary term selection.
+//!
+//! A `GlossaryMatcher` holds an Aho–Corasick automaton over every enabled term
+//! of one language pair. Scanning a source text then yields exactly the terms
+//! that occur in it, so prompt size follows the text instead of the glossary.
+
+use std::collections::HashSet;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+
+use crate::core::error::{AppError, AppResult};
+use crate::db::glossary::model::GlossaryTerm;
+
+/// Category whose terms are always sent, whether or not they occur in the text.
+pub const ALWAYS_INCLUDE_CATEGORY: &str = "Essential Terms";
+
+/// Upper bound on always-included terms so a large essentials list cannot
+/// crowd out the matched ones.
+pub const ALWAYS_INCLUDE_LIMIT: usize = 20;
+
+/// Automaton over the enabled terms of one language pair.
+pub struct GlossaryMatcher {
+    automaton: Option<AhoCorasick>,
+    /// Terms in pattern order (pattern id == index)
+    terms: Vec<GlossaryTerm>,
+    always_include: Vec<GlossaryTerm>,
+}
+
+impl GlossaryMatcher {
+    /// Build a matcher from the terms of one language pair.
+    ///
+    /// Disabled terms and terms with an empty input are dropped.
+    pub fn build(terms: Vec<GlossaryTerm>) -> AppResult<Self> {
+        let terms: Vec<GlossaryTerm> = terms
+            .into_iter()
+            .filter(|t| t.enabled && !t.input.trim().is_empty())
+            .collect();
+
+        let always_include = terms
+            .iter()
+            .filter(|t| t.category == ALWAYS_INCLUDE_CATEGORY)
+            .take(ALWAYS_INCLUDE_LIMIT)
+            .cloned()
+            .collect();
+
+        let automaton = if terms.is_empty() {
+            None
+        } else {
+            let built = AhoCorasickBuilder::new()
+                .match_kind(MatchKind::Standard)
+                .ascii_case_insensitive(true)
+                .build(terms.iter().map(|t| t.input.trim()))
+                .map_err(|e| AppError::Other(format!("Failed to build glossary matcher: {}", e)))?;
+            Some(built)
+        };
+
+        Ok(Self {
+            automaton,
+            terms,
+            always_include,
+        })
+    }
+
+    /// Number of terms the automaton was built from.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Terms relevant to `text`: the always-include set followed by every term
+    /// that occurs in the text, in order of first occurrence, without duplicates.
+    pub fn select(&self, text: &str) -> Vec<GlossaryTerm> {
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut selected: Vec<GlossaryTerm> = Vec::new();
+
+        for term in &self.always_include {
+            if seen.insert(term.id) {
+                selected.push(term.clone());
+            }
+        }
+
+        let automaton = match &self.automaton {
+            Some(automaton) => automaton,
+            None => return selected,
+        };
+
+        for m in automaton.find_overlapping_iter(text) {
+            let term = &self.terms[m.pattern().as_usize()];
+            if !is_word_match(text, m.start(), m.end(), term.input.trim()) {
+                continue;
+            }
+            if seen.insert(term.id) {
+                selected.push(term.clone());
+            }
+        }
+        selected
+    }
+}
+
+/// Latin-script terms must match whole words ("Ice" should not hit "Nice");
+/// terms in unspaced scripts match anywhere.
+fn is_word_match(text: &str, start: usize, end: usize, input: &str) -> bool {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric();
+    let starts_with_word = input.chars().next().is_some_and(is_word_char);
+    let ends_with_word = input.chars().last().is_some_and(is_word_char);
+
+    if starts_with_word && text[..start].chars().next_back().is_some_and(is_word_char) {
+        return false;
+    }
+    if ends_with_word && text[end..].chars().next().is_some_and(is_word_char) {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(id: i64, category: &str, input: &str, output: &str) -> GlossaryTerm {
+        GlossaryTerm {
+            id,
+            category: category.to_string(),
+            source_lang: "ja".to_string(),
+            target_lang: "en".to_string(),
+            input: input.to_string(),
+            output: output.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_selects_only_occurring_terms() {
+        let matcher = GlossaryMatcher::build(vec![
+            term(1, "Characters", "アレックス", "Alex"),
+            term(2, "Mechanics", "薬草", "Herb"),
+            term(3, "Mechanics", "エリクサー", "Elixir"),
+        ])
+        .unwrap();
+
+        let selected = matcher.select("アレックスは薬草を使った！");
+        let ids: Vec<i64> = selected.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_overlapping_terms_and_always_include() {
+        let matcher = GlossaryMatcher::build(vec![
+            term(1, "Mechanics", "魔法", "Magic"),
+            term(2, "Mechanics", "炎魔法", "Fire Magic"),
+            term(3, ALWAYS_INCLUDE_CATEGORY, "ゴールド", "Gold"),
+        ])
+        .unwrap();
+
+        let ids: Vec<i64> = matcher
+            .select("炎魔法を覚えた")
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![3, 2, 1]);
+        assert_eq!(matcher.select("こんにちは").len(), 1);
+    }
+
+    #[test]
+    fn test_latin_terms_match_whole_words_case_insensitively() {
+        let matcher = GlossaryMatcher::build(vec![term(1, "Mechanics", "Ice", "Hielo")]).unwrap();
+
+        assert_eq!(matcher.select("Cast ICE on the slime").len(), 1);
+        assert!(matcher.select("Nice weather").is_empty());
+    }
+
+    #[test]
+    fn test_ignores_disabled_and_empty_terms() {
+        let mut disabled = term(1, "Mechanics", "薬草", "Herb");
+        disabled.enabled = false;
+        let matcher =
+            GlossaryMatcher::build(vec![disabled, term(2, "Mechanics", " ", "")]).unwrap();
+
+        assert!(matcher.is_empty());
+        assert!(matcher.select("薬草").is_empty());
+    }
+}
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use sqlx::{self, Arguments, Row};
 
 use crate::core::error::AppResult;
+use crate::db::glossary::matcher::GlossaryMatcher;
 use crate::db::glossary::model::GlossaryQuery;
 use crate::db::glossary::model::GlossaryTerm;
 use crate::db::ManagedGlossaryState;
@@ -107,6 +110,7 @@ pub async fn upsert_term(state: &ManagedGlossaryState, term: GlossaryTerm) -> Ap
         .map_err(|e| crate::core::error::AppError::Database(e.to_string()))?;
         res.last_insert_rowid()
     };
+    state.bump_version();
     Ok(id)
 }
 
@@ -117,9 +121,54 @@ pub async fn delete_term(state: &ManagedGlossaryState, id: i64) -> AppResult<()>
         .execute(&pool)
         .await
         .map_err(|e| crate::core::error::AppError::Database(e.to_string()))?;
+    state.bump_version();
     Ok(())
 }
 
+/// Get the term matcher for a language pair, rebuilding it when the glossary
+/// changed since it was cached.
+pub async fn matcher_for(
+    state: &ManagedGlossaryState,
+    source_lang: &str,
+    target_lang: &str,
+) -> AppResult<Arc<GlossaryMatcher>> {
+    let key = format!("{}|{}", source_lang, target_lang);
+    let version = state.version();
+    if let Some((built_at, matcher)) = state.matchers().lock().await.get(&key) {
+        if *built_at == version {
+            return Ok(matcher.clone());
+        }
+    }
+
+    let q = GlossaryQuery {
+        source_lang: source_lang.to_string(),
+        target_lang: target_lang.to_string(),
+        categories: Vec::new(),
+        prompt_types: Vec::new(),
+        project_scope: None,
+        limit: None,
+        only_enabled: true,
+    };
+    let matcher = Arc::new(GlossaryMatcher::build(find_terms(state, &q).await?)?);
+    state
+        .matchers()
+        .lock()
+        .await
+        .insert(key, (version, matcher.clone()));
+    Ok(matcher)
+}
+
+/// Terms of a language pair that occur in `text`, plus the always-include set
+pub async fn find_relevant_terms(
+    state: &ManagedGlossaryState,
+    source_lang: &str,
+    target_lang: &str,
+    text: &str,
+) -> AppResult<Vec<GlossaryTerm>> {
+    let matcher = matcher_for(state, source_lang, target_lang).await?;
+    Ok(matcher.select(text))
+}
+
 /// Export all (or filtered) terms to JSON string
 pub async fn export_terms_json(
     state: &ManagedGlossaryState,
@@ -1,4 +1,5 @@
 pub mod glossary;
+pub mod profiles;
 pub mod state;
 pub mod translation;
 
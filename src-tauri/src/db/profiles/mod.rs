@@ -0,0 +1,5 @@
+pub mod model;
+pub mod repo;
+
+pub use model::*;
+// repo::* functions used directly via qualified paths
@@ -0,0 +1,24 @@
+/// A recurring character whose voice should stay consistent across lines.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CharacterProfile {
+    pub id: i64,
+    pub project_path: String,
+    /// Name as it appears as the speaker in the source text
+    pub name: String,
+    pub gender: Option<String>,
+    pub pronouns: Option<String>,
+    /// Speech register, e.g. "polite, formal keigo" or "rough, curt"
+    pub speech_register: Option<String>,
+    #[serde(default)]
+    pub catchphrases: Vec<String>,
+    /// How honorifics addressed by or to this character are handled
+    pub honorific_policy: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Project-wide style rules included in every prompt.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StyleGuide {
+    pub project_path: String,
+    pub rules: String,
+}
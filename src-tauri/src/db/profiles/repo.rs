@@ -0,0 +1,175 @@
+use sqlx::{self, sqlite::SqliteRow, Row};
+
+use crate::core::error::{AppError, AppResult};
+use crate::db::profiles::model::{CharacterProfile, StyleGuide};
+use crate::db::state::ManagedTranslationState;
+
+const PROFILE_COLUMNS: &str = "id, project_path, name, gender, pronouns, speech_register, \
+     catchphrases, honorific_policy, notes";
+
+fn profile_from_row(row: &SqliteRow) -> CharacterProfile {
+    let catchphrases = row.get::<String, _>("catchphrases");
+    CharacterProfile {
+        id: row.get::<i64, _>("id"),
+        project_path: row.get::<String, _>("project_path"),
+        name: row.get::<String, _>("name"),
+        gender: row.get::<Option<String>, _>("gender"),
+        pronouns: row.get::<Option<String>, _>("pronouns"),
+        speech_register: row.get::<Option<String>, _>("speech_register"),
+        catchphrases: serde_json::from_str(&catchphrases).unwrap_or_default(),
+        honorific_policy: row.get::<Option<String>, _>("honorific_policy"),
+        notes: row.get::<Option<String>, _>("notes"),
+    }
+}
+
+/// List every character profile of a project, by name
+pub async fn find_profiles(
+    state: &ManagedTranslationState,
+    project_path: &str,
+) -> AppResult<Vec<CharacterProfile>> {
+    let pool = state.pool().await;
+    let sql = format!(
+        "SELECT {} FROM character_profiles WHERE project_path = ? ORDER BY name ASC",
+        PROFILE_COLUMNS
+    );
+    let rows = sqlx::query(&sql)
+        .bind(project_path)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(rows.iter().map(profile_from_row).collect())
+}
+
+/// Find the profile of a speaker, matching the name case-insensitively
+pub async fn find_profile_by_name(
+    state: &ManagedTranslationState,
+    project_path: &str,
+    name: &str,
+) -> AppResult<Option<CharacterProfile>> {
+    let pool = state.pool().await;
+    let sql = format!(
+        "SELECT {} FROM character_profiles \
+         WHERE project_path = ? AND name = ? COLLATE NOCASE LIMIT 1",
+        PROFILE_COLUMNS
+    );
+    let row = sqlx::query(&sql)
+        .bind(project_path)
+        .bind(name.trim())
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(row.as_ref().map(profile_from_row))
+}
+
+/// Create or update a profile; a new profile with an existing name replaces it
+pub async fn upsert_profile(
+    state: &ManagedTranslationState,
+    profile: &CharacterProfile,
+) -> AppResult<i64> {
+    let pool = state.pool().await;
+    let catchphrases =
+        serde_json::to_string(&profile.catchphrases).map_err(|e| AppError::Other(e.to_string()))?;
+    let id = if profile.id > 0 {
+        sqlx::query(
+            r#"UPDATE character_profiles
+               SET name = ?, gender = ?, pronouns = ?, speech_register = ?, catchphrases = ?,
+                   honorific_policy = ?, notes = ?, updated_at = CURRENT_TIMESTAMP
+               WHERE id = ?"#,
+        )
+        .bind(profile.name.trim())
+        .bind(&profile.gender)
+        .bind(&profile.pronouns)
+        .bind(&profile.speech_register)
+        .bind(&catchphrases)
+        .bind(&profile.honorific_policy)
+        .bind(&profile.notes)
+        .bind(profile.id)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        profile.id
+    } else {
+        sqlx::query(
+            r#"INSERT INTO character_profiles
+                 (project_path, name, gender, pronouns, speech_register, catchphrases,
+                  honorific_policy, notes)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+               ON CONFLICT(project_path, name)
+               DO UPDATE SET
+                 gender = excluded.gender,
+                 pronouns = excluded.pronouns,
+                 speech_register = excluded.speech_register,
+                 catchphrases = excluded.catchphrases,
+                 honorific_policy = excluded.honorific_policy,
+                 notes = excluded.notes,
+                 updated_at = CURRENT_TIMESTAMP"#,
+        )
+        .bind(&profile.project_path)
+        .bind(profile.name.trim())
+        .bind(&profile.gender)
+        .bind(&profile.pronouns)
+        .bind(&profile.speech_register)
+        .bind(&catchphrases)
+        .bind(&profile.honorific_policy)
+        .bind(&profile.notes)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // last_insert_rowid is not reliable for the update branch of an upsert
+        sqlx::query("SELECT id FROM character_profiles WHERE project_path = ? AND name = ?")
+            .bind(&profile.project_path)
+            .bind(profile.name.trim())
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .get::<i64, _>("id")
+    };
+    Ok(id)
+}
+
+pub async fn delete_profile(state: &ManagedTranslationState, id: i64) -> AppResult<()> {
+    let pool = state.pool().await;
+    sqlx::query("DELETE FROM character_profiles WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Get the style guide of a project (empty rules when none was saved)
+pub async fn find_style_guide(
+    state: &ManagedTranslationState,
+    project_path: &str,
+) -> AppResult<StyleGuide> {
+    let pool = state.pool().await;
+    let row = sqlx::query("SELECT rules FROM style_guides WHERE project_path = ?")
+        .bind(project_path)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(StyleGuide {
+        project_path: project_path.to_string(),
+        rules: row.map(|r| r.get::<String, _>("rules")).unwrap_or_default(),
+    })
+}
+
+pub async fn save_style_guide(
+    state: &ManagedTranslationState,
+    guide: &StyleGuide,
+) -> AppResult<()> {
+    let pool = state.pool().await;
+    sqlx::query(
+        r#"INSERT INTO style_guides (project_path, rules)
+           VALUES (?, ?)
+           ON CONFLICT(project_path)
+           DO UPDATE SET rules = excluded.rules, updated_at = CURRENT_TIMESTAMP"#,
+    )
+    .bind(&guide.project_path)
+    .bind(&guide.rules)
+    .execute(&pool)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use tokio::sync::Mutex;
 
 use crate::core::error::{AppError, AppResult};
 
+use crate::db::glossary::matcher::GlossaryMatcher;
 use crate::db::glossary::model::GlossaryTerm;
 /// Shared database state for all database operations
 /// This replaces the individual state structs and provides a unified database connection
@@ -86,6 +88,10 @@ pub struct ManagedGlossaryState {
     db_path: PathBuf,
     pool: Mutex<Option<sqlx::SqlitePool>>,
     cache: Mutex<HashMap<String, std::sync::Arc<Vec<GlossaryTerm>>>>,
+    /// Bumped on every glossary write so cached matchers can tell they are stale
+    version: AtomicU64,
+    /// Term matchers keyed by language pair, tagged with the version they were built at
+    matchers: Mutex<HashMap<String, (u64, std::sync::Arc<GlossaryMatcher>)>>,
 }
 
 impl ManagedGlossaryState {
@@ -94,6 +100,8 @@ impl ManagedGlossaryState {
             db_path,
             pool: Mutex::new(None),
             cache: Mutex::new(HashMap::new()),
+            version: AtomicU64::new(0),
+            matchers: Mutex::new(HashMap::new()),
         }
     }
 
@@ -144,6 +152,20 @@ impl ManagedGlossaryState {
     pub fn cache(&self) -> &Mutex<HashMap<String, std::sync::Arc<Vec<GlossaryTerm>>>> {
         &self.cache
     }
+
+    /// Current glossary version
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Mark every cached matcher as stale after a glossary write
+    pub fn bump_version(&self) {
+        self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn matchers(&self) -> &Mutex<HashMap<String, (u64, std::sync::Arc<GlossaryMatcher>)>> {
+        &self.matchers
+    }
 }
 
 // Note: Old TranslationState wrapper removed - use ManagedTranslationState instead
@@ -1,5 +1,7 @@
 use crate::core::error::{AppError, AppResult};
-use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
+use crate::engines::registry;
+use crate::models::engine::{EngineCriteria, EngineInfo};
+use crate::models::settings::ProjectSettings;
 use log::info;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -32,6 +34,9 @@ pub struct ProjectManifest {
     pub total_text_units: Option<i64>,
     /// Number of text units already translated
     pub translated_text_units: Option<i64>,
+    /// Per-project translation settings
+    #[serde(default)]
+    pub settings: ProjectSettings,
 }
 
 /// Serializable version of EngineCriteria
@@ -59,15 +64,7 @@ impl ProjectManifest {
     pub fn generate_project_id(project_path: &Path, engine_info: &EngineInfo) -> String {
         let mut hasher = Sha256::new();
         hasher.update(project_path.to_string_lossy().as_bytes());
-        hasher.update(
-            match engine_info.engine_type {
-                EngineType::RpgMakerMv => "RpgMakerMv",
-                EngineType::RpgMakerMz => "RpgMakerMz",
-                EngineType::WolfRpg => "WolfRpg",
-                EngineType::Unknown => "Unknown",
-            }
-            .as_bytes(),
-        );
+        hasher.update(registry::engine_id(&engine_info.engine_type).as_bytes());
         if let Some(version) = &engine_info.version {
             hasher.update(version.as_bytes());
         }
@@ -84,12 +81,7 @@ impl ProjectManifest {
             schema_version: 1,
             project_id,
             project_path,
-            engine_type: match engine_info.engine_type {
-                EngineType::RpgMakerMv => "RpgMakerMv".to_string(),
-                EngineType::RpgMakerMz => "RpgMakerMz".to_string(),
-                EngineType::WolfRpg => "WolfRpg".to_string(),
-                EngineType::Unknown => "Unknown".to_string(),
-            },
+            engine_type: registry::engine_id(&engine_info.engine_type).to_string(),
             engine_version: engine_info.version.clone(),
             source_language: engine_info.source_language.id.clone(),
             target_language: engine_info.target_language.id.clone(),
@@ -98,6 +90,7 @@ impl ProjectManifest {
             last_accessed: now,
             total_text_units: None, // Will be updated when text units are extracted
             translated_text_units: None, // Will be updated when translations are saved
+            settings: ProjectSettings::default(),
         }
     }
 
@@ -175,16 +168,31 @@ impl ProjectManifest {
         Ok(Some(manifest))
     }
 
+    /// Read the project settings without touching the manifest on disk.
+    ///
+    /// Falls back to defaults when the manifest is missing or unreadable.
+    pub fn read_settings(project_path: &Path) -> ProjectSettings {
+        let manifest_path = Self::get_manifest_path(project_path);
+        std::fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .map(|manifest| manifest.settings)
+            .unwrap_or_default()
+    }
+
+    /// Settings for an engine info: the ones it carries (export copies) or the
+    /// project manifest's.
+    pub fn settings_for(engine_info: &EngineInfo) -> ProjectSettings {
+        engine_info
+            .settings
+            .clone()
+            .unwrap_or_else(|| Self::read_settings(&engine_info.path))
+    }
+
     /// Check if manifest matches current engine info
     pub fn matches_engine_info(&self, engine_info: &EngineInfo) -> bool {
         self.project_path == engine_info.path.to_string_lossy()
-            && self.engine_type
-                == match engine_info.engine_type {
-                    EngineType::RpgMakerMv => "RpgMakerMv",
-                    EngineType::RpgMakerMz => "RpgMakerMz",
-                    EngineType::WolfRpg => "WolfRpg",
-                    EngineType::Unknown => "Unknown",
-                }
+            && self.engine_type == registry::engine_id(&engine_info.engine_type)
             && self.source_language == engine_info.source_language.id
             && self.target_language == engine_info.target_language.id
     }
@@ -207,6 +215,7 @@ pub fn create_or_load_project_manifest(engine_info: &EngineInfo) -> AppResult<Pr
                 // Preserve existing translation statistics
                 new_manifest.total_text_units = manifest.total_text_units;
                 new_manifest.translated_text_units = manifest.translated_text_units;
+                new_manifest.settings = manifest.settings;
                 new_manifest.save_to_project(&engine_info.path)?;
                 Ok(new_manifest)
             }
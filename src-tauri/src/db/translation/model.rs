@@ -1,4 +1,7 @@
-use crate::models::translation::{PromptType, TextUnit, TranslationStatus};
+use crate::models::translation::{
+    DetectedLanguage, PromptType, SkipReason, SkippedText, TextUnit, TranslationStatus,
+    UnitPosition,
+};
 use serde::{Deserialize, Serialize};
 
 /// Database representation of a text unit for translation
@@ -16,6 +19,18 @@ pub struct TextUnitRecord {
     pub source_lang: String,
     pub target_lang: String,
     pub manifest_hash: Option<String>, // Links to .ludolingua.json
+    #[serde(default)]
+    pub event_ref: Option<String>, // Event the unit belongs to (event-based text only)
+    #[serde(default)]
+    pub page_index: Option<i64>,
+    #[serde(default)]
+    pub command_index: Option<i64>,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub detected_language: Option<String>, // Language detected for the source text
+    #[serde(default)]
+    pub language_confidence: Option<f64>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
@@ -44,11 +59,46 @@ impl TextUnitRecord {
             source_lang: "ja".to_string(), // TODO: Get from engine info
             target_lang: "en".to_string(), // TODO: Get from engine info
             manifest_hash: manifest_hash.map(|s| s.to_string()),
+            event_ref: text_unit.position.as_ref().map(|p| p.event_ref.clone()),
+            page_index: text_unit.position.as_ref().map(|p| p.page_index as i64),
+            command_index: text_unit.position.as_ref().map(|p| p.command_index as i64),
+            speaker: text_unit.position.as_ref().and_then(|p| p.speaker.clone()),
+            detected_language: text_unit
+                .detected_language
+                .as_ref()
+                .map(|d| d.language.clone()),
+            language_confidence: text_unit
+                .detected_language
+                .as_ref()
+                .map(|d| d.confidence as f64),
             created_at: None,
             updated_at: None,
         }
     }
 
+    /// Event position of this unit, when all position columns are set
+    pub fn position(&self) -> Option<UnitPosition> {
+        match (&self.event_ref, self.page_index, self.command_index) {
+            (Some(event_ref), Some(page_index), Some(command_index)) => Some(UnitPosition {
+                event_ref: event_ref.clone(),
+                page_index: page_index as u32,
+                command_index: command_index as u32,
+                speaker: self.speaker.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Detected source language of this unit, when recorded
+    pub fn detected_language(&self) -> Option<DetectedLanguage> {
+        self.detected_language
+            .as_ref()
+            .map(|language| DetectedLanguage {
+                language: language.clone(),
+                confidence: self.language_confidence.unwrap_or_default() as f32,
+            })
+    }
+
     /// Convert TextUnitRecord back to TextUnit for frontend use
     pub fn to_text_unit(&self) -> TextUnit {
         TextUnit {
@@ -63,6 +113,8 @@ impl TextUnitRecord {
             field_type: self.field_type.clone(),
             status: Self::string_to_status(&self.status),
             prompt_type: Self::string_to_prompt_type(&self.prompt_type),
+            position: self.position(),
+            detected_language: self.detected_language(),
         }
     }
 
@@ -96,7 +148,7 @@ impl TextUnitRecord {
         }
     }
 
-    fn prompt_type_to_string(prompt_type: PromptType) -> String {
+    pub fn prompt_type_to_string(prompt_type: PromptType) -> String {
         match prompt_type {
             PromptType::Character => "Character".to_string(),
             PromptType::State => "State".to_string(),
@@ -123,6 +175,53 @@ impl TextUnitRecord {
     }
 }
 
+/// Database representation of a string left out of extraction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedTextRecord {
+    pub project_path: String,
+    pub file_path: String, // Game file the text comes from, as named in its field type
+    pub field_type: String,
+    pub source_text: String,
+    pub reason: SkipReason,
+}
+
+impl SkippedTextRecord {
+    /// Create a record for a skipped string of the project
+    pub fn from_skipped_text(skipped: &SkippedText, project_path: &str) -> Self {
+        Self {
+            project_path: project_path.to_string(),
+            file_path: Self::file_of_field_type(&skipped.field_type),
+            field_type: skipped.field_type.clone(),
+            source_text: skipped.source_text.clone(),
+            reason: skipped.reason,
+        }
+    }
+
+    /// Game file named by a field type: the segment after the field name
+    /// (`message:www/data/Map001.json:5`) or a parenthesized file name
+    /// (`Database value (DataBase.json)`)
+    fn file_of_field_type(field_type: &str) -> String {
+        if let (Some(open), Some(close)) = (field_type.rfind('('), field_type.rfind(')')) {
+            if open < close {
+                return field_type[open + 1..close].trim().to_string();
+            }
+        }
+        field_type
+            .split(':')
+            .nth(1)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+/// Skipped strings of one game file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub file_path: String,
+    pub texts: Vec<SkippedTextRecord>,
+}
+
 /// Query parameters for finding text units
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TextUnitQuery {
@@ -130,6 +229,7 @@ pub struct TextUnitQuery {
     pub file_path: Option<String>,
     pub status: Option<String>,
     pub manifest_hash: Option<String>,
+    pub prompt_type: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -1,7 +1,39 @@
-use super::model::{BulkOperationResult, TextUnitQuery, TextUnitRecord};
+use super::model::{BulkOperationResult, SkippedTextRecord, TextUnitQuery, TextUnitRecord};
 use crate::core::error::{AppError, AppResult};
 use crate::db::state::ManagedTranslationState;
-use sqlx::{self, Arguments, Row};
+use crate::models::translation::SkipReason;
+use sqlx::{self, sqlite::SqliteRow, Arguments, Row};
+
+/// Columns selected for every `TextUnitRecord` query, in `record_from_row` order
+const TEXT_UNIT_COLUMNS: &str = "id, project_path, file_path, field_type, source_text, \
+     translated_text, status, prompt_type, source_lang, target_lang, manifest_hash, \
+     event_ref, page_index, command_index, speaker, detected_language, language_confidence, \
+     created_at, updated_at";
+
+/// Map a row selected with `TEXT_UNIT_COLUMNS` to a record
+fn record_from_row(row: &SqliteRow) -> TextUnitRecord {
+    TextUnitRecord {
+        id: Some(row.get::<i64, _>("id")),
+        project_path: row.get::<String, _>("project_path"),
+        file_path: row.get::<String, _>("file_path"),
+        field_type: row.get::<String, _>("field_type"),
+        source_text: row.get::<String, _>("source_text"),
+        translated_text: row.get::<Option<String>, _>("translated_text"),
+        status: row.get::<String, _>("status"),
+        prompt_type: row.get::<String, _>("prompt_type"),
+        source_lang: row.get::<String, _>("source_lang"),
+        target_lang: row.get::<String, _>("target_lang"),
+        manifest_hash: row.get::<Option<String>, _>("manifest_hash"),
+        event_ref: row.get::<Option<String>, _>("event_ref"),
+        page_index: row.get::<Option<i64>, _>("page_index"),
+        command_index: row.get::<Option<i64>, _>("command_index"),
+        speaker: row.get::<Option<String>, _>("speaker"),
+        detected_language: row.get::<Option<String>, _>("detected_language"),
+        language_confidence: row.get::<Option<f64>, _>("language_confidence"),
+        created_at: row.get::<Option<String>, _>("created_at"),
+        updated_at: row.get::<Option<String>, _>("updated_at"),
+    }
+}
 
 /// Find a single text unit by its database ID
 pub async fn find_unit_by_id(
@@ -10,32 +42,14 @@ pub async fn find_unit_by_id(
 ) -> AppResult<TextUnitRecord> {
     let pool = state.pool().await;
 
-    let row = sqlx::query(
-        r#"SELECT id, project_path, file_path, field_type, source_text, translated_text,
-                  status, prompt_type, source_lang, target_lang, manifest_hash,
-                  created_at, updated_at
-           FROM text_units WHERE id = ?"#,
-    )
-    .bind(id)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let sql = format!("SELECT {} FROM text_units WHERE id = ?", TEXT_UNIT_COLUMNS);
+    let row = sqlx::query(&sql)
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
 
-    Ok(TextUnitRecord {
-        id: Some(row.get("id")),
-        project_path: row.get("project_path"),
-        file_path: row.get("file_path"),
-        field_type: row.get("field_type"),
-        source_text: row.get("source_text"),
-        translated_text: row.get("translated_text"),
-        status: row.get("status"),
-        prompt_type: row.get("prompt_type"),
-        source_lang: row.get("source_lang"),
-        target_lang: row.get("target_lang"),
-        manifest_hash: row.get("manifest_hash"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-    })
+    Ok(record_from_row(&row))
 }
 
 /// Find text units matching the query criteria
@@ -45,12 +59,7 @@ pub async fn find_units(
 ) -> AppResult<Vec<TextUnitRecord>> {
     let pool = state.pool().await;
 
-    let mut sql = String::from(
-        r#"SELECT id, project_path, file_path, field_type, source_text, translated_text,
-                  status, prompt_type, source_lang, target_lang, manifest_hash,
-                  created_at, updated_at
-           FROM text_units WHERE 1=1"#,
-    );
+    let mut sql = format!("SELECT {} FROM text_units WHERE 1=1", TEXT_UNIT_COLUMNS);
     let mut args = sqlx::sqlite::SqliteArguments::default();
 
     if let Some(project_path) = &query.project_path {
@@ -77,6 +86,12 @@ pub async fn find_units(
             .map_err(|e| AppError::Database(e.to_string()))?;
     }
 
+    if let Some(prompt_type) = &query.prompt_type {
+        sql.push_str(" AND prompt_type = ?");
+        args.add(prompt_type)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
     sql.push_str(" ORDER BY updated_at DESC");
 
     if let Some(limit) = query.limit {
@@ -92,28 +107,68 @@ pub async fn find_units(
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-    let units = rows
-        .into_iter()
-        .map(|row| TextUnitRecord {
-            id: Some(row.get::<i64, _>("id")),
-            project_path: row.get::<String, _>("project_path"),
-            file_path: row.get::<String, _>("file_path"),
-            field_type: row.get::<String, _>("field_type"),
-            source_text: row.get::<String, _>("source_text"),
-            translated_text: row.get::<Option<String>, _>("translated_text"),
-            status: row.get::<String, _>("status"),
-            prompt_type: row.get::<String, _>("prompt_type"),
-            source_lang: row.get::<String, _>("source_lang"),
-            target_lang: row.get::<String, _>("target_lang"),
-            manifest_hash: row.get::<Option<String>, _>("manifest_hash"),
-            created_at: row.get::<Option<String>, _>("created_at"),
-            updated_at: row.get::<Option<String>, _>("updated_at"),
-        })
-        .collect();
+    let units = rows.iter().map(record_from_row).collect();
 
     Ok(units)
 }
 
+/// Find the units around a position in the same event page.
+///
+/// Returns `(previous, next)`: up to `before` units with a lower command index
+/// (oldest first) and up to `after` units with a higher one.
+pub async fn find_event_neighbors(
+    state: &ManagedTranslationState,
+    project_path: &str,
+    event_ref: &str,
+    page_index: i64,
+    command_index: i64,
+    before: i64,
+    after: i64,
+) -> AppResult<(Vec<TextUnitRecord>, Vec<TextUnitRecord>)> {
+    let pool = state.pool().await;
+
+    let previous_sql = format!(
+        r#"SELECT {} FROM text_units
+           WHERE project_path = ? AND event_ref = ? AND page_index = ? AND command_index < ?
+           ORDER BY command_index DESC LIMIT ?"#,
+        TEXT_UNIT_COLUMNS
+    );
+    let mut previous: Vec<TextUnitRecord> = sqlx::query(&previous_sql)
+        .bind(project_path)
+        .bind(event_ref)
+        .bind(page_index)
+        .bind(command_index)
+        .bind(before)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .iter()
+        .map(record_from_row)
+        .collect();
+    previous.reverse();
+
+    let next_sql = format!(
+        r#"SELECT {} FROM text_units
+           WHERE project_path = ? AND event_ref = ? AND page_index = ? AND command_index > ?
+           ORDER BY command_index ASC LIMIT ?"#,
+        TEXT_UNIT_COLUMNS
+    );
+    let next = sqlx::query(&next_sql)
+        .bind(project_path)
+        .bind(event_ref)
+        .bind(page_index)
+        .bind(command_index)
+        .bind(after)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .iter()
+        .map(record_from_row)
+        .collect();
+
+    Ok((previous, next))
+}
+
 /// Save a single text unit (insert or update)
 pub async fn upsert_unit(state: &ManagedTranslationState, unit: &TextUnitRecord) -> AppResult<i64> {
     let pool = state.pool().await;
@@ -122,12 +177,23 @@ pub async fn upsert_unit(state: &ManagedTranslationState, unit: &TextUnitRecord)
         // Update existing record
         sqlx::query(
             r#"UPDATE text_units
-               SET translated_text = ?, status = ?, prompt_type = ?, updated_at = CURRENT_TIMESTAMP
+               SET translated_text = ?, status = ?, prompt_type = ?,
+                   event_ref = COALESCE(?, event_ref), page_index = COALESCE(?, page_index),
+                   command_index = COALESCE(?, command_index), speaker = COALESCE(?, speaker),
+                   detected_language = COALESCE(?, detected_language),
+                   language_confidence = COALESCE(?, language_confidence),
+                   updated_at = CURRENT_TIMESTAMP
                WHERE id = ?"#,
         )
         .bind(&unit.translated_text)
         .bind(&unit.status)
         .bind(&unit.prompt_type)
+        .bind(&unit.event_ref)
+        .bind(unit.page_index)
+        .bind(unit.command_index)
+        .bind(&unit.speaker)
+        .bind(&unit.detected_language)
+        .bind(unit.language_confidence)
         .bind(id)
         .execute(&pool)
         .await
@@ -140,8 +206,10 @@ pub async fn upsert_unit(state: &ManagedTranslationState, unit: &TextUnitRecord)
         let result = sqlx::query(
             r#"INSERT INTO text_units
                (project_path, file_path, field_type, source_text, translated_text,
-                status, prompt_type, source_lang, target_lang, manifest_hash)
-               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                status, prompt_type, source_lang, target_lang, manifest_hash,
+                event_ref, page_index, command_index, speaker,
+                detected_language, language_confidence)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(&unit.project_path)
         .bind(&unit.file_path)
@@ -153,6 +221,12 @@ pub async fn upsert_unit(state: &ManagedTranslationState, unit: &TextUnitRecord)
         .bind(&unit.source_lang)
         .bind(&unit.target_lang)
         .bind(&unit.manifest_hash)
+        .bind(&unit.event_ref)
+        .bind(unit.page_index)
+        .bind(unit.command_index)
+        .bind(&unit.speaker)
+        .bind(&unit.detected_language)
+        .bind(unit.language_confidence)
         .execute(&pool)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -218,12 +292,23 @@ async fn upsert_unit_in_transaction(
         // Update existing record
         sqlx::query(
             r#"UPDATE text_units
-               SET translated_text = ?, status = ?, prompt_type = ?, updated_at = CURRENT_TIMESTAMP
+               SET translated_text = ?, status = ?, prompt_type = ?,
+                   event_ref = COALESCE(?, event_ref), page_index = COALESCE(?, page_index),
+                   command_index = COALESCE(?, command_index), speaker = COALESCE(?, speaker),
+                   detected_language = COALESCE(?, detected_language),
+                   language_confidence = COALESCE(?, language_confidence),
+                   updated_at = CURRENT_TIMESTAMP
                WHERE id = ?"#,
         )
         .bind(&unit.translated_text)
         .bind(&unit.status)
         .bind(&unit.prompt_type)
+        .bind(&unit.event_ref)
+        .bind(unit.page_index)
+        .bind(unit.command_index)
+        .bind(&unit.speaker)
+        .bind(&unit.detected_language)
+        .bind(unit.language_confidence)
         .bind(id)
         .execute(&mut **tx)
         .await
@@ -236,8 +321,10 @@ async fn upsert_unit_in_transaction(
         let result = sqlx::query(
             r#"INSERT INTO text_units
                (project_path, file_path, field_type, source_text, translated_text,
-                status, prompt_type, source_lang, target_lang, manifest_hash)
-               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                status, prompt_type, source_lang, target_lang, manifest_hash,
+                event_ref, page_index, command_index, speaker,
+                detected_language, language_confidence)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(&unit.project_path)
         .bind(&unit.file_path)
@@ -249,6 +336,12 @@ async fn upsert_unit_in_transaction(
         .bind(&unit.source_lang)
         .bind(&unit.target_lang)
         .bind(&unit.manifest_hash)
+        .bind(&unit.event_ref)
+        .bind(unit.page_index)
+        .bind(unit.command_index)
+        .bind(&unit.speaker)
+        .bind(&unit.detected_language)
+        .bind(unit.language_confidence)
         .execute(&mut **tx)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -363,40 +456,20 @@ pub async fn find_translated_units_for_export(
 ) -> AppResult<Vec<TextUnitRecord>> {
     let pool = state.pool().await;
 
-    let rows = sqlx::query(
-        r#"SELECT id, project_path, file_path, field_type, source_text, translated_text,
-                  status, prompt_type, source_lang, target_lang, manifest_hash,
-                  created_at, updated_at
-           FROM text_units
+    let sql = format!(
+        r#"SELECT {} FROM text_units
            WHERE manifest_hash = ?
            AND (status = 'MachineTranslated' OR status = 'HumanReviewed' OR status = 'Ignored')
            ORDER BY file_path, field_type"#,
-    )
-    .bind(manifest_hash)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let mut records = Vec::new();
-    for row in rows {
-        records.push(TextUnitRecord {
-            id: Some(row.get("id")),
-            project_path: row.get("project_path"),
-            file_path: row.get("file_path"),
-            field_type: row.get("field_type"),
-            source_text: row.get("source_text"),
-            translated_text: row.get("translated_text"),
-            status: row.get("status"),
-            prompt_type: row.get("prompt_type"),
-            source_lang: row.get("source_lang"),
-            target_lang: row.get("target_lang"),
-            manifest_hash: row.get("manifest_hash"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        });
-    }
+        TEXT_UNIT_COLUMNS
+    );
+    let rows = sqlx::query(&sql)
+        .bind(manifest_hash)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
 
-    Ok(records)
+    Ok(rows.iter().map(record_from_row).collect())
 }
 
 /// Bulk delete text units by their IDs
@@ -475,5 +548,81 @@ pub async fn delete_all_translations_for_project(
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+    sqlx::query("DELETE FROM skipped_texts WHERE manifest_hash = ?")
+        .bind(project_hash)
+        .execute(&pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
     Ok(result.rows_affected() as i64)
 }
+
+/// Replace the skipped strings recorded for a project with those of the latest extraction
+pub async fn replace_skipped_texts(
+    state: &ManagedTranslationState,
+    project_path: &str,
+    manifest_hash: Option<&str>,
+    records: &[SkippedTextRecord],
+) -> AppResult<()> {
+    let pool = state.pool().await;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    sqlx::query("DELETE FROM skipped_texts WHERE project_path = ?")
+        .bind(project_path)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    for record in records {
+        sqlx::query(
+            r#"INSERT INTO skipped_texts
+               (project_path, manifest_hash, file_path, field_type, source_text, rule)
+               VALUES (?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(project_path)
+        .bind(manifest_hash)
+        .bind(&record.file_path)
+        .bind(&record.field_type)
+        .bind(&record.source_text)
+        .bind(record.reason.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Skipped strings of a project, ordered by file and extraction order
+pub async fn find_skipped_texts(
+    state: &ManagedTranslationState,
+    project_path: &str,
+) -> AppResult<Vec<SkippedTextRecord>> {
+    let pool = state.pool().await;
+    let rows = sqlx::query(
+        r#"SELECT project_path, file_path, field_type, source_text, rule
+           FROM skipped_texts
+           WHERE project_path = ?
+           ORDER BY file_path, id"#,
+    )
+    .bind(project_path)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SkippedTextRecord {
+            project_path: row.get::<String, _>("project_path"),
+            file_path: row.get::<String, _>("file_path"),
+            field_type: row.get::<String, _>("field_type"),
+            source_text: row.get::<String, _>("source_text"),
+            reason: SkipReason::from_name(&row.get::<String, _>("rule")),
+        })
+        .collect())
+}
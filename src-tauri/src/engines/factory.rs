@@ -2,10 +2,9 @@ use std::path::Path;
 
 use crate::core::engine::Engine;
 use crate::core::error::{AppError, AppResult};
-use crate::engines::rpg_maker_mv::engine::RpgMakerMvEngine;
-use crate::engines::rpg_maker_mz::engine::RpgMakerMzEngine;
-use crate::engines::wolf_rpg::engine::WolfRpgEngine;
-use crate::models::engine::{EngineCriteria, EngineType};
+use crate::engines::registry;
+use crate::models::engine::EngineType;
+use crate::utils::text::hooks::ScriptHooks;
 
 /// Factory function to get the appropriate engine implementation based on project path.
 ///
@@ -26,6 +25,7 @@ use crate::models::engine::{EngineCriteria, EngineType};
 /// * The project path doesn't exist
 /// * The project path is not a directory
 /// * The project type cannot be determined
+/// * Several engine types match the project equally well
 pub fn get_engine(project_path: &Path) -> AppResult<Box<dyn Engine>> {
     // Check if the path exists and is a directory
     if !project_path.exists() {
@@ -73,13 +73,12 @@ pub fn get_engine_from_type(engine_type: EngineType) -> AppResult<Box<dyn Engine
 /// Private helper function to create an engine from a known engine type.
 /// This contains the common logic shared between get_engine and get_engine_from_type.
 fn create_engine_from_type(engine_type: EngineType) -> AppResult<Box<dyn Engine>> {
-    match engine_type {
-        EngineType::RpgMakerMv => Ok(Box::new(RpgMakerMvEngine::new()) as Box<dyn Engine>),
-        EngineType::RpgMakerMz => Ok(Box::new(RpgMakerMzEngine::new()) as Box<dyn Engine>),
-        EngineType::WolfRpg => Ok(Box::new(WolfRpgEngine::new()) as Box<dyn Engine>),
-        EngineType::Unknown => Err(AppError::Other(
-            "Unknown engine type - cannot create engine".to_string(),
-        )),
+    match registry::registration(&engine_type) {
+        Some(registration) => Ok((registration.create)()),
+        None => Err(AppError::Other(format!(
+            "{:?} engine type - cannot create engine",
+            engine_type
+        ))),
     }
 }
 
@@ -88,22 +87,16 @@ fn create_engine_from_type(engine_type: EngineType) -> AppResult<Box<dyn Engine>
 pub fn extract_game_data_files(
     project_info: &crate::models::engine::EngineInfo,
 ) -> AppResult<Vec<crate::models::engine::GameDataFile>> {
-    match project_info.engine_type {
-        EngineType::RpgMakerMv => {
-            let engine = RpgMakerMvEngine::new();
-            engine.extract_game_data_files(project_info)
-        }
-        EngineType::RpgMakerMz => {
-            let engine = RpgMakerMzEngine::new();
-            engine.extract_game_data_files(project_info)
-        }
-        EngineType::WolfRpg => Err(AppError::Other(
-            "Wolf RPG does not support structured game data file extraction".to_string(),
-        )),
-        EngineType::Unknown => Err(AppError::Other(
-            "Unknown engine type - cannot extract game data files".to_string(),
-        )),
+    let registration = registry::registration(&project_info.engine_type).ok_or_else(|| {
+        AppError::Other("Unknown engine type - cannot extract game data files".to_string())
+    })?;
+    if !registration.capabilities.game_data_files {
+        return Err(AppError::Other(format!(
+            "{} does not support structured game data file extraction",
+            registration.id
+        )));
     }
+    (registration.create)().extract_game_data_files(project_info)
 }
 
 /// Export translated text units using engine-specific logic.
@@ -141,7 +134,16 @@ pub async fn export_translated_subset(
     );
 
     // Get engine for this project type
-    let engine = create_engine_from_type(project_info.engine_type.clone())?;
+    let registration = registry::registration(&project_info.engine_type).ok_or_else(|| {
+        AppError::Other("Unknown engine type - cannot export translations".to_string())
+    })?;
+    if !registration.capabilities.minimal_export {
+        return Err(AppError::Other(format!(
+            "{} does not support exporting translated files",
+            registration.id
+        )));
+    }
+    let engine = (registration.create)();
 
     // Convert database records to TextUnit for engine injection
     let text_units: Vec<crate::models::translation::TextUnit> = translated_records
@@ -219,6 +221,13 @@ pub async fn export_translated_subset(
 
     // Copy export data roots (the actual game data files to be translated)
     for data_root in &criteria.export_data_roots {
+        if data_root.contains('*') {
+            copy_matching_files(&project_info.path, Path::new(destination_root), data_root)
+                .map_err(|e| {
+                    AppError::FileSystem(format!("Failed to copy {}: {}", data_root, e))
+                })?;
+            continue;
+        }
         let src_path = project_info.path.join(data_root);
         let dest_path = std::path::Path::new(destination_root).join(data_root);
 
@@ -232,9 +241,79 @@ pub async fn export_translated_subset(
         }
     }
 
+    // The project script runs again at injection, from the copy; it is
+    // checked against the source project first so it cannot come from elsewhere
+    if let Some(script_file) = &manifest.settings.scripts.file {
+        ScriptHooks::for_project(&manifest.settings.scripts, &project_info.path)?;
+        let dest_path = Path::new(destination_root).join(script_file);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::FileSystem(format!("Failed to create parent directory: {}", e))
+            })?;
+        }
+        std::fs::copy(project_info.path.join(script_file), &dest_path)
+            .map_err(|e| AppError::FileSystem(format!("Failed to copy {}: {}", script_file, e)))?;
+    }
+
     // Create new EngineInfo for the destination path
     let mut dest_engine_info = project_info.clone();
     dest_engine_info.path = std::path::Path::new(destination_root).to_path_buf();
+    // The copy has no manifest of its own; keep the source project's settings,
+    // with the rules file still read from the source project
+    let mut dest_settings = manifest.settings.clone();
+    if let Some(rules_file) = &dest_settings.formatter.rules_file {
+        let source_rules = project_info.path.join(rules_file);
+        dest_settings.formatter.rules_file = Some(source_rules.to_string_lossy().to_string());
+    }
+    // Fonts are not copied, so message fitting measures the source project's font
+    let measured_font = match &dest_settings.text_fit.font_file {
+        Some(font_file) => Some(project_info.path.join(font_file)),
+        None => crate::engines::rpg_maker_mv::fonts::locate_game_font(
+            &project_info.path,
+            &project_info.engine_type,
+        )
+        .map(|font| font.path()),
+    };
+    dest_settings.text_fit.font_file = measured_font.map(|path| path.to_string_lossy().to_string());
+    dest_engine_info.settings = Some(dest_settings);
+
+    // Install the replacement font first so message fitting measures with it
+    if let Some(font_file) = &manifest.settings.fonts.replacement_font {
+        let supported = registry::registration(&dest_engine_info.engine_type)
+            .map(|r| r.capabilities.replacement_font)
+            .unwrap_or(false);
+        if !supported {
+            log::warn!(
+                "Skipping replacement font {}: not supported for {:?}",
+                font_file,
+                dest_engine_info.engine_type
+            );
+        } else {
+            // The fonts directory is only exported when the game gets a new font
+            if let Some(fonts_dir) =
+                crate::engines::rpg_maker_mv::fonts::fonts_dir(&dest_engine_info.engine_type)
+            {
+                let src_path = project_info.path.join(fonts_dir);
+                if src_path.exists() {
+                    copy_dir_recursive(&src_path, &dest_engine_info.path.join(fonts_dir)).map_err(
+                        |e| AppError::FileSystem(format!("Failed to copy {}: {}", fonts_dir, e)),
+                    )?;
+                }
+            }
+            let game_font = crate::engines::rpg_maker_mv::fonts::install_replacement_font(
+                &dest_engine_info.path,
+                &dest_engine_info.engine_type,
+                Path::new(font_file),
+            )?;
+            info!("Installed replacement font {}", game_font.file_name);
+            if manifest.settings.text_fit.font_file.is_none() {
+                if let Some(settings) = dest_engine_info.settings.as_mut() {
+                    settings.text_fit.font_file =
+                        Some(game_font.path().to_string_lossy().to_string());
+                }
+            }
+        }
+    }
 
     // Inject translations into the copied files
     engine
@@ -250,6 +329,87 @@ pub async fn export_translated_subset(
     Ok(destination_root.to_string())
 }
 
+/// Check the translated units of a project against the font the export will use.
+pub async fn check_font_coverage(
+    project_info: &crate::models::engine::EngineInfo,
+    db: &crate::db::state::ManagedTranslationState,
+) -> AppResult<crate::engines::rpg_maker_mv::fonts::FontCoverageReport> {
+    let manifest = crate::db::translation::manifest::create_or_load_project_manifest(project_info)
+        .map_err(|e| AppError::Other(format!("Failed to load manifest: {}", e)))?;
+    let records =
+        crate::db::translation::repo::find_translated_units_for_export(db, &manifest.project_id)
+            .await?;
+
+    let report = crate::engines::rpg_maker_mv::fonts::check_font_coverage(
+        &project_info.path,
+        &project_info.engine_type,
+        manifest.settings.fonts.replacement_font.as_deref(),
+        &records,
+    )?;
+    if !report.units.is_empty() {
+        log::warn!(
+            "{} translated units use {} characters missing from {}",
+            report.units.len(),
+            report.missing_characters.len(),
+            report.font_file
+        );
+    }
+    Ok(report)
+}
+
+/// Copy the files matching a `dir/prefix*suffix` pattern, keeping their paths;
+/// a `dir/**/prefix*suffix` pattern also matches files in subdirectories
+fn copy_matching_files(
+    src_root: &Path,
+    dest_root: &Path,
+    pattern: &str,
+) -> Result<(), std::io::Error> {
+    let pattern = Path::new(pattern);
+    let dir = pattern.parent().unwrap_or(Path::new(""));
+    let name = pattern.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let (prefix, suffix) = name.split_once('*').unwrap_or((name, ""));
+    let (dir, recursive) = match dir.file_name().and_then(|n| n.to_str()) {
+        Some("**") => (dir.parent().unwrap_or(Path::new("")), true),
+        _ => (dir, false),
+    };
+
+    copy_matching_files_in(
+        &src_root.join(dir),
+        &dest_root.join(dir),
+        prefix,
+        suffix,
+        recursive,
+    )
+}
+
+fn copy_matching_files_in(
+    src_dir: &Path,
+    dest_dir: &Path,
+    prefix: &str,
+    suffix: &str,
+    recursive: bool,
+) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(dest_dir)?;
+    for entry in std::fs::read_dir(src_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        let path = entry.path();
+        if recursive && path.is_dir() {
+            copy_matching_files_in(&path, &dest_dir.join(file_name), prefix, suffix, true)?;
+        } else if path.is_file()
+            && file_name.len() >= prefix.len() + suffix.len()
+            && file_name.starts_with(prefix)
+            && file_name.ends_with(suffix)
+        {
+            std::fs::copy(&path, dest_dir.join(file_name))?;
+        }
+    }
+    Ok(())
+}
+
 /// Recursively copy a directory
 fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<(), std::io::Error> {
     use std::fs;
@@ -272,8 +432,9 @@ fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<(
 
 /// Detects the engine type based on the project directory structure.
 ///
-/// This function checks the project against criteria for each supported engine type
-/// and returns the matching engine type.
+/// Every registered engine scores the project and the highest score wins;
+/// when several engines share it, the detection is ambiguous and the error
+/// names them so the user can tell which files to remove.
 ///
 /// # Arguments
 ///
@@ -282,81 +443,122 @@ fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<(
 /// # Returns
 ///
 /// * `AppResult<EngineType>` - The detected engine type
+///
+/// # Errors
+///
+/// Returns an error if several engines match the project equally well
 fn detect_engine_type(project_path: &Path) -> AppResult<EngineType> {
-    // Check for RPG Maker MV
-    if matches_criteria(project_path, &RpgMakerMvEngine::get_detection_criteria())? {
-        return Ok(EngineType::RpgMakerMv);
-    }
-
-    // Check for RPG Maker MZ
-    if matches_criteria(project_path, &RpgMakerMzEngine::get_detection_criteria())? {
-        return Ok(EngineType::RpgMakerMz);
-    }
-
-    // Check for Wolf RPG - looks for dump folder
-    if WolfRpgEngine::is_wolf_rpg_project(project_path) {
-        return Ok(EngineType::WolfRpg);
+    let matches = registry::detect(project_path)?;
+    let Some(best) = matches.first() else {
+        return Ok(EngineType::Unknown);
+    };
+
+    let tied: Vec<&str> = matches
+        .iter()
+        .filter(|m| m.score == best.score)
+        .map(|m| m.id)
+        .collect();
+    if tied.len() > 1 {
+        return Err(AppError::Other(format!(
+            "Ambiguous engine detection for {}: {} match equally well (score {})",
+            project_path.display(),
+            tied.join(", "),
+            best.score
+        )));
     }
-
-    // If no engine type was detected, return Unknown
-    Ok(EngineType::Unknown)
+    Ok(best.engine_type.clone())
 }
 
-/// Checks if a project directory matches the given engine criteria.
-///
-/// # Arguments
-///
-/// * `project_path` - Path to the root directory of the game project
-/// * `criteria` - The criteria to check against
-///
-/// # Returns
-///
-/// * `AppResult<bool>` - True if the project matches the criteria, false otherwise
-fn matches_criteria(project_path: &Path, criteria: &EngineCriteria) -> AppResult<bool> {
-    use log::{debug, info};
-
-    debug!(
-        "Checking project at {} against criteria",
-        project_path.display()
-    );
-
-    // Check required files
-    for file in &criteria.required_files {
-        let file_path = project_path.join(file);
-        debug!("Checking required file: {}", file_path.display());
-        if !file_path.exists() || !file_path.is_file() {
-            debug!("Required file not found: {}", file_path.display());
-            return Ok(false);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::state::ManagedTranslationState;
+    use crate::db::translation::manifest::create_or_load_project_manifest;
+    use crate::db::translation::model::TextUnitRecord;
+    use crate::models::language::Language;
+    use crate::models::translation::TranslationStatus;
+    use crate::utils::test_support::TempDir;
+
+    fn language(id: &str, label: &str) -> Language {
+        Language {
+            id: id.to_string(),
+            label: label.to_string(),
+            native_name: label.to_string(),
+            dir: "ltr".to_string(),
+            enabled: true,
         }
     }
 
-    // Check required folders
-    for folder in &criteria.required_folders {
-        let folder_path = project_path.join(folder);
-        debug!("Checking required folder: {}", folder_path.display());
-        if !folder_path.exists() || !folder_path.is_dir() {
-            debug!("Required folder not found: {}", folder_path.display());
-            return Ok(false);
+    #[tokio::test]
+    async fn test_export_runs_the_project_script() {
+        let temp = TempDir::new("factory_export_test");
+        let dir = temp.path();
+        let project = dir.join("project");
+        let destination = dir.join("export");
+        std::fs::create_dir_all(project.join("data/scenario")).unwrap();
+        std::fs::create_dir_all(project.join("scripts")).unwrap();
+        std::fs::write(
+            project.join("data/scenario/first.ks"),
+            "*start\nこんにちは。[p]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.join("scripts/hooks.rhai"),
+            r#"fn before_inject(text, unit) { text.replace("Hello", "Hi"); text }"#,
+        )
+        .unwrap();
+
+        let engine = get_engine(&project).unwrap();
+        let info = engine
+            .load_project_info(
+                &project,
+                language("ja", "Japanese"),
+                language("en", "English"),
+            )
+            .unwrap();
+        let mut manifest = create_or_load_project_manifest(&info).unwrap();
+        manifest.settings.scripts.file = Some("scripts/hooks.rhai".to_string());
+        // TyranoScript has no game font to replace; the export skips it
+        manifest.settings.fonts.replacement_font =
+            Some(dir.join("missing.ttf").to_string_lossy().to_string());
+        manifest.save_to_project(&project).unwrap();
+
+        let db = ManagedTranslationState::new(dir.join("translations.db"));
+        let project_path = project.to_string_lossy().to_string();
+        for mut unit in engine.extract_text_units(&info).unwrap() {
+            unit.translated_text = "Hello.[p]".to_string();
+            unit.status = TranslationStatus::MachineTranslated;
+            let record = TextUnitRecord::from_text_unit(
+                &unit,
+                &project_path,
+                "data/scenario/first.ks",
+                Some(&manifest.project_id),
+            );
+            crate::db::translation::repo::upsert_unit(&db, &record)
+                .await
+                .unwrap();
         }
-    }
 
-    // Check extra files (lenient): if any are declared, log whether at least one exists
-    if !criteria.extra_files.is_empty() {
-        let mut found_any = false;
-        for file in &criteria.extra_files {
-            let file_path = project_path.join(file);
-            debug!("Checking extra file: {}", file_path.display());
-            if file_path.exists() && file_path.is_file() {
-                debug!("Found extra file: {}", file_path.display());
-                found_any = true;
-                break;
-            }
-        }
-        if !found_any {
-            info!("No extra files found; proceeding based on required files/folders");
-        }
+        export_translated_subset(&info, &db, &destination.to_string_lossy())
+            .await
+            .unwrap();
+        let exported = std::fs::read_to_string(destination.join("data/scenario/first.ks")).unwrap();
+        assert_eq!(exported, "*start\nHi.[p]\n");
     }
 
-    debug!("Project matches criteria");
-    Ok(true)
+    #[test]
+    fn test_tied_detection_names_the_engines() {
+        // Ren'Py and TyranoScript layouts without either's extra files
+        let dir = TempDir::new("factory_tie_test");
+        std::fs::create_dir_all(dir.path().join("game")).unwrap();
+        std::fs::create_dir_all(dir.path().join("data/scenario")).unwrap();
+        std::fs::write(dir.path().join("game/script.rpy"), "label start:\n").unwrap();
+        std::fs::write(dir.path().join("data/scenario/first.ks"), "*start\n").unwrap();
+
+        let error = get_engine(dir.path()).err().unwrap().to_string();
+        assert!(error.contains("RenPy, TyranoScript"), "{}", error);
+
+        std::fs::write(dir.path().join("game/options.rpy"), "").unwrap();
+        assert_eq!(detect_engine_type(dir.path()).unwrap(), EngineType::RenPy);
+    }
 }
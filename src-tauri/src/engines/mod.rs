@@ -1,5 +1,12 @@
 pub mod common;
 pub mod factory;
+pub mod registry;
+pub mod renpy;
+pub mod rpg_maker_2k;
 pub mod rpg_maker_mv;
 pub mod rpg_maker_mz;
+pub mod rpg_maker_vx;
+pub mod rpg_maker_vxace;
+pub mod rpg_maker_xp;
+pub mod tyrano_script;
 pub mod wolf_rpg;
@@ -0,0 +1,227 @@
+//! Registry of the supported engines.
+//!
+//! Each engine module describes itself with an `EngineRegistration`: the id
+//! stored in project manifests, how to create it, how well a project folder
+//! matches it, the formatter protecting its text codes and what it supports.
+//! The factory, the manifest and the text processor look engines up here
+//! instead of matching on `EngineType`.
+
+use std::path::Path;
+
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::core::engine::Engine;
+use crate::core::error::AppResult;
+use crate::engines::renpy::engine::RenPyEngine;
+use crate::engines::rpg_maker_2k::engine::RpgMaker2kEngine;
+use crate::engines::rpg_maker_mv::engine::RpgMakerMvEngine;
+use crate::engines::rpg_maker_mz::engine::RpgMakerMzEngine;
+use crate::engines::rpg_maker_vx::engine::RpgMakerVxEngine;
+use crate::engines::rpg_maker_vxace::engine::RpgMakerVxAceEngine;
+use crate::engines::rpg_maker_xp::engine::RpgMakerXpEngine;
+use crate::engines::tyrano_script::engine::TyranoScriptEngine;
+use crate::engines::wolf_rpg::engine::WolfRpgEngine;
+use crate::models::engine::{EngineCriteria, EngineType};
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+
+/// Id stored for projects whose engine is not registered
+pub const UNKNOWN_ENGINE_ID: &str = "Unknown";
+
+/// What an engine supports beyond extraction and injection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineCapabilities {
+    /// Structured view of the game data files (`extract_game_data_files`)
+    pub game_data_files: bool,
+    /// Export of the translated files only (`export_translated_subset`)
+    pub minimal_export: bool,
+    /// Swapping the main game font on export (`fonts.replacement_font`)
+    pub replacement_font: bool,
+}
+
+/// An engine as known to the registry
+#[derive(Clone)]
+pub struct EngineRegistration {
+    /// Stable id, stored in project manifests (`"RpgMakerMv"`)
+    pub id: &'static str,
+    pub engine_type: EngineType,
+    pub create: fn() -> Box<dyn Engine>,
+    /// How well a project folder matches the engine, 0 for no match; scores
+    /// are compared across engines, see `criteria_score`
+    pub detect: fn(&Path) -> AppResult<u32>,
+    pub formatter: EngineTextFormatter,
+    pub capabilities: EngineCapabilities,
+}
+
+/// An engine matching a project folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineMatch {
+    pub engine_type: EngineType,
+    pub id: &'static str,
+    pub score: u32,
+}
+
+/// Registered engines, in detection order (equal scores make detection ambiguous)
+static REGISTRY: Lazy<Vec<EngineRegistration>> = Lazy::new(|| {
+    vec![
+        RpgMakerMvEngine::registration(),
+        RpgMakerMzEngine::registration(),
+        RpgMakerVxAceEngine::registration(),
+        RpgMakerVxEngine::registration(),
+        RpgMakerXpEngine::registration(),
+        RpgMaker2kEngine::registration(),
+        WolfRpgEngine::registration(),
+        RenPyEngine::registration(),
+        TyranoScriptEngine::registration(),
+    ]
+});
+
+/// All registered engines, in registration order
+pub fn registrations() -> &'static [EngineRegistration] {
+    &REGISTRY
+}
+
+/// The registration of an engine type
+pub fn registration(engine_type: &EngineType) -> Option<&'static EngineRegistration> {
+    REGISTRY.iter().find(|r| r.engine_type == *engine_type)
+}
+
+/// Manifest id of an engine type
+pub fn engine_id(engine_type: &EngineType) -> &'static str {
+    registration(engine_type)
+        .map(|r| r.id)
+        .unwrap_or(UNKNOWN_ENGINE_ID)
+}
+
+/// Engines matching a project folder, best match first
+pub fn detect(project_path: &Path) -> AppResult<Vec<EngineMatch>> {
+    let mut matches = Vec::new();
+    for registration in registrations() {
+        let score = (registration.detect)(project_path)?;
+        debug!("Detection score of {}: {}", registration.id, score);
+        if score > 0 {
+            matches.push(EngineMatch {
+                engine_type: registration.engine_type.clone(),
+                id: registration.id,
+                score,
+            });
+        }
+    }
+    // Stable, so tied matches stay in registration order
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    Ok(matches)
+}
+
+/// Detection score from engine criteria: `score` when the required files and
+/// folders exist, 10 more when one of the extra files does, 0 otherwise.
+///
+/// Engines use 80 for files only they have (`www/js/rpg_core.js`), 70 for
+/// folder layouts another tool could share (`game/*.rpy`) and 50–60 for
+/// fallbacks (a dump folder, the `Game.ini` library line).
+pub fn criteria_score(
+    project_path: &Path,
+    criteria: &EngineCriteria,
+    score: u32,
+) -> AppResult<u32> {
+    if !matches_criteria(project_path, criteria)? {
+        return Ok(0);
+    }
+    let has_extra_file = criteria
+        .extra_files
+        .iter()
+        .any(|file| project_path.join(file).is_file());
+    Ok(if has_extra_file { score + 10 } else { score })
+}
+
+/// Checks if a project directory matches the given engine criteria.
+///
+/// # Arguments
+///
+/// * `project_path` - Path to the root directory of the game project
+/// * `criteria` - The criteria to check against
+///
+/// # Returns
+///
+/// * `AppResult<bool>` - True if the project matches the criteria, false otherwise
+pub fn matches_criteria(project_path: &Path, criteria: &EngineCriteria) -> AppResult<bool> {
+    debug!(
+        "Checking project at {} against criteria",
+        project_path.display()
+    );
+
+    // Check required files
+    for file in &criteria.required_files {
+        let file_path = project_path.join(file);
+        debug!("Checking required file: {}", file_path.display());
+        if !file_path.exists() || !file_path.is_file() {
+            debug!("Required file not found: {}", file_path.display());
+            return Ok(false);
+        }
+    }
+
+    // Check required folders
+    for folder in &criteria.required_folders {
+        let folder_path = project_path.join(folder);
+        debug!("Checking required folder: {}", folder_path.display());
+        if !folder_path.exists() || !folder_path.is_dir() {
+            debug!("Required folder not found: {}", folder_path.display());
+            return Ok(false);
+        }
+    }
+
+    // Check extra files (lenient): if any are declared, log whether at least one exists
+    if !criteria.extra_files.is_empty() {
+        let mut found_any = false;
+        for file in &criteria.extra_files {
+            let file_path = project_path.join(file);
+            debug!("Checking extra file: {}", file_path.display());
+            if file_path.exists() && file_path.is_file() {
+                debug!("Found extra file: {}", file_path.display());
+                found_any = true;
+                break;
+            }
+        }
+        if !found_any {
+            info!("No extra files found; proceeding based on required files/folders");
+        }
+    }
+
+    debug!("Project matches criteria");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    #[test]
+    fn test_registered_engines_have_distinct_ids_and_types() {
+        let registrations = registrations();
+        for (i, a) in registrations.iter().enumerate() {
+            for b in &registrations[i + 1..] {
+                assert_ne!(a.id, b.id);
+                assert_ne!(a.engine_type, b.engine_type);
+            }
+        }
+        assert_eq!(engine_id(&EngineType::RpgMakerMv), "RpgMakerMv");
+        assert_eq!(engine_id(&EngineType::Unknown), UNKNOWN_ENGINE_ID);
+    }
+
+    #[test]
+    fn test_detect_orders_by_score() {
+        let temp = TempDir::new("engine_registry_test");
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("game")).unwrap();
+        std::fs::create_dir_all(dir.join("data/scenario")).unwrap();
+        std::fs::write(dir.join("game/script.rpy"), "label start:\n").unwrap();
+        std::fs::write(dir.join("game/options.rpy"), "").unwrap();
+        std::fs::write(dir.join("data/scenario/first.ks"), "*start\n").unwrap();
+
+        let matches = detect(dir).unwrap();
+        let ids: Vec<(&str, u32)> = matches.iter().map(|m| (m.id, m.score)).collect();
+        assert_eq!(ids, [("RenPy", 80), ("TyranoScript", 70)]);
+    }
+}
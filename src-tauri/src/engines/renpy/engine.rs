@@ -0,0 +1,414 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::core::engine::Engine;
+use crate::core::error::{AppError, AppResult};
+use crate::engines::registry::{criteria_score, EngineCapabilities, EngineRegistration};
+use crate::engines::renpy::script::{self, EntryKind, ScriptEntry};
+use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
+use crate::models::language::Language;
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus, UnitPosition};
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::renpy_formatter::RenPyFormatter;
+use crate::utils::text::types::RawTextUnit;
+
+/// Folder holding a Ren'Py game's scripts
+const GAME_DIR: &str = "game";
+
+/// Folder of translation files inside `game/`
+const TL_DIR: &str = "tl";
+
+/// Script holding `config.name` and `config.version`
+const OPTIONS_FILE: &str = "game/options.rpy";
+
+/// Scripts with their entries, in the order identifiers were assigned
+type Scripts = Vec<(String, Vec<ScriptEntry>)>;
+
+/// Implementation of the Engine trait for Ren'Py games.
+///
+/// Scripts (`game/**/*.rpy`) are read by the `script` module. Translations are
+/// never written into them: injection generates the translation tree Ren'Py
+/// loads for a language, `game/tl/<language>/`, with one file per script.
+pub struct RenPyEngine {
+    detection_criteria: EngineCriteria,
+}
+
+impl RenPyEngine {
+    pub fn new() -> Self {
+        Self {
+            detection_criteria: Self::get_detection_criteria(),
+        }
+    }
+
+    /// A Ren'Py game: a `game` folder with `.rpy` scripts
+    pub fn get_detection_criteria() -> EngineCriteria {
+        EngineCriteria {
+            required_files: vec![],
+            required_folders: vec![GAME_DIR.to_string()],
+            extra_files: vec![OPTIONS_FILE.to_string()],
+            export_data_roots: vec![format!("{}/**/*.rpy", GAME_DIR)],
+        }
+    }
+
+    /// Registration of the engine: `.rpy` scripts in a `game` folder
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "RenPy",
+            engine_type: EngineType::RenPy,
+            create: || Box::new(Self::new()),
+            detect: |path| {
+                if !Self::is_renpy_project(path) {
+                    return Ok(0);
+                }
+                criteria_score(path, &Self::get_detection_criteria(), 70)
+            },
+            formatter: EngineTextFormatter::of::<RenPyFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: false,
+                minimal_export: true,
+                replacement_font: false,
+            },
+        }
+    }
+
+    /// Check if this directory holds Ren'Py scripts outside `game/tl`
+    pub fn is_renpy_project(path: &Path) -> bool {
+        !Self::script_files(path).is_empty()
+    }
+
+    /// Scripts of the game, relative to the project root with `/` separators,
+    /// in a stable order (translation identifiers depend on it)
+    fn script_files(project_path: &Path) -> Vec<String> {
+        let game_dir = project_path.join(GAME_DIR);
+        let tl_dir = game_dir.join(TL_DIR);
+        let mut files: Vec<String> = walkdir::WalkDir::new(&game_dir)
+            .into_iter()
+            .filter_entry(|entry| entry.path() != tl_dir)
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rpy")
+            })
+            .map(|path| Self::relative_path(&path, project_path))
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn relative_path(path: &Path, project_path: &Path) -> String {
+        path.strip_prefix(project_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    fn read_script(project_path: &Path, file_path: &str) -> AppResult<String> {
+        std::fs::read_to_string(project_path.join(file_path))
+            .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", file_path, e)))
+    }
+
+    /// Entries of every script with their identifiers, and the speaker names
+    /// declared across the game
+    fn read_scripts(project_path: &Path) -> AppResult<(Scripts, HashMap<String, String>)> {
+        let mut scripts = Vec::new();
+        let mut speakers = HashMap::new();
+        let mut identifiers = HashSet::new();
+        for file_path in Self::script_files(project_path) {
+            let source = Self::read_script(project_path, &file_path)?;
+            speakers.extend(script::character_names(&source));
+            let mut entries = script::parse_script(&source);
+            script::assign_identifiers(&mut entries, &mut identifiers);
+            scripts.push((file_path, entries));
+        }
+        Ok((scripts, speakers))
+    }
+
+    /// Folder name Ren'Py uses for a language (`french`, `schinese`)
+    fn tl_language(language: &Language) -> String {
+        match language.id.as_str() {
+            "zh" | "zh-CN" | "zh-Hans" => return "schinese".to_string(),
+            "zh-TW" | "zh-Hant" => return "tchinese".to_string(),
+            _ => {}
+        }
+        language
+            .label
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    /// Translation file generated for a script
+    fn tl_file(project_path: &Path, language: &str, file_path: &str) -> PathBuf {
+        let relative = file_path
+            .strip_prefix(&format!("{}/", GAME_DIR))
+            .unwrap_or(file_path);
+        project_path
+            .join(GAME_DIR)
+            .join(TL_DIR)
+            .join(language)
+            .join(relative)
+    }
+
+    fn unit_id(file_path: &str, kind: EntryKind, key: &str) -> String {
+        match kind {
+            EntryKind::Dialogue => format!("renpy:{}#{}", file_path, key),
+            EntryKind::MenuChoice => format!("renpy:{}#choice_{}", file_path, key),
+        }
+    }
+}
+
+impl Engine for RenPyEngine {
+    fn load_project_info(
+        &self,
+        path: &Path,
+        source_language: Language,
+        target_language: Language,
+    ) -> AppResult<EngineInfo> {
+        let config = std::fs::read_to_string(path.join(OPTIONS_FILE))
+            .map(|source| script::config_values(&source))
+            .unwrap_or_default();
+        let name = config.get("name").cloned().unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Ren'Py Project")
+                .to_string()
+        });
+
+        Ok(EngineInfo {
+            name,
+            path: path.to_path_buf(),
+            engine_type: EngineType::RenPy,
+            source_language,
+            target_language,
+            version: config.get("version").cloned(),
+            detection_criteria: self.detection_criteria.clone(),
+            manifest_hash: None,
+            settings: None,
+        })
+    }
+
+    fn get_detection_criteria(&self) -> EngineCriteria {
+        self.detection_criteria.clone()
+    }
+
+    fn extract_raw_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<RawTextUnit>> {
+        let (scripts, speakers) = Self::read_scripts(&project_info.path)?;
+        let mut raw_units = Vec::new();
+        for (file_path, entries) in scripts {
+            let count = raw_units.len();
+            for entry in entries {
+                let (key, field_type) = match (entry.kind, &entry.identifier) {
+                    (EntryKind::Dialogue, Some(identifier)) => (
+                        identifier.clone(),
+                        format!("dialogue:{}:{}", file_path, identifier),
+                    ),
+                    (EntryKind::MenuChoice, _) => (
+                        entry.line.to_string(),
+                        format!("menu_choice:{}:{}", file_path, entry.line),
+                    ),
+                    _ => continue,
+                };
+                // `e` is shown as its Character name, a string speaker as is
+                let speaker = entry.who.as_ref().map(|who| {
+                    speakers
+                        .get(who)
+                        .cloned()
+                        .unwrap_or_else(|| who.trim_matches(|c| c == '"' || c == '\'').to_string())
+                });
+                raw_units.push(RawTextUnit {
+                    id: Self::unit_id(&file_path, entry.kind, &key),
+                    source_text: entry.what,
+                    field_type,
+                    prompt_type: PromptType::Dialogue,
+                    position: Some(UnitPosition {
+                        event_ref: format!(
+                            "{}#{}",
+                            file_path,
+                            entry.label.as_deref().unwrap_or_default()
+                        ),
+                        page_index: 0,
+                        command_index: entry.line as u32,
+                        speaker,
+                    }),
+                });
+            }
+            log::debug!(
+                "Extracted {} text units from {}",
+                raw_units.len() - count,
+                file_path
+            );
+        }
+        Ok(raw_units)
+    }
+
+    fn inject_raw_text_units(
+        &self,
+        project_info: &EngineInfo,
+        raw_units: &[RawTextUnit],
+    ) -> AppResult<()> {
+        let translations: HashMap<&str, &str> = raw_units
+            .iter()
+            .map(|unit| (unit.id.as_str(), unit.source_text.as_str()))
+            .collect();
+        let language = Self::tl_language(&project_info.target_language);
+        let (scripts, _) = Self::read_scripts(&project_info.path)?;
+
+        // A string is translated once for the whole game
+        let mut translated_strings = HashSet::new();
+        for (file_path, entries) in &scripts {
+            let mut dialogue = Vec::new();
+            let mut strings = Vec::new();
+            for entry in entries {
+                let key = match entry.kind {
+                    EntryKind::Dialogue => entry.identifier.clone().unwrap_or_default(),
+                    EntryKind::MenuChoice => entry.line.to_string(),
+                };
+                let Some(translation) = translations
+                    .get(Self::unit_id(file_path, entry.kind, &key).as_str())
+                    .copied()
+                else {
+                    continue;
+                };
+                match entry.kind {
+                    EntryKind::Dialogue => dialogue.push((entry, translation)),
+                    EntryKind::MenuChoice => {
+                        if translated_strings.insert(entry.what.as_str()) {
+                            strings.push((entry, translation));
+                        }
+                    }
+                }
+            }
+            if dialogue.is_empty() && strings.is_empty() {
+                continue;
+            }
+
+            let tl_file = Self::tl_file(&project_info.path, &language, file_path);
+            if let Some(parent) = tl_file.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    AppError::FileSystem(format!("Failed to create {}: {}", parent.display(), e))
+                })?;
+            }
+            let content = script::render_translations(&language, file_path, &dialogue, &strings);
+            std::fs::write(&tl_file, content).map_err(|e| {
+                AppError::FileSystem(format!("Failed to write {}: {}", tl_file.display(), e))
+            })?;
+            log::info!(
+                "Wrote {} translations for {} to {}",
+                dialogue.len() + strings.len(),
+                file_path,
+                tl_file.display()
+            );
+        }
+        Ok(())
+    }
+
+    fn reconstruct_text_unit_id(
+        &self,
+        field_type: &str,
+        source_text: &str,
+        translated_text: &str,
+    ) -> AppResult<TextUnit> {
+        // Field types are "kind:file:key", e.g. "dialogue:game/script.rpy:start_a170b500"
+        let mut parts = field_type.splitn(3, ':');
+        let kind = match parts.next() {
+            Some("dialogue") => EntryKind::Dialogue,
+            Some("menu_choice") => EntryKind::MenuChoice,
+            _ => {
+                return Err(AppError::Other(format!(
+                    "Invalid Ren'Py field_type format: {}",
+                    field_type
+                )))
+            }
+        };
+        let (Some(file_path), Some(key)) = (parts.next(), parts.next()) else {
+            return Err(AppError::Other(format!(
+                "Invalid Ren'Py field_type format: {}",
+                field_type
+            )));
+        };
+
+        Ok(TextUnit {
+            id: Self::unit_id(file_path, kind, key),
+            source_text: source_text.to_string(),
+            translated_text: translated_text.to_string(),
+            field_type: field_type.to_string(),
+            status: TranslationStatus::MachineTranslated,
+            prompt_type: PromptType::Dialogue,
+            position: None,
+            detected_language: None,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    fn language(id: &str, label: &str) -> Language {
+        Language {
+            id: id.to_string(),
+            label: label.to_string(),
+            native_name: label.to_string(),
+            dir: "ltr".to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_extract_and_inject_into_tl_tree() {
+        let temp = TempDir::new("renpy_engine_test");
+        let dir = temp.path();
+        let game = dir.join("game");
+        std::fs::create_dir_all(game.join("tl/french")).unwrap();
+        std::fs::write(
+            game.join("script.rpy"),
+            "define e = Character(\"Eileen\")\n\nlabel start:\n    e \"Hello.\"\n    menu:\n        \"Yes\":\n            pass\n",
+        )
+        .unwrap();
+        std::fs::write(
+            game.join("tl/french/old.rpy"),
+            "translate french strings:\n",
+        )
+        .unwrap();
+        assert!(RenPyEngine::is_renpy_project(dir));
+
+        let engine = RenPyEngine::new();
+        let info = engine
+            .load_project_info(dir, language("en", "English"), language("fr", "French"))
+            .unwrap();
+        let units = engine.extract_raw_text_units(&info).unwrap();
+        assert_eq!(units.len(), 2);
+        assert_eq!(
+            units[0].position.as_ref().unwrap().speaker.as_deref(),
+            Some("Eileen")
+        );
+
+        let translated: Vec<RawTextUnit> = units
+            .iter()
+            .zip(["Bonjour.", "Oui"])
+            .map(|(unit, text)| RawTextUnit {
+                source_text: text.to_string(),
+                ..unit.clone()
+            })
+            .collect();
+        engine.inject_raw_text_units(&info, &translated).unwrap();
+
+        let tl = std::fs::read_to_string(game.join("tl/french/script.rpy")).unwrap();
+        assert!(tl.contains("    # e \"Hello.\"\n    e \"Bonjour.\"\n"));
+        assert!(tl.contains("    old \"Yes\"\n    new \"Oui\"\n"));
+        let original = std::fs::read_to_string(game.join("script.rpy")).unwrap();
+        assert!(original.contains("e \"Hello.\""));
+
+        let unit = engine
+            .reconstruct_text_unit_id(&units[0].field_type, "Hello.", "Bonjour.")
+            .unwrap();
+        assert_eq!(unit.id, units[0].id);
+    }
+}
@@ -0,0 +1,679 @@
+//! Ren'Py script (`.rpy`) reading and translation file writing.
+//!
+//! Scripts are read as logical lines (a line continues while a string or a
+//! bracket is open). Say statements give dialogue and narration, menu blocks
+//! give choices; Python, screen, style and other definition blocks are
+//! skipped.
+//!
+//! Dialogue is translated by `translate <language> <identifier>:` blocks whose
+//! identifier is the label followed by the first 8 hex digits of the MD5 of the
+//! statement's code, the way Ren'Py's own generator computes it. Menu choices
+//! go to a `translate <language> strings:` block keyed by their text.
+
+use std::collections::{HashMap, HashSet};
+
+use md5::{Digest, Md5};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Kind of translatable script text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Say statement: dialogue, narration or a menu caption
+    Dialogue,
+    /// Menu choice, translated as a string
+    MenuChoice,
+}
+
+/// Translatable text of a script
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptEntry {
+    pub kind: EntryKind,
+    /// 1-based line of the statement
+    pub line: usize,
+    /// Label the statement belongs to
+    pub label: Option<String>,
+    /// Speaker expression (`e`, `"Eileen"`), `None` for narration
+    pub who: Option<String>,
+    /// Text as Ren'Py shows it: escapes resolved, whitespace runs collapsed
+    pub what: String,
+    /// Translate block identifier, set by `assign_identifiers` for dialogue
+    pub identifier: Option<String>,
+    /// Words before the text (speaker and attributes)
+    prefix: Vec<String>,
+    /// Clauses after the text (`with dissolve`), without an `id` clause
+    suffix: String,
+    /// Identifier given with an `id` clause
+    explicit_id: Option<String>,
+    /// `voice` statement right before the dialogue, translated with it
+    voice: Option<String>,
+}
+
+/// First words of statements that are never say statements
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "$",
+    "at",
+    "behind",
+    "call",
+    "camera",
+    "default",
+    "define",
+    "elif",
+    "else",
+    "expression",
+    "hide",
+    "if",
+    "image",
+    "init",
+    "jump",
+    "label",
+    "layeredimage",
+    "menu",
+    "nvl",
+    "onlayer",
+    "pass",
+    "pause",
+    "play",
+    "python",
+    "queue",
+    "renpy",
+    "return",
+    "scene",
+    "screen",
+    "set",
+    "show",
+    "stop",
+    "style",
+    "transform",
+    "translate",
+    "voice",
+    "while",
+    "window",
+    "with",
+    "zorder",
+];
+
+/// Blocks holding code or definitions rather than script text
+const SKIPPED_BLOCKS: &[&str] = &[
+    "python",
+    "screen",
+    "style",
+    "transform",
+    "translate",
+    "layeredimage",
+    "image",
+    "testcase",
+];
+
+/// Speaker and attribute words before the text (`e`, `happy`, `@`, `-sad`)
+static SAY_WORD_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:@|[-@]?[A-Za-z_][A-Za-z0-9_.]*)$").unwrap());
+
+static LABEL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^label\s+(\.?[A-Za-z_][A-Za-z0-9_.]*)").unwrap());
+
+static MENU_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^menu(?:\s+[A-Za-z_][A-Za-z0-9_]*)?\s*(?:\(.*\))?\s*:$").unwrap());
+
+/// `id` clause of a say statement
+static ID_CLAUSE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|\s)id\s+([A-Za-z0-9_]+)").unwrap());
+
+/// `define e = Character("Eileen", ...)`, with an optional `_()` around the name
+static CHARACTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^define\s+([A-Za-z_][A-Za-z0-9_.]*)\s*=\s*(?:[A-Za-z_][A-Za-z0-9_]*\.)*Character\(\s*(?:_\(\s*)?("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')"#,
+    )
+    .unwrap()
+});
+
+/// `define config.<name> = "value"`, with an optional `_()` around the value
+static CONFIG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^define\s+config\.([a-z_]+)\s*=\s*(?:_\(\s*)?("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')"#,
+    )
+    .unwrap()
+});
+
+/// A statement with its continuation lines joined
+struct LogicalLine {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+/// Translatable entries of a script, in order
+pub fn parse_script(source: &str) -> Vec<ScriptEntry> {
+    let mut entries = Vec::new();
+    let mut label: Option<String> = None;
+    let mut global_label: Option<String> = None;
+    let mut skip_indent: Option<usize> = None;
+    // Menus as (indent of the `menu` line, indent of its choices)
+    let mut menus: Vec<(usize, Option<usize>)> = Vec::new();
+    let mut voice: Option<String> = None;
+
+    for line in logical_lines(source) {
+        if let Some(indent) = skip_indent {
+            if line.indent > indent {
+                continue;
+            }
+            skip_indent = None;
+        }
+        while menus
+            .last()
+            .is_some_and(|(indent, _)| line.indent <= *indent)
+        {
+            menus.pop();
+        }
+        if let Some((menu_indent, choice_indent)) = menus.last_mut() {
+            if choice_indent.is_none() && line.indent > *menu_indent {
+                *choice_indent = Some(line.indent);
+            }
+        }
+        let at_choice_level = menus
+            .last()
+            .is_some_and(|(_, choice_indent)| *choice_indent == Some(line.indent));
+
+        let text = line.text.as_str();
+        let first_word = text.split_whitespace().next().unwrap_or("");
+
+        if text.ends_with(':')
+            && (SKIPPED_BLOCKS.contains(&first_word)
+                || (first_word == "init" && text.split_whitespace().any(|w| w == "python")))
+        {
+            skip_indent = Some(line.indent);
+            voice = None;
+            continue;
+        }
+
+        if let Some(caps) = LABEL_REGEX.captures(text) {
+            let name = &caps[1];
+            let full_name = match (name.strip_prefix('.'), &global_label) {
+                (Some(local), Some(global)) => format!("{}.{}", global, local),
+                _ => name.to_string(),
+            };
+            // Labels starting with an underscore do not name translations
+            if !full_name.starts_with('_') {
+                if !name.starts_with('.') {
+                    global_label = Some(full_name.clone());
+                }
+                label = Some(full_name);
+            }
+            voice = None;
+            continue;
+        }
+
+        if MENU_REGEX.is_match(text) {
+            menus.push((line.indent, None));
+            voice = None;
+            continue;
+        }
+
+        if first_word == "voice" && text != "voice sustain" {
+            voice = Some(text.to_string());
+            continue;
+        }
+
+        let Some(say) = parse_say(text) else {
+            voice = None;
+            continue;
+        };
+
+        if say.suffix.ends_with(':') {
+            let condition = say.suffix.trim_end_matches(':').trim();
+            if at_choice_level
+                && say.prefix.is_empty()
+                && (condition.is_empty()
+                    || condition.starts_with("if ")
+                    || condition.starts_with('('))
+            {
+                entries.push(ScriptEntry {
+                    kind: EntryKind::MenuChoice,
+                    line: line.number,
+                    label: label.clone(),
+                    who: None,
+                    what: say.what,
+                    identifier: None,
+                    prefix: Vec::new(),
+                    suffix: String::new(),
+                    explicit_id: None,
+                    voice: None,
+                });
+            }
+            voice = None;
+            continue;
+        }
+
+        if label.is_none() {
+            log::debug!("Skipping dialogue outside a label at line {}", line.number);
+            voice = None;
+            continue;
+        }
+
+        entries.push(ScriptEntry {
+            kind: EntryKind::Dialogue,
+            line: line.number,
+            label: label.clone(),
+            who: say.prefix.first().cloned(),
+            what: say.what,
+            identifier: None,
+            prefix: say.prefix,
+            suffix: say.suffix,
+            explicit_id: say.explicit_id,
+            voice: voice.take(),
+        });
+    }
+
+    entries
+}
+
+/// Give each dialogue entry its translate identifier, suffixing duplicates
+/// (`start_1a2b3c4d_1`) the way Ren'Py does; `seen` spans the whole game
+pub fn assign_identifiers(entries: &mut [ScriptEntry], seen: &mut HashSet<String>) {
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.kind == EntryKind::Dialogue)
+    {
+        let identifier = match &entry.explicit_id {
+            Some(id) => id.clone(),
+            None => {
+                let mut hasher = Md5::new();
+                if let Some(voice) = &entry.voice {
+                    hasher.update(format!("{}\r\n", voice).as_bytes());
+                }
+                hasher.update(format!("{}\r\n", entry.code(&entry.what)).as_bytes());
+                let digest: String = hasher
+                    .finalize()
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                let label = entry.label.as_deref().unwrap_or_default().replace('.', "_");
+                let base = format!("{}_{}", label, &digest[..8]);
+                let mut identifier = base.clone();
+                let mut n = 1;
+                while seen.contains(&identifier) {
+                    identifier = format!("{}_{}", base, n);
+                    n += 1;
+                }
+                identifier
+            }
+        };
+        seen.insert(identifier.clone());
+        entry.identifier = Some(identifier);
+    }
+}
+
+impl ScriptEntry {
+    /// The say statement with `what` as its text
+    fn code(&self, what: &str) -> String {
+        let mut parts = self.prefix.clone();
+        parts.push(quote(what));
+        if !self.suffix.is_empty() {
+            parts.push(self.suffix.clone());
+        }
+        parts.join(" ")
+    }
+}
+
+/// Speaker names declared with `Character(...)`, by variable
+pub fn character_names(source: &str) -> HashMap<String, String> {
+    logical_lines(source)
+        .iter()
+        .filter_map(|line| {
+            let caps = CHARACTER_REGEX.captures(&line.text)?;
+            let name = string_value(&caps[2])?;
+            Some((caps[1].to_string(), name))
+        })
+        .filter(|(_, name)| !name.is_empty())
+        .collect()
+}
+
+/// `define config.<key> = "..."` values (`name`, `version`)
+pub fn config_values(source: &str) -> HashMap<String, String> {
+    logical_lines(source)
+        .iter()
+        .filter_map(|line| {
+            let caps = CONFIG_REGEX.captures(&line.text)?;
+            Some((caps[1].to_string(), string_value(&caps[2])?))
+        })
+        .collect()
+}
+
+/// Translation file for one script
+///
+/// `dialogue` pairs dialogue entries with their translation, `strings` pairs
+/// menu choices with theirs; `source_path` is the script's path for comments.
+pub fn render_translations(
+    language: &str,
+    source_path: &str,
+    dialogue: &[(&ScriptEntry, &str)],
+    strings: &[(&ScriptEntry, &str)],
+) -> String {
+    // Ren'Py writes its translation files with a BOM
+    let mut out = String::from("\u{feff}");
+
+    for (entry, translation) in dialogue {
+        let Some(identifier) = &entry.identifier else {
+            continue;
+        };
+        out.push_str(&format!("# {}:{}\n", source_path, entry.line));
+        out.push_str(&format!("translate {} {}:\n\n", language, identifier));
+        if let Some(voice) = &entry.voice {
+            out.push_str(&format!("    # {}\n", voice));
+        }
+        out.push_str(&format!("    # {}\n", entry.code(&entry.what)));
+        if let Some(voice) = &entry.voice {
+            out.push_str(&format!("    {}\n", voice));
+        }
+        out.push_str(&format!("    {}\n\n", entry.code(translation)));
+    }
+
+    if !strings.is_empty() {
+        out.push_str(&format!("translate {} strings:\n\n", language));
+        for (entry, translation) in strings {
+            out.push_str(&format!("    # {}:{}\n", source_path, entry.line));
+            out.push_str(&format!("    old {}\n", quote(&entry.what)));
+            out.push_str(&format!("    new {}\n\n", quote(translation)));
+        }
+    }
+
+    out
+}
+
+/// A say statement split around its text
+struct Say {
+    prefix: Vec<String>,
+    what: String,
+    suffix: String,
+    explicit_id: Option<String>,
+}
+
+/// Parse `[who] [attributes] "what" [clauses]` or `"who" "what" [clauses]`
+fn parse_say(text: &str) -> Option<Say> {
+    let mut prefix = Vec::new();
+    let mut rest = text.trim();
+    while !rest.starts_with(['"', '\'']) {
+        let end = rest.find(char::is_whitespace)?;
+        let word = &rest[..end];
+        if !SAY_WORD_REGEX.is_match(word)
+            || (prefix.is_empty() && STATEMENT_KEYWORDS.contains(&word))
+        {
+            return None;
+        }
+        prefix.push(word.to_string());
+        rest = rest[end..].trim_start();
+    }
+
+    let (length, mut what) = read_string(rest)?;
+    if prefix.is_empty() && rest[length..].trim_start().starts_with(['"', '\'']) {
+        // A string speaker: `"Eileen" "Hello"`
+        prefix.push(rest[..length].to_string());
+        rest = rest[length..].trim_start();
+        let (length, second) = read_string(rest)?;
+        what = second;
+        rest = rest[length..].trim_start();
+    } else {
+        rest = rest[length..].trim_start();
+    }
+
+    let mut suffix = rest.to_string();
+    let explicit_id = ID_CLAUSE_REGEX.captures(rest).map(|caps| {
+        suffix = ID_CLAUSE_REGEX.replace(rest, "").trim().to_string();
+        caps[1].to_string()
+    });
+    let valid_suffix = suffix.is_empty()
+        || suffix.ends_with(':')
+        || suffix == "nointeract"
+        || suffix.starts_with("nointeract ")
+        || suffix.starts_with("with ")
+        || suffix.starts_with('(');
+    if !valid_suffix {
+        return None;
+    }
+
+    Some(Say {
+        prefix,
+        what,
+        suffix: suffix.split_whitespace().collect::<Vec<_>>().join(" "),
+        explicit_id,
+    })
+}
+
+/// Read the string literal at the start of `text`: its length and value
+fn read_string(text: &str) -> Option<(usize, String)> {
+    let quote_char = text.chars().next()?;
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote_char {
+            return Some((i + 1, unescape(&text[1..i])));
+        }
+    }
+    None
+}
+
+fn string_value(literal: &str) -> Option<String> {
+    read_string(literal).map(|(_, value)| value)
+}
+
+/// String contents as Ren'Py reads them: whitespace runs collapse to one
+/// space, `\n` is a newline, `\{`, `\[` and `\%` stay escaped as doubled
+/// characters, and other escaped characters stand for themselves
+fn unescape(contents: &str) -> String {
+    let mut collapsed = String::with_capacity(contents.len());
+    let mut in_space = false;
+    for c in contents.chars() {
+        if c == ' ' || c == '\n' {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+
+    let mut out = String::with_capacity(collapsed.len());
+    let mut chars = collapsed.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('{') => out.push_str("{{"),
+            Some('[') => out.push_str("[["),
+            Some('%') => out.push_str("%%"),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Quote a text as Ren'Py's generator does: backslashes, newlines and quotes
+/// escaped, and every space after another space written as `\ `
+fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    let mut previous = None;
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '"' => out.push_str("\\\""),
+            ' ' if previous == Some(' ') => out.push_str("\\ "),
+            _ => out.push(c),
+        }
+        previous = Some(c);
+    }
+    out.push('"');
+    out
+}
+
+/// Statements with continuation lines joined and comments removed
+fn logical_lines(source: &str) -> Vec<LogicalLine> {
+    let mut lines = Vec::new();
+    let mut current: Option<LogicalLine> = None;
+    let mut in_string: Option<char> = None;
+    let mut depth: i32 = 0;
+
+    for (index, physical) in source.lines().enumerate() {
+        let physical = physical.trim_start_matches('\u{feff}');
+        let mut content = String::new();
+        let mut escaped = false;
+        for c in physical.chars() {
+            if let Some(quote_char) = in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == quote_char {
+                    in_string = None;
+                }
+            } else {
+                match c {
+                    '#' => break,
+                    '"' | '\'' | '`' => in_string = Some(c),
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            content.push(c);
+        }
+
+        match current.as_mut() {
+            Some(line) => {
+                line.text.push('\n');
+                line.text.push_str(content.trim_end());
+            }
+            None => {
+                let indent = content.len() - content.trim_start().len();
+                current = Some(LogicalLine {
+                    number: index + 1,
+                    indent,
+                    text: content.trim().to_string(),
+                });
+            }
+        }
+
+        if in_string.is_none() && depth <= 0 {
+            depth = 0;
+            if let Some(line) = current.take() {
+                if !line.text.is_empty() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    if let Some(line) = current {
+        if !line.text.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r##"define e = Character("Eileen", color="#c8ffc8")
+
+init python:
+    greeting = "Not dialogue"
+
+label start:
+    scene bg room
+    show eileen happy
+
+    e "You've created a new Ren'Py game."
+    "It's a  narration line." with dissolve
+    voice "eileen_01.ogg"
+    e happy "Multi
+        line."
+
+    menu:
+        "What should I do?"
+        "Go left" if can_go:
+            jump left
+        "Go right":
+            e "Right it is." id custom_id
+
+label .local:
+    e "You've created a new Ren'Py game."
+"##;
+
+    #[test]
+    fn test_parse_script_entries() {
+        let mut entries = parse_script(SCRIPT);
+        assign_identifiers(&mut entries, &mut HashSet::new());
+
+        let summary: Vec<(EntryKind, usize, Option<&str>, &str)> = entries
+            .iter()
+            .map(|e| (e.kind, e.line, e.who.as_deref(), e.what.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    EntryKind::Dialogue,
+                    10,
+                    Some("e"),
+                    "You've created a new Ren'Py game."
+                ),
+                (EntryKind::Dialogue, 11, None, "It's a narration line."),
+                (EntryKind::Dialogue, 13, Some("e"), "Multi line."),
+                (EntryKind::Dialogue, 17, None, "What should I do?"),
+                (EntryKind::MenuChoice, 18, None, "Go left"),
+                (EntryKind::MenuChoice, 20, None, "Go right"),
+                (EntryKind::Dialogue, 21, Some("e"), "Right it is."),
+                (
+                    EntryKind::Dialogue,
+                    24,
+                    Some("e"),
+                    "You've created a new Ren'Py game."
+                ),
+            ]
+        );
+
+        // MD5 of `e "You've created a new Ren'Py game."\r\n`
+        assert_eq!(entries[0].identifier.as_deref(), Some("start_a170b500"));
+        assert_eq!(entries[2].voice.as_deref(), Some("voice \"eileen_01.ogg\""));
+        assert_eq!(entries[6].identifier.as_deref(), Some("custom_id"));
+        assert!(entries[7]
+            .identifier
+            .as_deref()
+            .is_some_and(|id| id.starts_with("start_local_")));
+
+        assert_eq!(
+            character_names(SCRIPT).get("e").map(String::as_str),
+            Some("Eileen")
+        );
+    }
+
+    #[test]
+    fn test_render_translations() {
+        let mut entries = parse_script(SCRIPT);
+        assign_identifiers(&mut entries, &mut HashSet::new());
+        let out = render_translations(
+            "french",
+            "game/script.rpy",
+            &[(&entries[1], "Une ligne \"narrée\".")],
+            &[(&entries[4], "Aller à gauche")],
+        );
+        assert!(out.contains("# game/script.rpy:11\ntranslate french start_"));
+        assert!(out.contains(
+            "    # \"It's a narration line.\" with dissolve\n    \"Une ligne \\\"narrée\\\".\" with dissolve\n"
+        ));
+        assert!(out.contains(
+            "translate french strings:\n\n    # game/script.rpy:18\n    old \"Go left\"\n    new \"Aller à gauche\"\n"
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::core::engine::Engine;
+use crate::core::error::{AppError, AppResult};
+use crate::engines::registry::{criteria_score, EngineCapabilities, EngineRegistration};
+use crate::engines::rpg_maker_2k::files::{self, read_game_ini, DATABASE_FILE};
+use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
+use crate::models::language::Language;
+use crate::models::translation::{TextUnit, TranslationStatus};
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::rpg_maker_formatter::RpgMakerFormatter;
+use crate::utils::text::types::RawTextUnit;
+
+/// Implementation of the Engine trait for RPG Maker 2000/2003 games.
+///
+/// The database (`RPG_RT.ldb`) and maps (`Map####.lmu`) are LCF files read and
+/// written by the `lcf` module. Strings use the game's codepage, Shift-JIS
+/// unless `RPG_RT.ini` names another; translations it cannot represent are
+/// refused at injection.
+pub struct RpgMaker2kEngine {
+    detection_criteria: EngineCriteria,
+}
+
+impl RpgMaker2kEngine {
+    pub fn new() -> Self {
+        Self {
+            detection_criteria: Self::get_detection_criteria(),
+        }
+    }
+
+    /// A 2000/2003 project: `RPG_RT.ini` next to the `RPG_RT.ldb` database
+    pub fn get_detection_criteria() -> EngineCriteria {
+        EngineCriteria {
+            required_files: vec!["RPG_RT.ini".to_string(), DATABASE_FILE.to_string()],
+            required_folders: vec![],
+            extra_files: vec!["RPG_RT.exe".to_string(), "RPG_RT.lmt".to_string()],
+            export_data_roots: vec!["Map*.lmu".to_string()],
+        }
+    }
+
+    /// Registration of the engine: `RPG_RT.ini` and the LCF database are 2000/2003's own
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "RpgMaker2k",
+            engine_type: EngineType::RpgMaker2k,
+            create: || Box::new(Self::new()),
+            detect: |path| criteria_score(path, &Self::get_detection_criteria(), 80),
+            formatter: EngineTextFormatter::of::<RpgMakerFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: false,
+                minimal_export: true,
+                replacement_font: false,
+            },
+        }
+    }
+
+    /// The database and the maps, relative to the project root
+    fn data_files(&self, project_path: &Path) -> AppResult<Vec<String>> {
+        let entries = std::fs::read_dir(project_path).map_err(|e| {
+            AppError::FileSystem(format!("Failed to read {}: {}", project_path.display(), e))
+        })?;
+        let mut maps: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .filter(|name| files::is_map_file(name))
+            .collect();
+        maps.sort();
+
+        let mut data_files = vec![DATABASE_FILE.to_string()];
+        data_files.extend(maps);
+        Ok(data_files)
+    }
+
+    fn read_data_file(&self, project_path: &Path, file_path: &str) -> AppResult<Vec<u8>> {
+        std::fs::read(project_path.join(file_path))
+            .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", file_path, e)))
+    }
+}
+
+impl Engine for RpgMaker2kEngine {
+    fn load_project_info(
+        &self,
+        path: &Path,
+        source_language: Language,
+        target_language: Language,
+    ) -> AppResult<EngineInfo> {
+        let game_ini = read_game_ini(path);
+        let name = game_ini.title.unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("RPG Maker 2000 Project")
+                .to_string()
+        });
+        let version = self
+            .read_data_file(path, DATABASE_FILE)
+            .and_then(|database| files::database_version(&database))
+            .map(str::to_string)
+            .ok();
+
+        Ok(EngineInfo {
+            name,
+            path: path.to_path_buf(),
+            engine_type: EngineType::RpgMaker2k,
+            source_language,
+            target_language,
+            version,
+            detection_criteria: self.detection_criteria.clone(),
+            manifest_hash: None,
+            settings: None,
+        })
+    }
+
+    fn get_detection_criteria(&self) -> EngineCriteria {
+        self.detection_criteria.clone()
+    }
+
+    fn extract_raw_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<RawTextUnit>> {
+        let codepage = read_game_ini(&project_info.path).codepage();
+        let mut raw_units = Vec::new();
+        for file_path in self.data_files(&project_info.path)? {
+            let file = self.read_data_file(&project_info.path, &file_path)?;
+            let units = files::extract_file(&file_path, &file, codepage)
+                .map_err(|e| AppError::Parsing(format!("Failed to parse {}: {}", file_path, e)))?;
+            log::debug!("Extracted {} text units from {}", units.len(), file_path);
+            raw_units.extend(units);
+        }
+        Ok(raw_units)
+    }
+
+    fn inject_raw_text_units(
+        &self,
+        project_info: &EngineInfo,
+        raw_units: &[RawTextUnit],
+    ) -> AppResult<()> {
+        let codepage = read_game_ini(&project_info.path).codepage();
+
+        // Group units by the data file named in their ID
+        let mut units_by_file: BTreeMap<&str, Vec<&RawTextUnit>> = BTreeMap::new();
+        for unit in raw_units {
+            if let Some((file_path, _)) = unit.id.split_once('#') {
+                units_by_file.entry(file_path).or_default().push(unit);
+            }
+        }
+
+        for (file_path, units) in units_by_file {
+            if !project_info.path.join(file_path).exists() {
+                log::warn!("Skipping translations for missing file {}", file_path);
+                continue;
+            }
+            let file = self.read_data_file(&project_info.path, file_path)?;
+            let (new_file, replaced) = files::inject_file(file_path, &file, &units, codepage)?;
+            if let Some(new_file) = new_file {
+                std::fs::write(project_info.path.join(file_path), new_file).map_err(|e| {
+                    AppError::FileSystem(format!("Failed to write {}: {}", file_path, e))
+                })?;
+            }
+            log::info!("Injected {} translations into {}", replaced, file_path);
+        }
+        Ok(())
+    }
+
+    fn reconstruct_text_unit_id(
+        &self,
+        field_type: &str,
+        source_text: &str,
+        translated_text: &str,
+    ) -> AppResult<TextUnit> {
+        // Field types are "label:file:path", e.g. "message:Map0001.lmu:events[2].pages[1]..."
+        let mut parts = field_type.splitn(3, ':');
+        let (Some(label), Some(file_path), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(AppError::Other(format!(
+                "Invalid RPG Maker 2000 field_type format: {}",
+                field_type
+            )));
+        };
+
+        Ok(TextUnit {
+            id: files::unit_id(file_path, path),
+            source_text: source_text.to_string(),
+            translated_text: translated_text.to_string(),
+            field_type: field_type.to_string(),
+            status: TranslationStatus::MachineTranslated,
+            prompt_type: files::prompt_type_for(path, label),
+            position: None,
+            detected_language: None,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,778 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use encoding_rs::Encoding;
+
+use crate::core::error::{AppError, AppResult};
+use crate::engines::rpg_maker_2k::lcf::{self, Chunk, EventCommand};
+use crate::models::translation::{PromptType, UnitPosition};
+use crate::utils::text::types::RawTextUnit;
+
+/// Database file
+pub const DATABASE_FILE: &str = "RPG_RT.ldb";
+
+/// Show Message: the first line of a message
+const CODE_MESSAGE: u32 = 10110;
+
+/// Continuation line of a message
+const CODE_MESSAGE_LINE: u32 = 20110;
+
+/// Show Choice: every option joined by `/`
+const CODE_CHOICES: u32 = 10140;
+
+/// Branch of a Show Choice, holding the option text the player sees
+const CODE_CHOICE_OPTION: u32 = 20140;
+
+/// End of a Show Choice
+const CODE_CHOICES_END: u32 = 20141;
+
+/// An array of records and where its text sits
+struct Table {
+    /// Chunk holding the array
+    chunk: u32,
+
+    /// Name used in unit paths
+    name: &'static str,
+
+    /// String fields of each record: chunk ID and label
+    fields: &'static [(u32, &'static str)],
+
+    /// Chunk holding the record's event commands
+    commands: Option<u32>,
+
+    /// Chunk holding the record's event pages and, in each page, the chunk
+    /// holding its event commands
+    pages: Option<(u32, u32)>,
+}
+
+const NAME: &[(u32, &str)] = &[(0x01, "name")];
+
+/// Arrays of `RPG_RT.ldb`
+const DATABASE_TABLES: &[Table] = &[
+    Table {
+        chunk: 0x0B,
+        name: "actors",
+        fields: &[(0x01, "name"), (0x02, "title")],
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x0C,
+        name: "skills",
+        fields: &[
+            (0x01, "name"),
+            (0x02, "description"),
+            (0x03, "message1"),
+            (0x04, "message2"),
+            (0x07, "failure_message"),
+        ],
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x0D,
+        name: "items",
+        fields: &[(0x01, "name"), (0x02, "description")],
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x0E,
+        name: "enemies",
+        fields: NAME,
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x0F,
+        name: "troops",
+        fields: NAME,
+        commands: None,
+        pages: Some((0x0B, 0x0C)),
+    },
+    Table {
+        chunk: 0x10,
+        name: "terrains",
+        fields: NAME,
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x11,
+        name: "attributes",
+        fields: NAME,
+        commands: None,
+        pages: None,
+    },
+    Table {
+        chunk: 0x12,
+        name: "states",
+        fields: &[
+            (0x01, "name"),
+            (0x33, "message_actor"),
+            (0x34, "message_enemy"),
+            (0x35, "message_already"),
+            (0x36, "message_affected"),
+            (0x37, "message_recovery"),
+        ],
+        commands: None,
+        pages: None,
+    },
+    // Switch and variable names (0x17, 0x18) are editor labels, left out as for MV
+    Table {
+        chunk: 0x19,
+        name: "common_events",
+        fields: NAME,
+        commands: Some(0x16),
+        pages: None,
+    },
+    // RPG Maker 2003 only
+    Table {
+        chunk: 0x1E,
+        name: "classes",
+        fields: NAME,
+        commands: None,
+        pages: None,
+    },
+];
+
+/// Vocabulary struct of `RPG_RT.ldb`; every chunk in it is a string
+const TERMS_CHUNK: u32 = 0x15;
+
+/// System struct of `RPG_RT.ldb` and its database version field (2003 for
+/// RPG Maker 2003)
+const SYSTEM_CHUNK: u32 = 0x16;
+const SYSTEM_LDB_ID: u32 = 0x0A;
+
+/// Events array of a map
+const MAP_EVENTS: Table = Table {
+    chunk: 0x51,
+    name: "events",
+    fields: NAME,
+    commands: None,
+    pages: Some((0x05, 0x34)),
+};
+
+/// A string found while walking a file
+struct TextField<'a> {
+    /// Location of the string inside the file
+    path: String,
+    label: &'static str,
+    /// Bytes in the game's codepage
+    bytes: &'a [u8],
+    position: Option<UnitPosition>,
+}
+
+/// Called for every string; returning new bytes replaces it
+type Visitor<'v> = dyn FnMut(&TextField) -> AppResult<Option<Vec<u8>>> + 'v;
+
+/// `[RPG_RT]` section of `RPG_RT.ini`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameIni {
+    /// Window title of the game
+    pub title: Option<String>,
+
+    /// Codepage of the game's strings (`[EasyRPG] Encoding=`, Shift-JIS by default)
+    pub codepage: Option<&'static Encoding>,
+}
+
+/// Read `RPG_RT.ini`; the title is decoded with the game's codepage
+pub fn read_game_ini(project_path: &Path) -> GameIni {
+    let Ok(bytes) = std::fs::read(project_path.join("RPG_RT.ini")) else {
+        return GameIni::default();
+    };
+    let mut ini = GameIni::default();
+    let mut title = None;
+    for line in bytes.split(|b| *b == b'\n') {
+        let Some(split) = line.iter().position(|b| *b == b'=') else {
+            continue;
+        };
+        let key = String::from_utf8_lossy(&line[..split]);
+        let value = trim_ascii(&line[split + 1..]);
+        match key.trim() {
+            "GameTitle" if !value.is_empty() => title = Some(value.to_vec()),
+            "Encoding" => {
+                ini.codepage = std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .and_then(codepage_encoding)
+            }
+            _ => {}
+        }
+    }
+    ini.title = title.and_then(|t| lcf::decode(&t, ini.codepage()));
+    ini
+}
+
+/// `<[u8]>::trim_ascii`, which needs Rust 1.80
+fn trim_ascii(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
+
+impl GameIni {
+    /// Codepage to read and write strings with
+    pub fn codepage(&self) -> &'static Encoding {
+        self.codepage.unwrap_or(encoding_rs::SHIFT_JIS)
+    }
+}
+
+/// Encoding of a Windows codepage number
+fn codepage_encoding(codepage: u32) -> Option<&'static Encoding> {
+    Some(match codepage {
+        874 => encoding_rs::WINDOWS_874,
+        932 => encoding_rs::SHIFT_JIS,
+        936 => encoding_rs::GBK,
+        949 => encoding_rs::EUC_KR,
+        950 => encoding_rs::BIG5,
+        1250 => encoding_rs::WINDOWS_1250,
+        1251 => encoding_rs::WINDOWS_1251,
+        1252 => encoding_rs::WINDOWS_1252,
+        1253 => encoding_rs::WINDOWS_1253,
+        1254 => encoding_rs::WINDOWS_1254,
+        1255 => encoding_rs::WINDOWS_1255,
+        1256 => encoding_rs::WINDOWS_1256,
+        1257 => encoding_rs::WINDOWS_1257,
+        1258 => encoding_rs::WINDOWS_1258,
+        _ => return None,
+    })
+}
+
+/// Whether the file is a map (`Map0001.lmu`)
+pub fn is_map_file(file_path: &str) -> bool {
+    Path::new(file_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.strip_prefix("Map"))
+        .and_then(|n| n.strip_suffix(".lmu"))
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Unit ID of the string at `path` in a data file
+pub fn unit_id(file_path: &str, path: &str) -> String {
+    format!("{}#{}", file_path, path)
+}
+
+/// Prompt type of a unit from its path and field label
+pub fn prompt_type_for(path: &str, label: &str) -> PromptType {
+    if label == "message" || label == "choice" {
+        return PromptType::Dialogue;
+    }
+    match path.split('[').next().unwrap_or(path) {
+        "actors" | "enemies" | "events" | "troops" => PromptType::Character,
+        "classes" => PromptType::Class,
+        "skills" => PromptType::Skill,
+        "items" => PromptType::Equipment,
+        "states" => PromptType::State,
+        _ => PromptType::System,
+    }
+}
+
+/// RPG Maker version the database was saved with ("2000" or "2003")
+pub fn database_version(file: &[u8]) -> AppResult<&'static str> {
+    let chunks = lcf::read_struct(lcf::split_header(file, lcf::DATABASE_HEADER)?)?;
+    let ldb_id = lcf::chunk(&chunks, SYSTEM_CHUNK)
+        .map(|system| lcf::read_struct(&system.data))
+        .transpose()?
+        .and_then(|system| {
+            lcf::chunk(&system, SYSTEM_LDB_ID).and_then(|c| lcf::read_int(&c.data).ok())
+        });
+    Ok(if ldb_id == Some(2003) { "2003" } else { "2000" })
+}
+
+/// Extract the text of one data file
+///
+/// Unit IDs are `file#path`, where the path locates the string inside the
+/// LCF data (e.g. `skills[3].description`, `events[2].pages[1].commands[4]`);
+/// the field type is `label:file:path`.
+pub fn extract_file(
+    file_path: &str,
+    file: &[u8],
+    codepage: &'static Encoding,
+) -> AppResult<Vec<RawTextUnit>> {
+    let mut units = Vec::new();
+    walk_file(file_path, file, &mut |field| {
+        if let Some(text) = lcf::decode(field.bytes, codepage).filter(|t| !t.is_empty()) {
+            units.push(RawTextUnit {
+                id: unit_id(file_path, &field.path),
+                source_text: text,
+                field_type: format!("{}:{}:{}", field.label, file_path, field.path),
+                prompt_type: prompt_type_for(&field.path, field.label),
+                position: field.position.clone(),
+            });
+        }
+        Ok(None)
+    })?;
+    Ok(units)
+}
+
+/// Write translations into a data file; returns the new file, or `None` when
+/// no string changed, and the number of strings replaced
+///
+/// Fails when a translation has a character the codepage cannot represent.
+pub fn inject_file(
+    file_path: &str,
+    file: &[u8],
+    units: &[&RawTextUnit],
+    codepage: &'static Encoding,
+) -> AppResult<(Option<Vec<u8>>, usize)> {
+    let prefix = unit_id(file_path, "");
+    let translations: HashMap<&str, &str> = units
+        .iter()
+        .filter_map(|unit| Some((unit.id.strip_prefix(&prefix)?, unit.source_text.as_str())))
+        .collect();
+    let mut replaced = 0;
+    let new_file = walk_file(file_path, file, &mut |field| {
+        let Some(text) = translations.get(field.path.as_str()) else {
+            return Ok(None);
+        };
+        // Choices are stored joined with '/', so a slash would split one in two
+        let text = if field.label == "choice" {
+            text.replace('/', "／")
+        } else {
+            text.to_string()
+        };
+        let bytes = lcf::encode(&text, codepage).map_err(|c| {
+            AppError::Translation(format!(
+                "Translation of {} in {} contains '{}', which cannot be encoded in {}",
+                field.path,
+                file_path,
+                c,
+                codepage.name()
+            ))
+        })?;
+        replaced += 1;
+        Ok(Some(bytes))
+    })?;
+    Ok((new_file, replaced))
+}
+
+/// Visit every string of a database or map file; returns the rewritten file
+/// when the visitor replaced any
+fn walk_file(file_path: &str, file: &[u8], visit: &mut Visitor) -> AppResult<Option<Vec<u8>>> {
+    let (header, tables): (&[u8], &[Table]) = if is_map_file(file_path) {
+        (lcf::MAP_HEADER, std::slice::from_ref(&MAP_EVENTS))
+    } else {
+        (lcf::DATABASE_HEADER, DATABASE_TABLES)
+    };
+    let mut chunks = lcf::read_struct(lcf::split_header(file, header)?)?;
+    let mut changed = false;
+
+    for table in tables {
+        let Some(chunk) = lcf::chunk_mut(&mut chunks, table.chunk) else {
+            continue;
+        };
+        if let Some(data) = walk_table(file_path, table, &chunk.data, visit)? {
+            chunk.data = data;
+            changed = true;
+        }
+    }
+
+    if header == lcf::DATABASE_HEADER {
+        if let Some(chunk) = lcf::chunk_mut(&mut chunks, TERMS_CHUNK) {
+            let mut terms = lcf::read_struct(&chunk.data)?;
+            let fields: Vec<(u32, String)> = terms
+                .iter()
+                .map(|c| (c.id, format!("terms.{}", c.id)))
+                .collect();
+            if walk_fields(&mut terms, &fields, "term", None, visit)? {
+                chunk.data = lcf::write_struct(&terms);
+                changed = true;
+            }
+        }
+    }
+
+    Ok(changed.then(|| lcf::join_header(header, &lcf::write_struct(&chunks))))
+}
+
+/// Visit the string chunks listed in `fields` (chunk ID and path)
+fn walk_fields(
+    chunks: &mut [Chunk],
+    fields: &[(u32, String)],
+    label: &'static str,
+    position: Option<UnitPosition>,
+    visit: &mut Visitor,
+) -> AppResult<bool> {
+    let mut changed = false;
+    for (id, path) in fields {
+        let Some(chunk) = lcf::chunk_mut(chunks, *id) else {
+            continue;
+        };
+        let field = TextField {
+            path: path.clone(),
+            label,
+            bytes: &chunk.data,
+            position: position.clone(),
+        };
+        if let Some(bytes) = visit(&field)? {
+            chunk.data = bytes;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+fn walk_table(
+    file_path: &str,
+    table: &Table,
+    data: &[u8],
+    visit: &mut Visitor,
+) -> AppResult<Option<Vec<u8>>> {
+    let mut records = lcf::read_array(data)?;
+    let mut changed = false;
+
+    for record in &mut records {
+        let base = format!("{}[{}]", table.name, record.index);
+        for (id, label) in table.fields {
+            let fields = [(*id, format!("{}.{}", base, label))];
+            changed |= walk_fields(&mut record.chunks, &fields, label, None, visit)?;
+        }
+
+        let event_ref = unit_id(file_path, &base);
+        if let Some(list_chunk) = table.commands {
+            changed |=
+                walk_command_chunk(&mut record.chunks, list_chunk, &base, &event_ref, 0, visit)?;
+        }
+        if let Some((pages_chunk, list_chunk)) = table.pages {
+            let Some(chunk) = lcf::chunk_mut(&mut record.chunks, pages_chunk) else {
+                continue;
+            };
+            let mut pages = lcf::read_array(&chunk.data)?;
+            let mut pages_changed = false;
+            for (page_index, page) in pages.iter_mut().enumerate() {
+                let owner = format!("{}.pages[{}]", base, page.index);
+                pages_changed |= walk_command_chunk(
+                    &mut page.chunks,
+                    list_chunk,
+                    &owner,
+                    &event_ref,
+                    page_index,
+                    visit,
+                )?;
+            }
+            if pages_changed {
+                chunk.data = lcf::write_array(&pages);
+                changed = true;
+            }
+        }
+    }
+
+    Ok(changed.then(|| lcf::write_array(&records)))
+}
+
+/// Visit the dialogue of an event command list; the chunk just before the
+/// list holds its byte size and is updated with it
+fn walk_command_chunk(
+    chunks: &mut [Chunk],
+    list_chunk: u32,
+    owner: &str,
+    event_ref: &str,
+    page_index: usize,
+    visit: &mut Visitor,
+) -> AppResult<bool> {
+    let Some(chunk) = lcf::chunk_mut(chunks, list_chunk) else {
+        return Ok(false);
+    };
+    let mut commands = lcf::read_commands(&chunk.data)?;
+    if !walk_commands(&mut commands, owner, event_ref, page_index, visit)? {
+        return Ok(false);
+    }
+    chunk.data = lcf::write_commands(&commands);
+    let size = chunk.data.len() as u32;
+    if let Some(size_chunk) = lcf::chunk_mut(chunks, list_chunk - 1) {
+        size_chunk.data = lcf::int_bytes(size);
+    }
+    Ok(true)
+}
+
+/// Message lines (10110/20110) and choices (10140); translated choices are
+/// also written to the options (20140) the player sees
+fn walk_commands(
+    commands: &mut [EventCommand],
+    owner: &str,
+    event_ref: &str,
+    page_index: usize,
+    visit: &mut Visitor,
+) -> AppResult<bool> {
+    let mut changed = false;
+    for index in 0..commands.len() {
+        let path = format!("{}.commands[{}]", owner, index);
+        let position = Some(UnitPosition {
+            event_ref: event_ref.to_string(),
+            page_index: page_index as u32,
+            command_index: index as u32,
+            speaker: None,
+        });
+        match commands[index].code {
+            CODE_MESSAGE | CODE_MESSAGE_LINE => {
+                let field = TextField {
+                    path,
+                    label: "message",
+                    bytes: &commands[index].string,
+                    position,
+                };
+                if let Some(bytes) = visit(&field)? {
+                    commands[index].string = bytes;
+                    changed = true;
+                }
+            }
+            CODE_CHOICES => {
+                let mut options: Vec<Vec<u8>> = commands[index]
+                    .string
+                    .split(|b| *b == b'/')
+                    .map(<[u8]>::to_vec)
+                    .collect();
+                let mut options_changed = false;
+                for (option_index, option) in options.iter_mut().enumerate() {
+                    let field = TextField {
+                        path: format!("{}.choice[{}]", path, option_index),
+                        label: "choice",
+                        bytes: option,
+                        position: position.clone(),
+                    };
+                    if let Some(bytes) = visit(&field)? {
+                        *option = bytes;
+                        options_changed = true;
+                    }
+                }
+                if options_changed {
+                    commands[index].string = options.join(&b'/');
+                    update_choice_options(commands, index, &options);
+                    changed = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(changed)
+}
+
+/// Copy option texts into the branches of the Show Choice at `index`
+fn update_choice_options(commands: &mut [EventCommand], index: usize, options: &[Vec<u8>]) {
+    let indent = commands[index].indent;
+    for command in commands[index + 1..].iter_mut() {
+        if command.indent != indent {
+            continue;
+        }
+        match command.code {
+            CODE_CHOICE_OPTION => {
+                let option = command
+                    .parameters
+                    .first()
+                    .and_then(|i| options.get(*i as usize));
+                if let Some(option) = option {
+                    command.string = option.clone();
+                }
+            }
+            CODE_CHOICES_END => break,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::rpg_maker_2k::lcf::Element;
+
+    const SJIS: &Encoding = encoding_rs::SHIFT_JIS;
+
+    fn element(index: u32, chunks: Vec<Chunk>) -> Element {
+        Element { index, chunks }
+    }
+
+    fn string(id: u32, text: &str) -> Chunk {
+        Chunk {
+            id,
+            data: lcf::encode(text, SJIS).unwrap(),
+        }
+    }
+
+    fn command(code: u32, indent: u32, text: &str, parameters: Vec<u32>) -> EventCommand {
+        EventCommand {
+            code,
+            indent,
+            string: lcf::encode(text, SJIS).unwrap(),
+            parameters,
+        }
+    }
+
+    fn map_file() -> Vec<u8> {
+        let commands = lcf::write_commands(&[
+            command(10110, 0, "おはよう！", vec![]),
+            command(20110, 0, "いい天気だね。", vec![]),
+            command(10140, 0, "はい/いいえ", vec![3]),
+            command(20140, 0, "はい", vec![0]),
+            command(20140, 0, "いいえ", vec![1]),
+            command(20141, 0, "", vec![]),
+            command(0, 0, "", vec![]),
+        ]);
+        let page = element(
+            1,
+            vec![
+                Chunk {
+                    id: 0x33,
+                    data: lcf::int_bytes(commands.len() as u32),
+                },
+                Chunk {
+                    id: 0x34,
+                    data: commands,
+                },
+            ],
+        );
+        let event = element(
+            5,
+            vec![
+                string(0x01, "村人"),
+                Chunk {
+                    id: 0x05,
+                    data: lcf::write_array(&[page]),
+                },
+            ],
+        );
+        let body = lcf::write_struct(&[
+            Chunk {
+                id: 0x01,
+                data: lcf::int_bytes(1),
+            },
+            Chunk {
+                id: 0x51,
+                data: lcf::write_array(&[event]),
+            },
+        ]);
+        lcf::join_header(lcf::MAP_HEADER, &body)
+    }
+
+    #[test]
+    fn test_map_messages_and_choices_round_trip() {
+        let file_path = "Map0001.lmu";
+        let file = map_file();
+        let units = extract_file(file_path, &file, SJIS).unwrap();
+        let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "Map0001.lmu#events[5].name",
+                "Map0001.lmu#events[5].pages[1].commands[0]",
+                "Map0001.lmu#events[5].pages[1].commands[1]",
+                "Map0001.lmu#events[5].pages[1].commands[2].choice[0]",
+                "Map0001.lmu#events[5].pages[1].commands[2].choice[1]",
+            ]
+        );
+        assert_eq!(units[1].source_text, "おはよう！");
+        assert_eq!(units[3].prompt_type, PromptType::Dialogue);
+        assert_eq!(
+            units[1].position.as_ref().map(|p| p.event_ref.as_str()),
+            Some("Map0001.lmu#events[5]")
+        );
+
+        // Nothing to replace leaves the file alone
+        assert_eq!(inject_file(file_path, &file, &[], SJIS).unwrap(), (None, 0));
+
+        let mut message = units[1].clone();
+        message.source_text = "Good morning!".to_string();
+        let mut choice = units[3].clone();
+        choice.source_text = "Yes".to_string();
+        let (new_file, replaced) =
+            inject_file(file_path, &file, &[&message, &choice], SJIS).unwrap();
+        assert_eq!(replaced, 2);
+        let new_file = new_file.unwrap();
+
+        let texts: Vec<String> = extract_file(file_path, &new_file, SJIS)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.source_text)
+            .collect();
+        assert_eq!(texts[1], "Good morning!");
+        assert_eq!(texts[3], "Yes");
+        assert_eq!(texts[4], "いいえ");
+
+        // The size chunk and the option branch follow the new text
+        let chunks =
+            lcf::read_struct(lcf::split_header(&new_file, lcf::MAP_HEADER).unwrap()).unwrap();
+        let events = lcf::read_array(&lcf::chunk(&chunks, 0x51).unwrap().data).unwrap();
+        let pages = lcf::read_array(&lcf::chunk(&events[0].chunks, 0x05).unwrap().data).unwrap();
+        let list = &lcf::chunk(&pages[0].chunks, 0x34).unwrap().data;
+        let size = lcf::read_int(&lcf::chunk(&pages[0].chunks, 0x33).unwrap().data).unwrap();
+        assert_eq!(size as usize, list.len());
+        let commands = lcf::read_commands(list).unwrap();
+        assert_eq!(commands[3].string, b"Yes");
+    }
+
+    #[test]
+    fn test_slash_in_choice_does_not_split_it() {
+        let file_path = "Map0001.lmu";
+        let file = map_file();
+        let mut choice = extract_file(file_path, &file, SJIS).unwrap()[3].clone();
+        choice.source_text = "Yes/Sure".to_string();
+        let new_file = inject_file(file_path, &file, &[&choice], SJIS)
+            .unwrap()
+            .0
+            .unwrap();
+
+        let texts: Vec<String> = extract_file(file_path, &new_file, SJIS)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.source_text)
+            .collect();
+        assert_eq!(texts.len(), 5);
+        assert_eq!(texts[3], "Yes／Sure");
+        assert_eq!(texts[4], "いいえ");
+    }
+
+    #[test]
+    fn test_unencodable_translation_is_an_error() {
+        let file_path = "Map0001.lmu";
+        let file = map_file();
+        let mut unit = extract_file(file_path, &file, SJIS).unwrap()[1].clone();
+        unit.source_text = "Bonjour, ça va ?".to_string();
+        let error = inject_file(file_path, &file, &[&unit], SJIS).unwrap_err();
+        assert!(error.to_string().contains("'ç'"), "{}", error);
+    }
+
+    #[test]
+    fn test_database_tables_and_terms() {
+        let actor = element(1, vec![string(0x01, "アレックス"), string(0x02, "戦士")]);
+        let terms = lcf::write_struct(&[string(0x01, "が出現！"), string(0x5F, "ゴールド")]);
+        let system = lcf::write_struct(&[Chunk {
+            id: SYSTEM_LDB_ID,
+            data: lcf::int_bytes(2003),
+        }]);
+        let body = lcf::write_struct(&[
+            Chunk {
+                id: 0x0B,
+                data: lcf::write_array(&[actor]),
+            },
+            Chunk {
+                id: TERMS_CHUNK,
+                data: terms,
+            },
+            Chunk {
+                id: SYSTEM_CHUNK,
+                data: system,
+            },
+        ]);
+        let file = lcf::join_header(lcf::DATABASE_HEADER, &body);
+        let units = extract_file(DATABASE_FILE, &file, SJIS).unwrap();
+        let fields: Vec<&str> = units.iter().map(|u| u.field_type.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "name:RPG_RT.ldb:actors[1].name",
+                "title:RPG_RT.ldb:actors[1].title",
+                "term:RPG_RT.ldb:terms.1",
+                "term:RPG_RT.ldb:terms.95",
+            ]
+        );
+        assert_eq!(units[0].prompt_type, PromptType::Character);
+        assert_eq!(units[2].prompt_type, PromptType::System);
+        assert_eq!(database_version(&file).unwrap(), "2003");
+    }
+}
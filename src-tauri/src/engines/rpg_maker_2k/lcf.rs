@@ -0,0 +1,346 @@
+//! LCF reader and writer for RPG Maker 2000/2003 data files.
+//!
+//! LCF files (`RPG_RT.ldb`, `Map####.lmu`) start with a header string and hold
+//! a tree of chunks: a struct is a list of `id, size, data` chunks ended by a
+//! zero ID, an array is a count followed by indexed structs, and event command
+//! lists are packed records. All integers are BER compressed (7 bits per byte,
+//! high bit set on every byte but the last).
+//!
+//! Chunks are decoded only where text lives; everything else is kept as raw
+//! bytes, so a file written back without edits is unchanged.
+
+use encoding_rs::Encoding;
+
+use crate::core::error::{AppError, AppResult};
+
+/// Header of the database file
+pub const DATABASE_HEADER: &[u8] = b"LcfDataBase";
+
+/// Header of map files
+pub const MAP_HEADER: &[u8] = b"LcfMapUnit";
+
+/// One chunk of a struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+/// One element of an array: its index (1-based database ID) and fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub index: u32,
+    pub chunks: Vec<Chunk>,
+}
+
+/// One event command
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventCommand {
+    pub code: u32,
+    pub indent: u32,
+    /// Text argument in the game's codepage
+    pub string: Vec<u8>,
+    /// Integer arguments; negative values are stored as their two's complement
+    pub parameters: Vec<u32>,
+}
+
+/// Chunk with the given ID
+pub fn chunk(chunks: &[Chunk], id: u32) -> Option<&Chunk> {
+    chunks.iter().find(|c| c.id == id)
+}
+
+/// Mutable chunk with the given ID
+pub fn chunk_mut(chunks: &mut [Chunk], id: u32) -> Option<&mut Chunk> {
+    chunks.iter_mut().find(|c| c.id == id)
+}
+
+/// Split a file into its header and body, checking the header
+pub fn split_header<'a>(bytes: &'a [u8], header: &[u8]) -> AppResult<&'a [u8]> {
+    let mut reader = Reader::new(bytes);
+    let found = reader.bytes_with_length()?;
+    if found != header {
+        return Err(AppError::Parsing(format!(
+            "Not an LCF file: expected header {}, found {}",
+            String::from_utf8_lossy(header),
+            String::from_utf8_lossy(found)
+        )));
+    }
+    Ok(reader.rest())
+}
+
+/// Prefix a body with its header
+pub fn join_header(header: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(header.len() + body.len() + 1);
+    write_int(&mut out, header.len() as u32);
+    out.extend_from_slice(header);
+    out.extend_from_slice(body);
+    out
+}
+
+/// Read a struct: chunks up to a zero ID or the end of the data
+pub fn read_struct(data: &[u8]) -> AppResult<Vec<Chunk>> {
+    let mut reader = Reader::new(data);
+    read_struct_from(&mut reader)
+}
+
+fn read_struct_from(reader: &mut Reader) -> AppResult<Vec<Chunk>> {
+    let mut chunks = Vec::new();
+    while !reader.at_end() {
+        let id = reader.int()?;
+        if id == 0 {
+            break;
+        }
+        let data = reader.bytes_with_length()?.to_vec();
+        chunks.push(Chunk { id, data });
+    }
+    Ok(chunks)
+}
+
+/// Write a struct with its terminating zero
+pub fn write_struct(chunks: &[Chunk]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_struct_into(&mut out, chunks);
+    out
+}
+
+fn write_struct_into(out: &mut Vec<u8>, chunks: &[Chunk]) {
+    for chunk in chunks {
+        write_int(out, chunk.id);
+        write_int(out, chunk.data.len() as u32);
+        out.extend_from_slice(&chunk.data);
+    }
+    write_int(out, 0);
+}
+
+/// Read an array of structs
+pub fn read_array(data: &[u8]) -> AppResult<Vec<Element>> {
+    let mut reader = Reader::new(data);
+    let count = reader.int()?;
+    let mut elements = Vec::new();
+    for _ in 0..count {
+        let index = reader.int()?;
+        let chunks = read_struct_from(&mut reader)?;
+        elements.push(Element { index, chunks });
+    }
+    Ok(elements)
+}
+
+/// Write an array of structs
+pub fn write_array(elements: &[Element]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_int(&mut out, elements.len() as u32);
+    for element in elements {
+        write_int(&mut out, element.index);
+        write_struct_into(&mut out, &element.chunks);
+    }
+    out
+}
+
+/// Read an event command list, including its closing empty command
+pub fn read_commands(data: &[u8]) -> AppResult<Vec<EventCommand>> {
+    let mut reader = Reader::new(data);
+    let mut commands = Vec::new();
+    while !reader.at_end() {
+        let code = reader.int()?;
+        let indent = reader.int()?;
+        let string = reader.bytes_with_length()?.to_vec();
+        let count = reader.int()?;
+        let parameters = (0..count)
+            .map(|_| reader.int())
+            .collect::<AppResult<Vec<u32>>>()?;
+        commands.push(EventCommand {
+            code,
+            indent,
+            string,
+            parameters,
+        });
+    }
+    Ok(commands)
+}
+
+/// Write an event command list
+pub fn write_commands(commands: &[EventCommand]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for command in commands {
+        write_int(&mut out, command.code);
+        write_int(&mut out, command.indent);
+        write_int(&mut out, command.string.len() as u32);
+        out.extend_from_slice(&command.string);
+        write_int(&mut out, command.parameters.len() as u32);
+        for parameter in &command.parameters {
+            write_int(&mut out, *parameter);
+        }
+    }
+    out
+}
+
+/// Read a chunk holding a single integer
+pub fn read_int(data: &[u8]) -> AppResult<u32> {
+    Reader::new(data).int()
+}
+
+/// Append a BER compressed integer
+pub fn write_int(out: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+/// BER compressed integer as a chunk payload
+pub fn int_bytes(value: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_int(&mut out, value);
+    out
+}
+
+/// Decode a string from the game's codepage; `None` if the bytes are not
+/// valid in it, so such strings are never rewritten
+pub fn decode(bytes: &[u8], codepage: &'static Encoding) -> Option<String> {
+    let (text, had_errors) = codepage.decode_without_bom_handling(bytes);
+    (!had_errors).then(|| text.into_owned())
+}
+
+/// Encode a string into the game's codepage, naming the first character it
+/// cannot represent
+pub fn encode(text: &str, codepage: &'static Encoding) -> Result<Vec<u8>, char> {
+    let (bytes, _, had_errors) = codepage.encode(text);
+    if !had_errors {
+        return Ok(bytes.into_owned());
+    }
+    let mut buffer = [0u8; 4];
+    let unencodable = text
+        .chars()
+        .find(|c| codepage.encode(c.encode_utf8(&mut buffer)).2)
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    Err(unencodable)
+}
+
+/// Cursor over LCF data
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    fn byte(&mut self) -> AppResult<u8> {
+        let byte = *self.data.get(self.pos).ok_or_else(|| {
+            AppError::Parsing(format!("Unexpected end of LCF data at byte {}", self.pos))
+        })?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn int(&mut self) -> AppResult<u32> {
+        let mut value: u32 = 0;
+        for _ in 0..5 {
+            let byte = self.byte()?;
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(AppError::Parsing(format!(
+            "Invalid LCF integer ending at byte {}",
+            self.pos
+        )))
+    }
+
+    fn bytes_with_length(&mut self) -> AppResult<&'a [u8]> {
+        let length = self.int()? as usize;
+        let end = self
+            .pos
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| {
+                AppError::Parsing(format!(
+                    "LCF chunk of {} bytes at byte {} runs past the end of the data",
+                    length, self.pos
+                ))
+            })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ber_integers() {
+        for value in [0, 1, 127, 128, 10110, 20110, 0xFFFF_FFFF] {
+            let bytes = int_bytes(value);
+            assert_eq!(read_int(&bytes).unwrap(), value);
+        }
+        assert_eq!(int_bytes(10110), vec![0xCE, 0x7E]);
+        assert_eq!(int_bytes(0xFFFF_FFFF), vec![0x8F, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn test_struct_array_and_commands_round_trip() {
+        let commands = vec![
+            EventCommand {
+                code: 10110,
+                indent: 0,
+                string: encode("こんにちは", encoding_rs::SHIFT_JIS).unwrap(),
+                parameters: vec![],
+            },
+            EventCommand {
+                code: 0,
+                indent: 0,
+                string: vec![],
+                parameters: vec![],
+            },
+        ];
+        let command_bytes = write_commands(&commands);
+        assert_eq!(read_commands(&command_bytes).unwrap(), commands);
+        assert!(command_bytes.ends_with(&[0, 0, 0, 0]));
+
+        let elements = vec![Element {
+            index: 1,
+            chunks: vec![
+                Chunk {
+                    id: 0x01,
+                    data: b"Alex".to_vec(),
+                },
+                Chunk {
+                    id: 0x16,
+                    data: command_bytes,
+                },
+            ],
+        }];
+        let file = join_header(DATABASE_HEADER, &write_array(&elements));
+        let body = split_header(&file, DATABASE_HEADER).unwrap();
+        assert_eq!(read_array(body).unwrap(), elements);
+        assert!(split_header(&file, MAP_HEADER).is_err());
+    }
+
+    #[test]
+    fn test_codepage_errors_name_the_character() {
+        assert_eq!(
+            decode(
+                &encode("勇者", encoding_rs::SHIFT_JIS).unwrap(),
+                encoding_rs::SHIFT_JIS
+            ),
+            Some("勇者".to_string())
+        );
+        assert_eq!(encode("Café", encoding_rs::SHIFT_JIS), Err('é'));
+    }
+}
@@ -11,9 +11,13 @@ use crate::engines::rpg_maker_mv::files::{
     actors, armors, classes, common_events, enemies, items, maps, maps_infos, skills, states,
     system, troops, weapons,
 };
+use crate::engines::rpg_maker_mv::text_fit;
+use crate::engines::registry::{criteria_score, EngineCapabilities, EngineRegistration};
 use crate::models::engine::{EngineCriteria, EngineInfo, EngineType, GameDataFile};
 use crate::models::language::Language;
 use crate::models::translation::TextUnit;
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::rpg_maker_formatter::RpgMakerFormatter;
 use crate::utils::text::types::RawTextUnit;
 
 /// Implementation of the Engine trait for RPG Maker MV games.
@@ -57,6 +61,22 @@ impl RpgMakerMvEngine {
         }
     }
 
+    /// Registration of the engine: `www/js/rpg_core.js` is MV's own
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "RpgMakerMv",
+            engine_type: EngineType::RpgMakerMv,
+            create: || Box::new(Self::new()),
+            detect: |path| criteria_score(path, &Self::get_detection_criteria(), 80),
+            formatter: EngineTextFormatter::of::<RpgMakerFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: true,
+                minimal_export: true,
+                replacement_font: true,
+            },
+        }
+    }
+
     /// Reads and parses the package.json file to extract project metadata.
     ///
     /// # Arguments
@@ -450,6 +470,9 @@ impl RpgMakerMvEngine {
             "system",
         )?;
 
+        // Re-wrap translated messages that no longer fit the message window
+        text_fit::fit_message_windows(project_info, "www/data", text_units)?;
+
         // info!("Translation injection completed");
         Ok(())
     }
@@ -491,6 +514,7 @@ impl Engine for RpgMakerMvEngine {
             version,
             detection_criteria: self.detection_criteria.clone(),
             manifest_hash: None,
+            settings: None,
         })
     }
 
@@ -498,6 +522,10 @@ impl Engine for RpgMakerMvEngine {
         self.detection_criteria.clone()
     }
 
+    fn extract_game_data_files(&self, project_info: &EngineInfo) -> AppResult<Vec<GameDataFile>> {
+        RpgMakerMvEngine::extract_game_data_files(self, project_info)
+    }
+
     fn reconstruct_text_unit_id(
         &self,
         field_type: &str,
@@ -543,6 +571,8 @@ impl Engine for RpgMakerMvEngine {
                 status: crate::models::translation::TranslationStatus::MachineTranslated,
                 field_type: field_type.to_string(),
                 prompt_type: crate::models::translation::PromptType::Dialogue,
+                position: None,
+                detected_language: None,
             });
         }
 
@@ -590,6 +620,8 @@ impl Engine for RpgMakerMvEngine {
                 status: crate::models::translation::TranslationStatus::MachineTranslated,
                 field_type: field_type.to_string(),
                 prompt_type: crate::models::translation::PromptType::Dialogue,
+                position: None,
+                detected_language: None,
             });
         }
 
@@ -695,6 +727,8 @@ impl Engine for RpgMakerMvEngine {
             status: crate::models::translation::TranslationStatus::MachineTranslated, // Default for export
             field_type: field_type.to_string(),
             prompt_type: crate::models::translation::PromptType::Character, // Default, can be refined
+            position: None,
+            detected_language: None,
         })
     }
 
@@ -715,6 +749,7 @@ impl Engine for RpgMakerMvEngine {
                     source_text: text_unit.source_text.clone(),
                     field_type: text_unit.field_type.clone(),
                     prompt_type: text_unit.prompt_type,
+                    position: text_unit.position.clone(),
                 });
             }
         }
@@ -737,6 +772,8 @@ impl Engine for RpgMakerMvEngine {
                 field_type: raw_unit.field_type.clone(),
                 status: crate::models::translation::TranslationStatus::NotTranslated,
                 prompt_type: raw_unit.prompt_type,
+                position: raw_unit.position.clone(),
+                detected_language: None,
             })
             .collect();
 
@@ -1,6 +1,6 @@
 use crate::core::error::{AppError, AppResult};
 use crate::models::engine::GameDataFile;
-use crate::models::translation::{PromptType, TextUnit, TranslationStatus};
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus, UnitPosition};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -32,6 +32,8 @@ pub fn extract_text_units_for_object(
                 field_type: format!("{}:{}:{}", field, file_path, index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -260,7 +262,8 @@ pub struct EventCommand {
 /// * `object_type` - Type of object (e.g., "common_event", "troop")
 /// * `object_id` - ID of the object
 /// * `commands` - Vector of event commands to process
-/// * `_file_path` - File path for context (unused but kept for consistency)
+/// * `page_index` - Page the command list belongs to (0 for single-list objects)
+/// * `file_path` - Relative path of the file, used in the unit field types and event references
 ///
 /// # Returns
 /// * `Vec<TextUnit>` - Vector of extracted text units
@@ -268,16 +271,33 @@ pub fn extract_text_units_from_event_commands(
     object_type: &str,
     object_id: i32,
     commands: &[EventCommand],
-    _file_path: &str,
+    page_index: usize,
+    file_path: &str,
 ) -> Vec<TextUnit> {
     let mut text_units = Vec::new();
+    let event_ref = format!("{}#{}_{}", file_path, object_type, object_id);
+    let position = |command_index: usize, speaker: Option<String>| UnitPosition {
+        event_ref: event_ref.clone(),
+        page_index: page_index as u32,
+        command_index: command_index as u32,
+        speaker,
+    };
+    // Speaker name of the current message window (MZ stores it in parameter 4)
+    let mut speaker: Option<String> = None;
 
     for (command_index, command) in commands.iter().enumerate() {
         match command.code {
             101 => {
                 // Show Text - Message window attributes
-                // Parameters: [0] = window type, [1] = position, [2] = background, [3] = position type
+                // Parameters: [0] = face name, [1] = face index, [2] = background, [3] = position type,
+                // [4] = speaker name (MZ only)
                 // No translatable text in this command
+                speaker = command
+                    .parameters
+                    .get(4)
+                    .and_then(|v| v.as_str())
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| name.to_string());
             }
             401 => {
                 // Show Text - Message content
@@ -295,10 +315,12 @@ pub fn extract_text_units_from_event_commands(
                                 translated_text: String::new(),
                                 field_type: format!(
                                     "message:{}:{}:{}",
-                                    _file_path, object_id, command_index
+                                    file_path, object_id, command_index
                                 ),
                                 status: TranslationStatus::NotTranslated,
                                 prompt_type: PromptType::Dialogue,
+                                position: Some(position(command_index, speaker.clone())),
+                                detected_language: None,
                             });
                         }
                     }
@@ -323,10 +345,12 @@ pub fn extract_text_units_from_event_commands(
                                         translated_text: String::new(),
                                         field_type: format!(
                                             "choice:{}:{}:{}:{}",
-                                            _file_path, object_id, command_index, choice_index
+                                            file_path, object_id, command_index, choice_index
                                         ),
                                         status: TranslationStatus::NotTranslated,
                                         prompt_type: PromptType::Dialogue,
+                                        position: Some(position(command_index, None)),
+                                        detected_language: None,
                                     });
                                 }
                             }
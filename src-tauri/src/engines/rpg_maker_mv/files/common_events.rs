@@ -81,6 +81,7 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 "common_event",
                 common_event.id,
                 &common_event.list,
+                0,
                 file_path,
             ));
 
@@ -268,11 +268,12 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 ));
 
                 // Extract text from event pages using common function
-                for (_page_index, page) in event.pages.iter().enumerate() {
+                for (page_index, page) in event.pages.iter().enumerate() {
                     text_units.extend(extract_text_units_from_event_commands(
                         &format!("map_{}_event", map_id),
                         event.id,
                         &page.list,
+                        page_index,
                         file_path,
                     ));
                 }
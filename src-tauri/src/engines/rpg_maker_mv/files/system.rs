@@ -127,6 +127,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
             field_type: "gameTitle:www/data/System.json:0".to_string(),
             status: TranslationStatus::NotTranslated,
             prompt_type: PromptType::Character,
+            position: None,
+            detected_language: None,
         });
     }
 
@@ -139,6 +141,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
             field_type: "currencyUnit:www/data/System.json:0".to_string(),
             status: TranslationStatus::NotTranslated,
             prompt_type: PromptType::System,
+            position: None,
+            detected_language: None,
         });
     }
 
@@ -152,6 +156,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("armorTypes[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Equipment,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -166,6 +172,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("elements[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -180,6 +188,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("equipTypes[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Equipment,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -194,6 +204,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("skillTypes[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Skill,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -208,6 +220,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("weaponTypes[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Equipment,
+                position: None,
+                detected_language: None,
             });
         }
     }*/
@@ -222,6 +236,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("switches[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }*/
@@ -236,6 +252,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("variables[{}]:www/data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }*/
@@ -251,6 +269,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                     field_type: format!("terms.basic[{}]:www/data/System.json:0", index),
                     status: TranslationStatus::NotTranslated,
                     prompt_type: PromptType::System,
+                    position: None,
+                    detected_language: None,
                 });
             }
         }
@@ -267,6 +287,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                     field_type: format!("terms.commands[{}]:www/data/System.json:0", index),
                     status: TranslationStatus::NotTranslated,
                     prompt_type: PromptType::System,
+                    position: None,
+                    detected_language: None,
                 });
             }
         }
@@ -283,6 +305,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                     field_type: format!("terms.params[{}]:www/data/System.json:0", index),
                     status: TranslationStatus::NotTranslated,
                     prompt_type: PromptType::System,
+                    position: None,
+                    detected_language: None,
                 });
             }
         }
@@ -298,6 +322,8 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
                 field_type: format!("terms.messages.{}:www/data/System.json:0", key),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
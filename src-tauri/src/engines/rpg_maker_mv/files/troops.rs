@@ -127,14 +127,17 @@ pub fn extract_text(project_path: &Path, file_path: &str) -> AppResult<GameDataF
             ));
         }
 
-        // Extract text from all pages' event commands
+        // Extract text from all pages' event commands; like map event pages, they
+        // share the troop's unit ids (`troop_<id>_message_<index>`) so the ids can be
+        // rebuilt from the field type
         for (page_index, page) in troop.pages.iter().enumerate() {
             let common_commands: Vec<CommonEventCommand> =
                 page.list.iter().map(|cmd| cmd.clone().into()).collect();
             text_units.extend(extract_text_units_from_event_commands(
-                &format!("troop_{}_page_{}", troop.id, page_index),
+                "troop",
                 troop.id,
                 &common_commands,
+                page_index,
                 file_path,
             ));
         }
@@ -183,11 +186,11 @@ pub fn inject_translations(
         );
 
         // Update text in all pages' event commands
-        for (page_index, page) in troop.pages.iter_mut().enumerate() {
+        for page in troop.pages.iter_mut() {
             let mut common_commands: Vec<CommonEventCommand> =
                 page.list.iter().map(|cmd| cmd.clone().into()).collect();
             inject_text_units_into_event_commands(
-                &format!("troop_{}_page_{}", troop.id, page_index),
+                "troop",
                 troop.id,
                 &mut common_commands,
                 text_unit_map,
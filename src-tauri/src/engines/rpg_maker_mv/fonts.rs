@@ -0,0 +1,260 @@
+//! Game font lookup for RPG Maker MV and MZ.
+//!
+//! MV loads its main font through `www/fonts/gamefont.css`; MZ names it in
+//! `System.json` under `advanced.mainFontFilename` and keeps it in `fonts/`.
+//! Both fall back to the stock M+ 1m font when nothing else is configured.
+//!
+//! Besides locating the font, this module checks translated text against the
+//! font's glyphs and installs a replacement font into an exported copy.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::core::error::{AppError, AppResult};
+use crate::db::translation::model::TextUnitRecord;
+use crate::models::engine::EngineType;
+use crate::utils::fonts::FontMetrics;
+
+/// `url(...)` reference inside `gamefont.css`.
+static CSS_URL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"url\(\s*["']?([^"')]+)["']?\s*\)"#).unwrap());
+
+/// Stock message font size of MV (`Window_Base.standardFontSize`)
+pub const MV_DEFAULT_FONT_SIZE: u32 = 28;
+
+/// Stock message font size of MZ (`System.json` `advanced.fontSize`)
+pub const MZ_DEFAULT_FONT_SIZE: u32 = 26;
+
+/// The main game font of a project as configured by the engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameFont {
+    /// Directory fonts are loaded from
+    pub fonts_dir: PathBuf,
+
+    /// File name of the main font inside `fonts_dir`
+    pub file_name: String,
+
+    /// Font size used for message text
+    pub font_size: u32,
+}
+
+impl GameFont {
+    pub fn path(&self) -> PathBuf {
+        self.fonts_dir.join(&self.file_name)
+    }
+}
+
+/// Fonts directory relative to the project root.
+pub fn fonts_dir(engine_type: &EngineType) -> Option<&'static str> {
+    match engine_type {
+        EngineType::RpgMakerMv => Some("www/fonts"),
+        EngineType::RpgMakerMz => Some("fonts"),
+        _ => None,
+    }
+}
+
+/// Find the main game font of an MV or MZ project.
+pub fn locate_game_font(project_path: &Path, engine_type: &EngineType) -> Option<GameFont> {
+    let fonts_dir = project_path.join(fonts_dir(engine_type)?);
+    match engine_type {
+        EngineType::RpgMakerMv => {
+            let file_name = std::fs::read_to_string(fonts_dir.join("gamefont.css"))
+                .ok()
+                .and_then(|css| {
+                    CSS_URL_REGEX
+                        .captures(&css)
+                        .map(|caps| caps[1].trim().to_string())
+                })
+                .unwrap_or_else(|| "mplus-1m-regular.ttf".to_string());
+            Some(GameFont {
+                fonts_dir,
+                file_name,
+                font_size: MV_DEFAULT_FONT_SIZE,
+            })
+        }
+        EngineType::RpgMakerMz => {
+            let advanced = std::fs::read_to_string(project_path.join("data/System.json"))
+                .ok()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+                .and_then(|system| system.get("advanced").cloned());
+            let file_name = advanced
+                .as_ref()
+                .and_then(|a| a.get("mainFontFilename"))
+                .and_then(|v| v.as_str())
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or("mplus-1m-regular.woff")
+                .to_string();
+            let font_size = advanced
+                .as_ref()
+                .and_then(|a| a.get("fontSize"))
+                .and_then(|v| v.as_u64())
+                .map(|size| size as u32)
+                .unwrap_or(MZ_DEFAULT_FONT_SIZE);
+            Some(GameFont {
+                fonts_dir,
+                file_name,
+                font_size,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Translated units containing characters the game font cannot render.
+#[derive(Debug, Clone, Serialize)]
+pub struct FontCoverageReport {
+    /// Font file that was checked
+    pub font_file: String,
+
+    /// Whether the checked font is the configured replacement font
+    pub is_replacement: bool,
+
+    /// Every missing character, in order of first appearance
+    pub missing_characters: Vec<char>,
+
+    /// Units with at least one missing character
+    pub units: Vec<UnitGlyphIssue>,
+}
+
+/// A translated unit with characters missing from the game font.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnitGlyphIssue {
+    pub id: Option<i64>,
+    pub file_path: String,
+    pub field_type: String,
+    pub translated_text: String,
+    pub missing_characters: Vec<char>,
+}
+
+/// Check translated records against the font the exported game will use.
+///
+/// With a `replacement_font` that font is checked instead of the bundled one.
+pub fn check_font_coverage(
+    project_path: &Path,
+    engine_type: &EngineType,
+    replacement_font: Option<&str>,
+    records: &[TextUnitRecord],
+) -> AppResult<FontCoverageReport> {
+    let font_path = match replacement_font {
+        Some(path) => PathBuf::from(path),
+        None => locate_game_font(project_path, engine_type)
+            .ok_or_else(|| {
+                AppError::Other(format!(
+                    "Font coverage check is not supported for {:?}",
+                    engine_type
+                ))
+            })?
+            .path(),
+    };
+    let font = FontMetrics::load(&font_path)?;
+
+    let mut missing_characters: Vec<char> = Vec::new();
+    let mut units = Vec::new();
+    for record in records {
+        let text = match record.translated_text.as_deref() {
+            Some(text) if record.status != "Ignored" && !text.is_empty() => text,
+            _ => continue,
+        };
+        let missing = font.missing_glyphs(text);
+        if missing.is_empty() {
+            continue;
+        }
+        for c in &missing {
+            if !missing_characters.contains(c) {
+                missing_characters.push(*c);
+            }
+        }
+        units.push(UnitGlyphIssue {
+            id: record.id,
+            file_path: record.file_path.clone(),
+            field_type: record.field_type.clone(),
+            translated_text: text.to_string(),
+            missing_characters: missing,
+        });
+    }
+
+    Ok(FontCoverageReport {
+        font_file: font_path.to_string_lossy().to_string(),
+        is_replacement: replacement_font.is_some(),
+        missing_characters,
+        units,
+    })
+}
+
+/// Install `font_file` as the main game font of the project at `project_path`.
+///
+/// The font is copied into the fonts directory and referenced from
+/// `gamefont.css` (MV) or `System.json` (MZ). Meant for exported copies only.
+pub fn install_replacement_font(
+    project_path: &Path,
+    engine_type: &EngineType,
+    font_file: &Path,
+) -> AppResult<GameFont> {
+    // Refuse files the engine could not load either
+    FontMetrics::load(font_file)?;
+
+    let mut game_font = locate_game_font(project_path, engine_type).ok_or_else(|| {
+        AppError::Other(format!(
+            "Replacement fonts are not supported for {:?}",
+            engine_type
+        ))
+    })?;
+    let file_name = font_file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::Other(format!("Invalid font path: {}", font_file.display())))?;
+
+    std::fs::create_dir_all(&game_font.fonts_dir)
+        .map_err(|e| AppError::FileSystem(format!("Failed to create fonts directory: {}", e)))?;
+    std::fs::copy(font_file, game_font.fonts_dir.join(&file_name))
+        .map_err(|e| AppError::FileSystem(format!("Failed to copy font {}: {}", file_name, e)))?;
+
+    match engine_type {
+        EngineType::RpgMakerMv => {
+            let css_path = game_font.fonts_dir.join("gamefont.css");
+            let css = std::fs::read_to_string(&css_path).unwrap_or_default();
+            let url = format!("url(\"{}\")", file_name);
+            let updated = if CSS_URL_REGEX.is_match(&css) {
+                CSS_URL_REGEX.replace(&css, url.as_str()).to_string()
+            } else {
+                format!(
+                    "@font-face {{\n    font-family: GameFont;\n    src: {};\n}}\n",
+                    url
+                )
+            };
+            std::fs::write(&css_path, updated).map_err(|e| {
+                AppError::FileSystem(format!("Failed to write gamefont.css: {}", e))
+            })?;
+        }
+        EngineType::RpgMakerMz => {
+            let system_path = project_path.join("data/System.json");
+            let content = std::fs::read_to_string(&system_path)
+                .map_err(|e| AppError::FileSystem(format!("Failed to read System.json: {}", e)))?;
+            let mut system: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| AppError::Parsing(format!("Failed to parse System.json: {}", e)))?;
+            let root = system
+                .as_object_mut()
+                .ok_or_else(|| AppError::Parsing("System.json is not an object".to_string()))?;
+            let advanced = root
+                .entry("advanced")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(advanced) = advanced.as_object_mut() {
+                advanced.insert(
+                    "mainFontFilename".to_string(),
+                    serde_json::Value::String(file_name.clone()),
+                );
+            }
+            let updated = serde_json::to_string(&system).map_err(|e| {
+                AppError::Parsing(format!("Failed to serialize System.json: {}", e))
+            })?;
+            std::fs::write(&system_path, updated)
+                .map_err(|e| AppError::FileSystem(format!("Failed to write System.json: {}", e)))?;
+        }
+        _ => {}
+    }
+
+    game_font.file_name = file_name;
+    Ok(game_font)
+}
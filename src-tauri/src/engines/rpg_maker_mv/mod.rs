@@ -1,2 +1,4 @@
 pub mod engine;
 pub mod files;
+pub mod fonts;
+pub mod text_fit;
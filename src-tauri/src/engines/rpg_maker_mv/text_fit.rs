@@ -0,0 +1,848 @@
+//! Fit translated dialogue into the MV/MZ message window.
+//!
+//! A "Show Text" command (101) is followed by up to four 401 commands, one per
+//! line. Translations are injected line by line, so a longer target language
+//! easily overflows the window. After injection, every translated message whose
+//! lines no longer fit is joined, re-wrapped to the window width measured with
+//! the game's own font, and split into extra message pages (copies of the 101
+//! command) when it needs more lines than one window holds. Event files are
+//! read and written with the same types and serializer as the injection.
+//!
+//! Escape codes are kept intact and measured the way the engine draws them:
+//! icons take their pixel width, names and variables an estimate, and colour
+//! or timing codes take no space.
+
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::core::error::{AppError, AppResult};
+use crate::db::translation::manifest::ProjectManifest;
+use crate::engines::rpg_maker_mv::files::common::EventCommand;
+use crate::engines::rpg_maker_mv::files::common_events::CommonEvent;
+use crate::engines::rpg_maker_mv::files::maps::{extract_map_id, Map};
+use crate::engines::rpg_maker_mv::files::troops::Troop;
+use crate::engines::rpg_maker_mv::fonts::{locate_game_font, MV_DEFAULT_FONT_SIZE};
+use crate::models::engine::{EngineInfo, EngineType};
+use crate::models::settings::TextFitSettings;
+use crate::models::translation::TextUnit;
+use crate::utils::fonts::FontMetrics;
+
+/// Icon width plus the gap the engine leaves after it (`\I[n]`)
+const ICON_WIDTH: f32 = 36.0;
+
+/// Stand-in text used to estimate actor and party member names (`\N[n]`, `\P[n]`)
+const NAME_ESTIMATE: &str = "MMMMMM";
+
+/// Stand-in text used to estimate variable values (`\V[n]`)
+const VARIABLE_ESTIMATE: &str = "0000";
+
+/// Width (in em) assumed for characters the font cannot measure
+const FALLBACK_CHAR_EM: f32 = 0.5;
+const FALLBACK_WIDE_CHAR_EM: f32 = 1.0;
+
+/// Message window geometry used for fitting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageLayout {
+    /// Usable text width without a face graphic
+    pub width: f32,
+
+    /// Usable text width with a face graphic
+    pub width_with_face: f32,
+
+    /// Lines per message page
+    pub max_lines: usize,
+}
+
+impl MessageLayout {
+    /// Stock window geometry of the engine, overridden by project settings.
+    pub fn for_engine(engine_type: &EngineType, settings: &TextFitSettings) -> Self {
+        // MV: 816px box, 18px padding, text starts at 168px next to a face.
+        // MZ: 808px box, 12px padding, 4px margin, text starts at 164px next to a face.
+        let (width, width_with_face) = match engine_type {
+            EngineType::RpgMakerMz => (780.0, 620.0),
+            _ => (780.0, 612.0),
+        };
+        Self {
+            width: settings.width.map(|w| w as f32).unwrap_or(width),
+            width_with_face: settings
+                .width_with_face
+                .map(|w| w as f32)
+                .unwrap_or(width_with_face),
+            max_lines: settings.max_lines.max(1),
+        }
+    }
+}
+
+/// Measures plain text in pixels, with the game font when it could be loaded.
+pub struct TextMeasurer {
+    font: Option<FontMetrics>,
+    font_size: f32,
+}
+
+impl TextMeasurer {
+    pub fn new(font: Option<FontMetrics>, font_size: f32) -> Self {
+        Self { font, font_size }
+    }
+
+    /// Width of `text`, which must not contain escape codes.
+    pub fn measure(&self, text: &str) -> f32 {
+        match &self.font {
+            Some(font) => font.text_width(text, self.font_size, FALLBACK_CHAR_EM),
+            None => text
+                .chars()
+                .map(|c| {
+                    let em = if is_wide(c) {
+                        FALLBACK_WIDE_CHAR_EM
+                    } else {
+                        FALLBACK_CHAR_EM
+                    };
+                    em * self.font_size
+                })
+                .sum(),
+        }
+    }
+
+    /// Width of a message line including escape codes.
+    pub fn measure_line(&self, line: &str) -> f32 {
+        tokenize(line)
+            .iter()
+            .map(|token| self.token_width(token))
+            .sum()
+    }
+
+    fn token_width(&self, token: &Token) -> f32 {
+        match token {
+            Token::Text(text) | Token::Space(text) => self.measure(text),
+            Token::Code(code) => self.code_width(code),
+        }
+    }
+
+    fn code_width(&self, code: &str) -> f32 {
+        let name: String = code
+            .chars()
+            .skip(1)
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        match name.as_str() {
+            "I" => ICON_WIDTH,
+            "N" | "P" => self.measure(NAME_ESTIMATE),
+            "V" => self.measure(VARIABLE_ESTIMATE),
+            "G" => self.measure("G"),
+            "" if code == "\\\\" => self.measure("\\"),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Re-wrap message lines to `max_width`, returning the new lines.
+pub fn wrap_message(lines: &[String], max_width: f32, measurer: &TextMeasurer) -> Vec<String> {
+    let joined = join_lines(lines);
+    let space_width = measurer.measure(" ");
+
+    let mut wrapped: Vec<String> = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0.0f32;
+    for piece in pieces(&joined) {
+        let width: f32 = piece.tokens.iter().map(|t| measurer.token_width(t)).sum();
+        let gap = if piece.space_before && !line.is_empty() {
+            space_width
+        } else {
+            0.0
+        };
+        if !line.is_empty() && line_width + gap + width > max_width {
+            wrapped.push(std::mem::take(&mut line));
+            line_width = 0.0;
+        } else if gap > 0.0 {
+            line.push(' ');
+            line_width += gap;
+        }
+        for token in &piece.tokens {
+            line.push_str(token.as_str());
+        }
+        line_width += width;
+    }
+    if !line.is_empty() || wrapped.is_empty() {
+        wrapped.push(line);
+    }
+    wrapped
+}
+
+/// Fit every translated message of an event command list in place.
+///
+/// `translated` tells whether the 401 command at an index of the list as
+/// injected holds a translation; messages without any are left untouched. A
+/// message is re-wrapped when a line is too wide and split into extra pages
+/// when it has more lines than the window holds. Returns the number of
+/// messages changed.
+pub fn fit_command_list(
+    commands: &mut Vec<EventCommand>,
+    layout: &MessageLayout,
+    measurer: &TextMeasurer,
+    translated: impl Fn(usize) -> bool,
+) -> usize {
+    // Show Text headers and the end of their 401 lines
+    let mut messages = Vec::new();
+    let mut index = 0;
+    while index < commands.len() {
+        if commands[index].code != 101 {
+            index += 1;
+            continue;
+        }
+        let header = index;
+        let mut end = header + 1;
+        while end < commands.len() && commands[end].code == 401 {
+            end += 1;
+        }
+        messages.push((header, end));
+        index = end;
+    }
+
+    // Back to front, so the indexes of the messages still to fit stay valid
+    let mut fitted = 0;
+    for (header, end) in messages.into_iter().rev() {
+        if end == header + 1 || !(header + 1..end).any(&translated) {
+            continue;
+        }
+        let lines: Vec<String> = commands[header + 1..end]
+            .iter()
+            .map(|c| first_string_param(c).unwrap_or_default().to_string())
+            .collect();
+        let has_face = first_string_param(&commands[header]).is_some_and(|face| !face.is_empty());
+        let max_width = if has_face {
+            layout.width_with_face
+        } else {
+            layout.width
+        };
+
+        let overflows = lines.iter().any(|l| measurer.measure_line(l) > max_width);
+        if !overflows && lines.len() <= layout.max_lines {
+            continue;
+        }
+        let wrapped = if overflows {
+            wrap_message(&lines, max_width, measurer)
+        } else {
+            lines.clone()
+        };
+
+        let indent = commands[header + 1].indent;
+        let mut replacement: Vec<EventCommand> = Vec::new();
+        for (page_index, page) in wrapped.chunks(layout.max_lines).enumerate() {
+            if page_index > 0 {
+                replacement.push(commands[header].clone());
+            }
+            for line in page {
+                replacement.push(EventCommand {
+                    code: 401,
+                    indent,
+                    parameters: vec![Value::String(line.clone())],
+                });
+            }
+        }
+        debug!(
+            "Fitted message of {} lines into {} lines",
+            lines.len(),
+            wrapped.len()
+        );
+
+        commands.splice(header + 1..end, replacement);
+        fitted += 1;
+    }
+    fitted
+}
+
+/// Re-wrap translated messages in the event files of `data_dir` after injection.
+///
+/// Settings come from `project_info.settings` when present (export) or the
+/// project manifest otherwise.
+pub fn fit_message_windows(
+    project_info: &EngineInfo,
+    data_dir: &str,
+    text_units: &[TextUnit],
+) -> AppResult<usize> {
+    let settings = ProjectManifest::settings_for(project_info).text_fit;
+    if !settings.enabled {
+        return Ok(0);
+    }
+
+    let translated_ids: HashSet<&str> = text_units
+        .iter()
+        .filter(|unit| unit.id.contains("_message_") && !unit.translated_text.is_empty())
+        .map(|unit| unit.id.as_str())
+        .collect();
+    if translated_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let fitter = MessageFitter {
+        layout: MessageLayout::for_engine(&project_info.engine_type, &settings),
+        measurer: game_font_measurer(project_info, &settings),
+        translated_ids,
+    };
+
+    let data_path = project_info.path.join(data_dir);
+    let mut fitted = 0;
+    for file_name in event_file_names(&data_path)? {
+        fitted += fitter.fit_file(&data_path.join(&file_name), &file_name)?;
+    }
+
+    info!(
+        "Re-wrapped {} translated messages to fit the message window",
+        fitted
+    );
+    Ok(fitted)
+}
+
+/// Fits the command lists of the event files the engine injected into.
+struct MessageFitter<'a> {
+    layout: MessageLayout,
+    measurer: TextMeasurer,
+    /// Ids of the injected message lines
+    translated_ids: HashSet<&'a str>,
+}
+
+impl MessageFitter<'_> {
+    /// Fit the command list of one object, identified like its message units
+    /// (`<object_type>_<object_id>_message_<index>`).
+    fn fit(&self, object_type: &str, object_id: i32, commands: &mut Vec<EventCommand>) -> usize {
+        fit_command_list(commands, &self.layout, &self.measurer, |index| {
+            let id = format!("{}_{}_message_{}", object_type, object_id, index);
+            self.translated_ids.contains(id.as_str())
+        })
+    }
+
+    /// Fit one event file, parsed and written back with the same types and
+    /// serializer as the injection; files without changes are left alone.
+    fn fit_file(&self, path: &Path, file_name: &str) -> AppResult<usize> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", file_name, e)))?;
+        let parse_error = |e: serde_json::Error| {
+            AppError::Parsing(format!("Failed to parse {}: {}", file_name, e))
+        };
+
+        let mut fitted = 0;
+        let updated = match file_name {
+            "CommonEvents.json" => {
+                let mut events: Vec<Option<CommonEvent>> =
+                    serde_json::from_str(&content).map_err(parse_error)?;
+                for event in events.iter_mut().flatten() {
+                    fitted += self.fit("common_event", event.id, &mut event.list);
+                }
+                if fitted == 0 {
+                    return Ok(0);
+                }
+                serde_json::to_string_pretty(&events)
+            }
+            "Troops.json" => {
+                let mut troops: Vec<Option<Troop>> =
+                    serde_json::from_str(&content).map_err(parse_error)?;
+                for troop in troops.iter_mut().flatten() {
+                    for page in troop.pages.iter_mut() {
+                        let mut commands: Vec<EventCommand> =
+                            page.list.iter().map(|cmd| cmd.clone().into()).collect();
+                        let count = self.fit("troop", troop.id, &mut commands);
+                        if count > 0 {
+                            page.list = commands.into_iter().map(|cmd| cmd.into()).collect();
+                            fitted += count;
+                        }
+                    }
+                }
+                if fitted == 0 {
+                    return Ok(0);
+                }
+                serde_json::to_string_pretty(&troops)
+            }
+            _ => {
+                let mut map: Map = serde_json::from_str(&content).map_err(parse_error)?;
+                let object_type = format!("map_{}_event", extract_map_id(file_name));
+                for event in map.events.iter_mut().flatten() {
+                    for page in &mut event.pages {
+                        fitted += self.fit(&object_type, event.id, &mut page.list);
+                    }
+                }
+                if fitted == 0 {
+                    return Ok(0);
+                }
+                serde_json::to_string_pretty(&map)
+            }
+        }
+        .map_err(|e| AppError::Parsing(format!("Failed to serialize {}: {}", file_name, e)))?;
+
+        std::fs::write(path, updated)
+            .map_err(|e| AppError::FileSystem(format!("Failed to write {}: {}", file_name, e)))?;
+        Ok(fitted)
+    }
+}
+
+/// Measurer for the project's main font, falling back to width estimates.
+fn game_font_measurer(project_info: &EngineInfo, settings: &TextFitSettings) -> TextMeasurer {
+    let game_font = locate_game_font(&project_info.path, &project_info.engine_type);
+    let font_size = settings
+        .font_size
+        .or(game_font.as_ref().map(|f| f.font_size))
+        .unwrap_or(MV_DEFAULT_FONT_SIZE) as f32;
+    let font_path = match &settings.font_file {
+        Some(font_file) => Some(project_info.path.join(font_file)),
+        None => game_font.map(|f| f.path()),
+    };
+    let font = font_path.and_then(|path| match FontMetrics::load(&path) {
+        Ok(font) => Some(font),
+        Err(e) => {
+            warn!("Using estimated text widths, game font unavailable: {}", e);
+            None
+        }
+    });
+    TextMeasurer::new(font, font_size)
+}
+
+/// Data files that contain event command lists.
+fn event_file_names(data_path: &Path) -> AppResult<Vec<String>> {
+    let entries = std::fs::read_dir(data_path).map_err(|e| {
+        AppError::FileSystem(format!("Failed to read {}: {}", data_path.display(), e))
+    })?;
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name == "CommonEvents.json"
+                || name == "Troops.json"
+                || (name.starts_with("Map")
+                    && name.ends_with(".json")
+                    && name[3..name.len() - 5].chars().all(|c| c.is_ascii_digit())
+                    && name.len() > 8)
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+fn first_string_param(command: &EventCommand) -> Option<&str> {
+    command.parameters.first().and_then(|v| v.as_str())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Space(String),
+    Code(String),
+}
+
+impl Token {
+    fn as_str(&self) -> &str {
+        match self {
+            Token::Text(s) | Token::Space(s) | Token::Code(s) => s,
+        }
+    }
+}
+
+/// Split a message line into text, whitespace and escape code tokens.
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && i + 1 < chars.len() {
+            let start = i;
+            i += 1;
+            if chars[i].is_ascii_alphabetic() {
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                // Optional argument: \C[2], \FS<24>
+                if i < chars.len() && (chars[i] == '[' || chars[i] == '<') {
+                    let close = if chars[i] == '[' { ']' } else { '>' };
+                    if let Some(offset) = chars[i..].iter().position(|&ch| ch == close) {
+                        i += offset + 1;
+                    }
+                }
+            } else {
+                // Single-character codes: \{ \} \. \| \! \> \< \^ \$ \\
+                i += 1;
+            }
+            tokens.push(Token::Code(chars[start..i].iter().collect()));
+        } else if c.is_whitespace() {
+            let start = i;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            tokens.push(Token::Space(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Text(c.to_string()));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// An unbreakable run of tokens, optionally preceded by a space.
+#[derive(Debug)]
+struct Piece {
+    tokens: Vec<Token>,
+    space_before: bool,
+}
+
+/// Group tokens into the units a line may be broken between.
+///
+/// Spaces separate pieces; wide (CJK) characters are pieces of their own, except
+/// that closing punctuation sticks to the character before it. Escape codes
+/// stick to the text that follows them.
+fn pieces(text: &str) -> Vec<Piece> {
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut current: Vec<Token> = Vec::new();
+    let mut space_before = false;
+    let mut has_text = false;
+
+    let mut flush = |current: &mut Vec<Token>, space_before: &mut bool, has_text: &mut bool| {
+        if !current.is_empty() {
+            pieces.push(Piece {
+                tokens: std::mem::take(current),
+                space_before: *space_before,
+            });
+        }
+        *space_before = false;
+        *has_text = false;
+    };
+
+    for token in tokenize(text) {
+        match &token {
+            Token::Space(_) => {
+                flush(&mut current, &mut space_before, &mut has_text);
+                space_before = true;
+            }
+            Token::Code(_) => current.push(token),
+            Token::Text(s) => {
+                let c = s.chars().next().unwrap_or(' ');
+                if is_wide(c) && !is_closing_punctuation(c) && has_text {
+                    flush(&mut current, &mut space_before, &mut has_text);
+                }
+                current.push(token);
+                has_text = true;
+            }
+        }
+    }
+    flush(&mut current, &mut space_before, &mut has_text);
+    pieces
+}
+
+/// Join the lines of one message; spaced scripts get a space at line ends.
+fn join_lines(lines: &[String]) -> String {
+    let mut joined = String::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let needs_space = match (joined.chars().last(), line.chars().next()) {
+            (Some(end), Some(start)) => !is_wide(end) && !is_wide(start),
+            _ => false,
+        };
+        if needs_space {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+    }
+    joined
+}
+
+/// Characters rendered full-width and breakable anywhere (CJK, kana, full-width forms).
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA960..=0xA97F
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6)
+}
+
+/// Punctuation that must not start a line.
+fn is_closing_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '、' | '。'
+            | '，'
+            | '．'
+            | '！'
+            | '？'
+            | '」'
+            | '』'
+            | '）'
+            | '】'
+            | '〉'
+            | '》'
+            | 'ー'
+            | '…'
+            | 'っ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ッ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::Engine;
+    use crate::engines::rpg_maker_mv::engine::RpgMakerMvEngine;
+    use crate::engines::rpg_maker_mv::files::troops;
+    use crate::utils::test_support::TempDir;
+
+    /// 10px per half-width character, 20px per wide character
+    fn measurer() -> TextMeasurer {
+        TextMeasurer::new(None, 20.0)
+    }
+
+    fn lines(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_measure_line_with_codes() {
+        let m = measurer();
+        assert_eq!(m.measure_line("abc"), 30.0);
+        assert_eq!(m.measure_line("\\C[2]abc\\C[0]"), 30.0);
+        assert_eq!(m.measure_line("\\I[5]abc"), 66.0);
+        assert_eq!(m.measure_line("勇者"), 40.0);
+    }
+
+    #[test]
+    fn test_wrap_spaced_text() {
+        let wrapped = wrap_message(
+            &lines(&["The quick brown fox", "jumps over the lazy dog"]),
+            100.0,
+            &measurer(),
+        );
+        assert_eq!(
+            wrapped,
+            vec!["The quick", "brown fox", "jumps over", "the lazy", "dog"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_codes_with_following_word() {
+        let wrapped = wrap_message(
+            &lines(&["Hello \\C[2]world\\C[0] again"]),
+            110.0,
+            &measurer(),
+        );
+        assert_eq!(wrapped, vec!["Hello \\C[2]world\\C[0]", "again"]);
+    }
+
+    #[test]
+    fn test_wrap_cjk_respects_closing_punctuation() {
+        let wrapped = wrap_message(&lines(&["あいうえお。かきく"]), 60.0, &measurer());
+        assert_eq!(wrapped, vec!["あいう", "えお。", "かきく"]);
+    }
+
+    fn command(code: i32, parameters: Vec<Value>) -> EventCommand {
+        EventCommand {
+            code,
+            indent: 0,
+            parameters,
+        }
+    }
+
+    fn show_text(face: &str) -> EventCommand {
+        command(101, vec![face.into(), 0.into(), 0.into(), 2.into()])
+    }
+
+    fn line(text: &str) -> EventCommand {
+        command(401, vec![text.into()])
+    }
+
+    fn texts(commands: &[EventCommand]) -> Vec<(i32, &str)> {
+        commands
+            .iter()
+            .map(|c| (c.code, first_string_param(c).unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_command_list_adds_pages() {
+        let layout = MessageLayout {
+            width: 100.0,
+            width_with_face: 60.0,
+            max_lines: 2,
+        };
+        let mut commands = vec![
+            show_text(""),
+            line("one two three four five six"),
+            command(0, vec![]),
+        ];
+
+        let fitted = fit_command_list(&mut commands, &layout, &measurer(), |i| i == 1);
+        assert_eq!(fitted, 1);
+        assert_eq!(
+            texts(&commands),
+            vec![
+                (101, ""),
+                (401, "one two"),
+                (401, "three four"),
+                (101, ""),
+                (401, "five six"),
+                (0, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fit_command_list_pages_extra_lines() {
+        let layout = MessageLayout {
+            width: 100.0,
+            width_with_face: 60.0,
+            max_lines: 2,
+        };
+        // Short lines that fit keep their breaks and only move to a new page
+        let mut commands = vec![show_text(""), line("a"), line("b"), line("c")];
+        let fitted = fit_command_list(&mut commands, &layout, &measurer(), |i| i == 3);
+        assert_eq!(fitted, 1);
+        assert_eq!(
+            texts(&commands),
+            vec![(101, ""), (401, "a"), (401, "b"), (101, ""), (401, "c")]
+        );
+    }
+
+    #[test]
+    fn test_fit_command_list_skips_untranslated_and_fitting() {
+        let layout = MessageLayout {
+            width: 100.0,
+            width_with_face: 60.0,
+            max_lines: 4,
+        };
+        let mut commands = vec![
+            show_text(""),
+            line("a very long untranslated line"),
+            show_text(""),
+            line("short"),
+            // Same text as a translated line, but not injected
+            show_text(""),
+            line("a very long untranslated line"),
+        ];
+        assert_eq!(
+            fit_command_list(&mut commands, &layout, &measurer(), |i| i == 3),
+            0
+        );
+        assert_eq!(commands.len(), 6);
+
+        // Matched by index: only the message holding the injected line changes
+        assert_eq!(
+            fit_command_list(&mut commands, &layout, &measurer(), |i| i == 5),
+            1
+        );
+        assert_eq!(
+            texts(&commands)[..4],
+            [
+                (101, ""),
+                (401, "a very long untranslated line"),
+                (101, ""),
+                (401, "short"),
+            ]
+        );
+        assert_eq!(commands.len(), 9);
+    }
+
+    fn fitter(translated_ids: HashSet<&str>) -> MessageFitter<'_> {
+        MessageFitter {
+            layout: MessageLayout {
+                width: 100.0,
+                width_with_face: 60.0,
+                max_lines: 2,
+            },
+            measurer: measurer(),
+            translated_ids,
+        }
+    }
+
+    #[test]
+    fn test_fit_file_keeps_engine_serialization() {
+        let dir = TempDir::new("text_fit_test");
+        let data = dir.path();
+        let events = serde_json::json!([
+            null,
+            {"id": 1, "name": "Intro", "switchId": 1, "trigger": 0, "list": [
+                {"code": 101, "indent": 0, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "indent": 0, "parameters": ["one two three four five six"]},
+                {"code": 0, "indent": 0, "parameters": []}
+            ]}
+        ]);
+        let path = data.join("CommonEvents.json");
+        std::fs::write(&path, serde_json::to_string(&events).unwrap()).unwrap();
+        std::fs::write(data.join("Map001.json"), "{\"events\": [null]}").unwrap();
+
+        let fitter = fitter(["common_event_1_message_1"].into_iter().collect());
+
+        assert_eq!(fitter.fit_file(&path, "CommonEvents.json").unwrap(), 1);
+        assert_eq!(
+            fitter
+                .fit_file(&data.join("Map001.json"), "Map001.json")
+                .unwrap(),
+            0
+        );
+        let written = std::fs::read_to_string(&path).unwrap();
+        let parsed: Vec<Option<CommonEvent>> = serde_json::from_str(&written).unwrap();
+        assert_eq!(written, serde_json::to_string_pretty(&parsed).unwrap());
+        let list = &parsed[1].as_ref().unwrap().list;
+        assert_eq!(list.len(), 6);
+        // Untouched files are not rewritten
+        assert_eq!(
+            std::fs::read_to_string(data.join("Map001.json")).unwrap(),
+            "{\"events\": [null]}"
+        );
+    }
+
+    #[test]
+    fn test_fit_troop_messages_by_reconstructed_id() {
+        let dir = TempDir::new("text_fit_troop_test");
+        let data = dir.path().join("www/data");
+        std::fs::create_dir_all(&data).unwrap();
+        let message = |text: &str| {
+            serde_json::json!([
+                {"code": 101, "indent": 0, "parameters": ["", 0, 0, 2]},
+                {"code": 401, "indent": 0, "parameters": [text]},
+                {"code": 0, "indent": 0, "parameters": []}
+            ])
+        };
+        let troops = serde_json::json!([
+            null,
+            {"id": 1, "name": "Slimes", "members": [], "pages": [
+                {"conditions": {}, "span": 0, "list": message("short")},
+                {"conditions": {}, "span": 0, "list": message("one two three four five six")}
+            ]}
+        ]);
+        let path = data.join("Troops.json");
+        std::fs::write(&path, serde_json::to_string(&troops).unwrap()).unwrap();
+
+        // The id the export rebuilds from the stored field type is the extracted one
+        let extracted = troops::extract_text(dir.path(), "www/data/Troops.json").unwrap();
+        let unit = extracted
+            .text_units
+            .iter()
+            .find(|unit| unit.source_text == "one two three four five six")
+            .unwrap();
+        let rebuilt = RpgMakerMvEngine::new()
+            .reconstruct_text_unit_id(&unit.field_type, &unit.source_text, "translated")
+            .unwrap();
+        assert_eq!(rebuilt.id, "troop_1_message_1");
+        assert_eq!(rebuilt.id, unit.id);
+
+        let fitter = fitter([rebuilt.id.as_str()].into_iter().collect());
+        assert_eq!(fitter.fit_file(&path, "Troops.json").unwrap(), 1);
+        let parsed: Vec<Option<Troop>> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let pages = &parsed[1].as_ref().unwrap().pages;
+        assert_eq!(pages[0].list.len(), 3);
+        assert_eq!(pages[1].list.len(), 6);
+    }
+}
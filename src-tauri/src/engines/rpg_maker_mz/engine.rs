@@ -17,9 +17,13 @@ use crate::engines::rpg_maker_mv::files::states as mz_states;
 use crate::engines::rpg_maker_mz::files::system as mz_system;
 use crate::engines::rpg_maker_mv::files::troops as mz_troops;
 use crate::engines::rpg_maker_mv::files::weapons as mz_weapons;
+use crate::engines::rpg_maker_mv::text_fit;
+use crate::engines::registry::{criteria_score, EngineCapabilities, EngineRegistration};
 use crate::models::engine::{EngineCriteria, EngineInfo, EngineType, GameDataFile};
 use crate::models::language::Language;
 use crate::models::translation::TextUnit;
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::rpg_maker_formatter::RpgMakerFormatter;
 use crate::utils::text::types::RawTextUnit;
 
 /// RPG Maker MZ engine (MVP: Actors.json only)
@@ -43,6 +47,22 @@ impl RpgMakerMzEngine {
         }
     }
 
+    /// Registration of the engine: `js/rmmz_core.js` is MZ's own
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "RpgMakerMz",
+            engine_type: EngineType::RpgMakerMz,
+            create: || Box::new(Self::new()),
+            detect: |path| criteria_score(path, &Self::get_detection_criteria(), 80),
+            formatter: EngineTextFormatter::of::<RpgMakerFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: true,
+                minimal_export: true,
+                replacement_font: true,
+            },
+        }
+    }
+
     /// Extract core MZ files (reuse MV modules; MZ uses data/ root)
     pub fn extract_game_data_files(
         &self,
@@ -327,6 +347,9 @@ impl RpgMakerMzEngine {
             }
         }
 
+        // Re-wrap translated messages that no longer fit the message window
+        text_fit::fit_message_windows(project_info, "data", text_units)?;
+
         Ok(())
     }
 }
@@ -353,6 +376,7 @@ impl Engine for RpgMakerMzEngine {
             version: None,
             detection_criteria: self.detection_criteria.clone(),
             manifest_hash: None,
+            settings: None,
         })
     }
 
@@ -360,6 +384,10 @@ impl Engine for RpgMakerMzEngine {
         self.detection_criteria.clone()
     }
 
+    fn extract_game_data_files(&self, project_info: &EngineInfo) -> AppResult<Vec<GameDataFile>> {
+        RpgMakerMzEngine::extract_game_data_files(self, project_info)
+    }
+
     // Removed overridden extract_text_units and inject_text_units methods
     // Now using default Engine trait implementation which applies text formatting
 
@@ -411,6 +439,8 @@ impl Engine for RpgMakerMzEngine {
                 status: crate::models::translation::TranslationStatus::MachineTranslated,
                 field_type: field_type.to_string(),
                 prompt_type: crate::models::translation::PromptType::Dialogue,
+                position: None,
+                detected_language: None,
             });
         }
 
@@ -461,6 +491,8 @@ impl Engine for RpgMakerMzEngine {
                 status: crate::models::translation::TranslationStatus::MachineTranslated,
                 field_type: field_type.to_string(),
                 prompt_type: crate::models::translation::PromptType::Dialogue,
+                position: None,
+                detected_language: None,
             });
         }
 
@@ -569,6 +601,8 @@ impl Engine for RpgMakerMzEngine {
             status: crate::models::translation::TranslationStatus::MachineTranslated,
             field_type: field_type.to_string(),
             prompt_type: crate::models::translation::PromptType::Character,
+            position: None,
+            detected_language: None,
         })
     }
 
@@ -592,6 +626,7 @@ impl Engine for RpgMakerMzEngine {
                     source_text: text_unit.source_text.clone(),
                     field_type: text_unit.field_type.clone(),
                     prompt_type: text_unit.prompt_type,
+                    position: text_unit.position.clone(),
                 });
             }
         }
@@ -614,6 +649,8 @@ impl Engine for RpgMakerMzEngine {
                 field_type: raw_unit.field_type.clone(),
                 status: crate::models::translation::TranslationStatus::NotTranslated,
                 prompt_type: raw_unit.prompt_type,
+                position: raw_unit.position.clone(),
+                detected_language: None,
             })
             .collect();
 
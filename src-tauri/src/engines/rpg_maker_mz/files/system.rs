@@ -54,6 +54,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
             field_type: "gameTitle:data/System.json:0".to_string(),
             status: TranslationStatus::NotTranslated,
             prompt_type: PromptType::Character,
+            position: None,
+            detected_language: None,
         });
     }
     
@@ -66,6 +68,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
             field_type: "currencyUnit:data/System.json:0".to_string(),
             status: TranslationStatus::NotTranslated,
             prompt_type: PromptType::System,
+            position: None,
+            detected_language: None,
         });
     }
     
@@ -79,6 +83,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("armorTypes[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Equipment,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -93,6 +99,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("equipTypes[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Equipment,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -107,6 +115,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("skillTypes[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::Skill,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -164,6 +174,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("terms.basic[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -178,6 +190,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("terms.commands[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -192,6 +206,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("terms.params[{}]:data/System.json:0", index),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
@@ -206,6 +222,8 @@ pub fn extract_text(project_path: &std::path::Path, relative_path: &str) -> Resu
                 field_type: format!("terms.messages.{}:data/System.json:0", key),
                 status: TranslationStatus::NotTranslated,
                 prompt_type: PromptType::System,
+                position: None,
+                detected_language: None,
             });
         }
     }
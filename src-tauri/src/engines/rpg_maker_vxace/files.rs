@@ -0,0 +1,863 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::core::error::{AppError, AppResult};
+use crate::engines::registry::criteria_score;
+use crate::engines::rpg_maker_vxace::marshal::{self, Value};
+use crate::models::engine::EngineCriteria;
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus, UnitPosition};
+use crate::utils::text::types::RawTextUnit;
+
+/// Folder holding the data files
+const DATA_DIR: &str = "Data";
+
+/// Data layout of one RGSS generation: where the text sits in its database,
+/// system and event data
+///
+/// XP, VX and VX Ace share the Marshal container and most of the event
+/// commands, but each renamed or added database fields.
+#[derive(Debug)]
+pub struct Layout {
+    /// Engine name used in messages
+    pub name: &'static str,
+
+    /// Extension of the data files (`rvdata2`, `rvdata`, `rxdata`)
+    pub extension: &'static str,
+
+    /// Encrypted archive packed games ship instead of a `Data` folder
+    pub archive: &'static str,
+
+    /// Text fields (instance variables without the `@`) of each database file
+    database_fields: &'static [(&'static str, &'static [&'static str])],
+
+    /// String arrays of `RPG::System` (switch and variable names are editor
+    /// labels the player never sees, so they are left out as for MV)
+    system_arrays: &'static [&'static str],
+
+    /// Single strings of `RPG::System`
+    system_strings: &'static [&'static str],
+
+    /// `RPG::System` objects made only of strings (VX `@terms`, XP `@words`)
+    system_vocabulary: &'static [&'static str],
+
+    /// Whether "Show Text" (101) carries the first message line, as in XP;
+    /// later versions keep the window setup there and every line in 401
+    message_in_show_text: bool,
+}
+
+/// RPG Maker VX Ace (RGSS3, `.rvdata2`)
+pub const VX_ACE: Layout = Layout {
+    name: "VX Ace",
+    extension: "rvdata2",
+    archive: "Game.rgss3a",
+    database_fields: &[
+        ("Actors", &["name", "nickname", "description"]),
+        ("Classes", &["name"]),
+        ("Skills", &["name", "description", "message1", "message2"]),
+        ("Items", &["name", "description"]),
+        ("Weapons", &["name", "description"]),
+        ("Armors", &["name", "description"]),
+        ("Enemies", &["name"]),
+        (
+            "States",
+            &[
+                "name",
+                "description",
+                "message1",
+                "message2",
+                "message3",
+                "message4",
+            ],
+        ),
+    ],
+    system_arrays: &[
+        "@elements",
+        "@skill_types",
+        "@weapon_types",
+        "@armor_types",
+        "@terms.@basic",
+        "@terms.@params",
+        "@terms.@etypes",
+        "@terms.@commands",
+    ],
+    system_strings: &["@game_title", "@currency_unit"],
+    system_vocabulary: &[],
+    message_in_show_text: false,
+};
+
+/// RPG Maker VX (RGSS2, `.rvdata`)
+pub const VX: Layout = Layout {
+    name: "VX",
+    extension: "rvdata",
+    archive: "Game.rgssad",
+    database_fields: &[
+        ("Actors", &["name"]),
+        ("Classes", &["name"]),
+        ("Skills", &["name", "description", "message1", "message2"]),
+        ("Items", &["name", "description"]),
+        ("Weapons", &["name", "description"]),
+        ("Armors", &["name", "description"]),
+        ("Enemies", &["name"]),
+        (
+            "States",
+            &["name", "message1", "message2", "message3", "message4"],
+        ),
+    ],
+    system_arrays: &["@elements"],
+    system_strings: &["@game_title"],
+    system_vocabulary: &["@terms"],
+    message_in_show_text: false,
+};
+
+/// RPG Maker XP (RGSS1, `.rxdata`)
+pub const XP: Layout = Layout {
+    name: "XP",
+    extension: "rxdata",
+    archive: "Game.rgssad",
+    database_fields: &[
+        ("Actors", &["name"]),
+        ("Classes", &["name"]),
+        ("Skills", &["name", "description"]),
+        ("Items", &["name", "description"]),
+        ("Weapons", &["name", "description"]),
+        ("Armors", &["name", "description"]),
+        ("Enemies", &["name"]),
+        ("States", &["name"]),
+    ],
+    system_arrays: &["@elements"],
+    system_strings: &[],
+    system_vocabulary: &["@words"],
+    message_in_show_text: true,
+};
+
+/// File name without directory and extension ("Data/Map001.rvdata2" -> "Map001")
+pub fn file_stem(file_path: &str) -> &str {
+    Path::new(file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_path)
+}
+
+/// Whether the file stem is a map (`Map001`)
+pub fn is_map_file(stem: &str) -> bool {
+    stem.len() > 3 && stem.starts_with("Map") && stem[3..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Prompt type of a unit from its file and field label
+pub fn prompt_type_for(file_path: &str, label: &str) -> PromptType {
+    if label == "message" || label == "choice" {
+        return PromptType::Dialogue;
+    }
+    match file_stem(file_path) {
+        "Actors" | "Enemies" => PromptType::Character,
+        "Classes" => PromptType::Class,
+        "Skills" => PromptType::Skill,
+        "Items" | "Weapons" | "Armors" => PromptType::Equipment,
+        "States" => PromptType::State,
+        "System" => PromptType::System,
+        _ => PromptType::Character,
+    }
+}
+
+/// Whether the data file holds translatable text (scripts, tilesets and
+/// animations are left alone)
+pub fn has_text(layout: &Layout, file_path: &str) -> bool {
+    let stem = file_stem(file_path);
+    is_map_file(stem)
+        || layout.database_fields.iter().any(|(name, _)| *name == stem)
+        || matches!(stem, "MapInfos" | "CommonEvents" | "Troops" | "System")
+}
+
+/// `[Game]` section of `Game.ini`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameIni {
+    /// Window title of the game
+    pub title: Option<String>,
+
+    /// RGSS runtime library (e.g. `System\RGSS301.dll`)
+    pub library: Option<String>,
+}
+
+/// Read `Game.ini`; values that are not valid UTF-8 (Shift-JIS titles) are left out
+pub fn read_game_ini(project_path: &Path) -> GameIni {
+    let Ok(bytes) = std::fs::read(project_path.join("Game.ini")) else {
+        return GameIni::default();
+    };
+    let mut ini = GameIni::default();
+    for line in bytes.split(|b| *b == b'\n') {
+        let Ok(line) = std::str::from_utf8(line) else {
+            continue;
+        };
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        match key.trim() {
+            "Title" => ini.title = value,
+            "Library" => ini.library = value,
+            _ => {}
+        }
+    }
+    ini
+}
+
+/// RGSS generation named by a `Game.ini` library line: 1 for XP
+/// (`RGSS102J.dll`), 2 for VX (`RGSS202E.dll`), 3 for VX Ace
+/// (`System\RGSS301.dll`)
+pub fn rgss_generation(library: &str) -> Option<u32> {
+    let upper = library.to_ascii_uppercase();
+    let start = upper.find("RGSS")? + "RGSS".len();
+    upper[start..].chars().next()?.to_digit(10)
+}
+
+/// Detection score of an RGSS engine: the score of its unpacked layout, or 60
+/// when only `Game.ini`'s library names its generation (packed games)
+pub fn rgss_detection_score(
+    project_path: &Path,
+    criteria: &EngineCriteria,
+    generation: u32,
+) -> AppResult<u32> {
+    let score = criteria_score(project_path, criteria, 80)?;
+    if score > 0 {
+        return Ok(score);
+    }
+    let library_generation = read_game_ini(project_path)
+        .library
+        .as_deref()
+        .and_then(rgss_generation);
+    Ok(if library_generation == Some(generation) {
+        60
+    } else {
+        0
+    })
+}
+
+/// Data files with translatable text, relative to the project root
+pub fn data_files(layout: &Layout, project_path: &Path) -> AppResult<Vec<String>> {
+    let data_dir = project_path.join(DATA_DIR);
+    if !data_dir.is_dir() && project_path.join(layout.archive).is_file() {
+        return Err(AppError::FileSystem(format!(
+            "{} data is packed into {}; unpack it before extracting text",
+            layout.name, layout.archive
+        )));
+    }
+    let entries = std::fs::read_dir(&data_dir).map_err(|e| {
+        AppError::FileSystem(format!("Failed to read {}: {}", data_dir.display(), e))
+    })?;
+
+    let mut data_files: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(layout.extension))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            Some(format!("{}/{}", DATA_DIR, name))
+        })
+        .filter(|file_path| has_text(layout, file_path))
+        .collect();
+    data_files.sort();
+    Ok(data_files)
+}
+
+/// Read and parse one data file
+pub fn load_data_file(project_path: &Path, file_path: &str) -> AppResult<Value> {
+    let bytes = std::fs::read(project_path.join(file_path))
+        .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", file_path, e)))?;
+    marshal::load(&bytes)
+        .map_err(|e| AppError::Parsing(format!("Failed to parse {}: {}", file_path, e)))
+}
+
+/// Extract the text of every data file of a project
+pub fn extract_project(layout: &Layout, project_path: &Path) -> AppResult<Vec<RawTextUnit>> {
+    let mut raw_units = Vec::new();
+    for file_path in data_files(layout, project_path)? {
+        let data = load_data_file(project_path, &file_path)?;
+        let units = extract_file(layout, &file_path, &data);
+        log::debug!("Extracted {} text units from {}", units.len(), file_path);
+        raw_units.extend(units);
+    }
+    Ok(raw_units)
+}
+
+/// Write translations back into the data files named in the unit IDs
+pub fn inject_project(project_path: &Path, raw_units: &[RawTextUnit]) -> AppResult<()> {
+    // Group units by the data file named in their ID
+    let mut units_by_file: BTreeMap<&str, Vec<&RawTextUnit>> = BTreeMap::new();
+    for unit in raw_units {
+        if let Some((file_path, _)) = unit.id.split_once('#') {
+            units_by_file.entry(file_path).or_default().push(unit);
+        }
+    }
+
+    for (file_path, units) in units_by_file {
+        if !project_path.join(file_path).exists() {
+            log::warn!("Skipping translations for missing file {}", file_path);
+            continue;
+        }
+        let mut data = load_data_file(project_path, file_path)?;
+        let replaced = inject_file(file_path, &mut data, &units);
+        std::fs::write(project_path.join(file_path), marshal::dump(&data))
+            .map_err(|e| AppError::FileSystem(format!("Failed to write {}: {}", file_path, e)))?;
+        log::info!("Injected {} translations into {}", replaced, file_path);
+    }
+    Ok(())
+}
+
+/// Rebuild a text unit from its stored field type
+pub fn reconstruct_text_unit(
+    layout: &Layout,
+    field_type: &str,
+    source_text: &str,
+    translated_text: &str,
+) -> AppResult<TextUnit> {
+    // Field types are "label:file:path", e.g. "message:Data/Map001.rvdata2:@events{3}..."
+    let mut parts = field_type.splitn(3, ':');
+    let (Some(label), Some(file_path), Some(path)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(AppError::Other(format!(
+            "Invalid {} field_type format: {}",
+            layout.name, field_type
+        )));
+    };
+
+    Ok(TextUnit {
+        id: unit_id(file_path, path),
+        source_text: source_text.to_string(),
+        translated_text: translated_text.to_string(),
+        field_type: field_type.to_string(),
+        status: TranslationStatus::MachineTranslated,
+        prompt_type: prompt_type_for(file_path, label),
+        position: None,
+        detected_language: None,
+    })
+}
+
+/// Unit ID of the string at `path` in a data file
+pub fn unit_id(file_path: &str, path: &str) -> String {
+    format!("{}#{}", file_path, path)
+}
+
+/// Extract the text of one data file, dispatching on its name
+///
+/// Unit IDs are `file#path`, where the path locates the string inside the
+/// Marshal data; the field type is `label:file:path`.
+pub fn extract_file(layout: &Layout, file_path: &str, data: &Value) -> Vec<RawTextUnit> {
+    let mut units = Vec::new();
+    let stem = file_stem(file_path);
+    if let Some((_, fields)) = layout
+        .database_fields
+        .iter()
+        .find(|(name, _)| *name == stem)
+    {
+        extract_records(&mut units, file_path, data, fields);
+    } else if is_map_file(stem) {
+        extract_map(layout, &mut units, file_path, data);
+    } else {
+        match stem {
+            "MapInfos" => {
+                for (key, info) in data.as_hash().unwrap_or_default() {
+                    if let Some(id) = key.as_int() {
+                        let path = format!("{{{}}}.@name", id);
+                        push_string(
+                            &mut units,
+                            file_path,
+                            &path,
+                            "name",
+                            info.ivar("@name"),
+                            None,
+                        );
+                    }
+                }
+            }
+            "CommonEvents" => {
+                for (index, event) in data.as_array().unwrap_or_default().iter().enumerate() {
+                    let path = format!("[{}]", index);
+                    push_string(
+                        &mut units,
+                        file_path,
+                        &format!("{}.@name", path),
+                        "name",
+                        event.ivar("@name"),
+                        None,
+                    );
+                    extract_event_commands(
+                        layout,
+                        &mut units,
+                        file_path,
+                        &path,
+                        0,
+                        event.ivar("@list"),
+                    );
+                }
+            }
+            "Troops" => {
+                for (index, troop) in data.as_array().unwrap_or_default().iter().enumerate() {
+                    let path = format!("[{}]", index);
+                    push_string(
+                        &mut units,
+                        file_path,
+                        &format!("{}.@name", path),
+                        "name",
+                        troop.ivar("@name"),
+                        None,
+                    );
+                    extract_pages(layout, &mut units, file_path, &path, troop.ivar("@pages"));
+                }
+            }
+            "System" => extract_system(layout, &mut units, file_path, data),
+            _ => {}
+        }
+    }
+    units
+}
+
+/// Write translations into a data file; returns the number of strings replaced
+///
+/// Units are located by the path in their ID, so the file must be the one the
+/// units were extracted from.
+pub fn inject_file(file_path: &str, data: &mut Value, units: &[&RawTextUnit]) -> usize {
+    let prefix = format!("{}#", file_path);
+    let mut replaced = 0;
+    for unit in units {
+        let Some(path) = unit.id.strip_prefix(&prefix) else {
+            continue;
+        };
+        match data.pointer_mut(path) {
+            Some(value) if value.as_str().is_some() => {
+                value.set_str(&unit.source_text);
+                replaced += 1;
+            }
+            _ => log::warn!("No string at {} in {}", path, file_path),
+        }
+    }
+    replaced
+}
+
+fn push_string(
+    units: &mut Vec<RawTextUnit>,
+    file_path: &str,
+    path: &str,
+    label: &str,
+    value: Option<&Value>,
+    position: Option<UnitPosition>,
+) {
+    let Some(text) = value.and_then(Value::as_str) else {
+        return;
+    };
+    if text.is_empty() {
+        return;
+    }
+    units.push(RawTextUnit {
+        id: unit_id(file_path, path),
+        source_text: text.to_string(),
+        field_type: format!("{}:{}:{}", label, file_path, path),
+        prompt_type: prompt_type_for(file_path, label),
+        position,
+    });
+}
+
+/// Database records: an array of objects indexed by ID, `nil` first
+fn extract_records(units: &mut Vec<RawTextUnit>, file_path: &str, data: &Value, fields: &[&str]) {
+    for (index, record) in data.as_array().unwrap_or_default().iter().enumerate() {
+        for field in fields {
+            let ivar = format!("@{}", field);
+            let path = format!("[{}].{}", index, ivar);
+            push_string(units, file_path, &path, field, record.ivar(&ivar), None);
+        }
+    }
+}
+
+fn extract_map(layout: &Layout, units: &mut Vec<RawTextUnit>, file_path: &str, map: &Value) {
+    push_string(
+        units,
+        file_path,
+        "@display_name",
+        "display_name",
+        map.ivar("@display_name"),
+        None,
+    );
+    let events = map
+        .ivar("@events")
+        .and_then(Value::as_hash)
+        .unwrap_or_default();
+    for (key, event) in events {
+        let Some(id) = key.as_int() else {
+            continue;
+        };
+        let path = format!("@events{{{}}}", id);
+        push_string(
+            units,
+            file_path,
+            &format!("{}.@name", path),
+            "name",
+            event.ivar("@name"),
+            None,
+        );
+        extract_pages(layout, units, file_path, &path, event.ivar("@pages"));
+    }
+}
+
+fn extract_pages(
+    layout: &Layout,
+    units: &mut Vec<RawTextUnit>,
+    file_path: &str,
+    owner_path: &str,
+    pages: Option<&Value>,
+) {
+    let pages = pages.and_then(Value::as_array).unwrap_or_default();
+    for (page_index, page) in pages.iter().enumerate() {
+        let path = format!("{}.@pages[{}]", owner_path, page_index);
+        extract_event_commands(
+            layout,
+            units,
+            file_path,
+            &path,
+            page_index,
+            page.ivar("@list"),
+        );
+    }
+}
+
+/// Dialogue of an event command list: 401 message lines, the first line in
+/// 101 itself for XP, and 102 choices
+fn extract_event_commands(
+    layout: &Layout,
+    units: &mut Vec<RawTextUnit>,
+    file_path: &str,
+    owner_path: &str,
+    page_index: usize,
+    list: Option<&Value>,
+) {
+    let commands = list.and_then(Value::as_array).unwrap_or_default();
+    // Pages share the event reference so that surrounding lines can be found
+    let event_ref = unit_id(
+        file_path,
+        owner_path.split(".@pages").next().unwrap_or(owner_path),
+    );
+    let position = |command_index: usize| UnitPosition {
+        event_ref: event_ref.clone(),
+        page_index: page_index as u32,
+        command_index: command_index as u32,
+        speaker: None,
+    };
+
+    for (command_index, command) in commands.iter().enumerate() {
+        let code = command.ivar("@code").and_then(Value::as_int);
+        let parameters = command
+            .ivar("@parameters")
+            .and_then(Value::as_array)
+            .unwrap_or_default();
+        let path = format!("{}.@list[{}].@parameters", owner_path, command_index);
+        match code {
+            Some(101) if layout.message_in_show_text => {
+                push_string(
+                    units,
+                    file_path,
+                    &format!("{}[0]", path),
+                    "message",
+                    parameters.first(),
+                    Some(position(command_index)),
+                );
+            }
+            Some(401) => {
+                push_string(
+                    units,
+                    file_path,
+                    &format!("{}[0]", path),
+                    "message",
+                    parameters.first(),
+                    Some(position(command_index)),
+                );
+            }
+            Some(102) => {
+                let choices = parameters
+                    .first()
+                    .and_then(Value::as_array)
+                    .unwrap_or_default();
+                for (choice_index, choice) in choices.iter().enumerate() {
+                    push_string(
+                        units,
+                        file_path,
+                        &format!("{}[0][{}]", path, choice_index),
+                        "choice",
+                        Some(choice),
+                        Some(position(command_index)),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn extract_system(layout: &Layout, units: &mut Vec<RawTextUnit>, file_path: &str, system: &Value) {
+    for field in layout.system_strings {
+        push_string(
+            units,
+            file_path,
+            field,
+            &field[1..],
+            system.ivar(field),
+            None,
+        );
+    }
+    for field in layout.system_arrays {
+        let Some(items) = system.pointer(field).and_then(Value::as_array) else {
+            continue;
+        };
+        let label = field.rsplit('@').next().unwrap_or(field);
+        for (index, item) in items.iter().enumerate() {
+            push_string(
+                units,
+                file_path,
+                &format!("{}[{}]", field, index),
+                label,
+                Some(item),
+                None,
+            );
+        }
+    }
+    for field in layout.system_vocabulary {
+        let Some(Value::Object { ivars, .. }) = system.ivar(field) else {
+            continue;
+        };
+        for (name, value) in ivars {
+            push_string(
+                units,
+                file_path,
+                &format!("{}.{}", field, name.name),
+                name.name.trim_start_matches('@'),
+                Some(value),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::rpg_maker_vxace::marshal::{dump, load, Symbol};
+
+    fn object(class: &str, ivars: Vec<(&str, Value)>) -> Value {
+        Value::Object {
+            class: Symbol::new(class),
+            ivars: ivars
+                .into_iter()
+                .map(|(name, value)| (Symbol::new(name), value))
+                .collect(),
+        }
+    }
+
+    fn command(code: i32, parameters: Vec<Value>) -> Value {
+        object(
+            "RPG::EventCommand",
+            vec![
+                ("@code", Value::Int(code)),
+                ("@indent", Value::Int(0)),
+                ("@parameters", Value::Array(parameters)),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_map_dialogue_round_trip() {
+        let page = object(
+            "RPG::Event::Page",
+            vec![(
+                "@list",
+                Value::Array(vec![
+                    command(
+                        101,
+                        vec![
+                            Value::utf8_string("Actor1"),
+                            Value::Int(0),
+                            Value::Int(0),
+                            Value::Int(2),
+                        ],
+                    ),
+                    command(401, vec![Value::utf8_string("\\C[2]勇者\\C[0]、起きて！")]),
+                    command(
+                        102,
+                        vec![
+                            Value::Array(vec![
+                                Value::utf8_string("はい"),
+                                Value::utf8_string("いいえ"),
+                            ]),
+                            Value::Int(2),
+                        ],
+                    ),
+                    command(0, vec![]),
+                ]),
+            )],
+        );
+        let event = object(
+            "RPG::Event",
+            vec![
+                ("@id", Value::Int(3)),
+                ("@name", Value::utf8_string("EV003")),
+                ("@pages", Value::Array(vec![page])),
+            ],
+        );
+        let map = object(
+            "RPG::Map",
+            vec![
+                ("@display_name", Value::utf8_string("はじまりの村")),
+                (
+                    "@events",
+                    Value::Hash {
+                        pairs: vec![(Value::Int(3), event)],
+                        default: None,
+                    },
+                ),
+            ],
+        );
+        let file = "Data/Map001.rvdata2";
+        let units = extract_file(&VX_ACE, file, &map);
+        let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "Data/Map001.rvdata2#@display_name",
+                "Data/Map001.rvdata2#@events{3}.@name",
+                "Data/Map001.rvdata2#@events{3}.@pages[0].@list[1].@parameters[0]",
+                "Data/Map001.rvdata2#@events{3}.@pages[0].@list[2].@parameters[0][0]",
+                "Data/Map001.rvdata2#@events{3}.@pages[0].@list[2].@parameters[0][1]",
+            ]
+        );
+        assert_eq!(units[2].prompt_type, PromptType::Dialogue);
+        assert_eq!(
+            units[2].position.as_ref().map(|p| p.event_ref.as_str()),
+            Some("Data/Map001.rvdata2#@events{3}")
+        );
+
+        let mut data = load(&dump(&map)).unwrap();
+        let mut translated = units[2].clone();
+        translated.source_text = "\\C[2]Hero\\C[0], wake up!".to_string();
+        let mut choice = units[3].clone();
+        choice.source_text = "Yes".to_string();
+        assert_eq!(inject_file(file, &mut data, &[&translated, &choice]), 2);
+
+        let reloaded = load(&dump(&data)).unwrap();
+        let texts: Vec<String> = extract_file(&VX_ACE, file, &reloaded)
+            .into_iter()
+            .map(|u| u.source_text)
+            .collect();
+        assert_eq!(texts[2], "\\C[2]Hero\\C[0], wake up!");
+        assert_eq!(texts[3], "Yes");
+        assert_eq!(texts[4], "いいえ");
+    }
+
+    #[test]
+    fn test_database_and_system_fields() {
+        let actors = Value::Array(vec![
+            Value::Nil,
+            object(
+                "RPG::Actor",
+                vec![
+                    ("@name", Value::utf8_string("エリック")),
+                    ("@nickname", Value::utf8_string("")),
+                    ("@description", Value::utf8_string("旅の剣士。")),
+                    ("@note", Value::utf8_string("<tag>")),
+                ],
+            ),
+        ]);
+        let units = extract_file(&VX_ACE, "Data/Actors.rvdata2", &actors);
+        let fields: Vec<&str> = units.iter().map(|u| u.field_type.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "name:Data/Actors.rvdata2:[1].@name",
+                "description:Data/Actors.rvdata2:[1].@description",
+            ]
+        );
+        assert_eq!(units[0].prompt_type, PromptType::Character);
+
+        let terms = object(
+            "RPG::System::Terms",
+            vec![(
+                "@commands",
+                Value::Array(vec![
+                    Value::utf8_string("戦う"),
+                    Value::utf8_string("逃げる"),
+                ]),
+            )],
+        );
+        let system = object(
+            "RPG::System",
+            vec![
+                ("@game_title", Value::utf8_string("冒険")),
+                ("@terms", terms),
+            ],
+        );
+        let units = extract_file(&VX_ACE, "Data/System.rvdata2", &system);
+        let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "Data/System.rvdata2#@game_title",
+                "Data/System.rvdata2#@terms.@commands[0]",
+                "Data/System.rvdata2#@terms.@commands[1]",
+            ]
+        );
+        assert_eq!(
+            units[1].field_type,
+            "commands:Data/System.rvdata2:@terms.@commands[0]"
+        );
+    }
+
+    #[test]
+    fn test_xp_show_text_and_words() {
+        let list = Value::Array(vec![
+            command(
+                101,
+                vec![Value::String("\\n[1]、起きて！".as_bytes().to_vec())],
+            ),
+            command(401, vec![Value::String("朝だよ。".as_bytes().to_vec())]),
+            command(0, vec![]),
+        ]);
+        let common_events = Value::Array(vec![
+            Value::Nil,
+            object(
+                "RPG::CommonEvent",
+                vec![("@name", Value::String(b"wake".to_vec())), ("@list", list)],
+            ),
+        ]);
+        let file = "Data/CommonEvents.rxdata";
+        let units = extract_file(&XP, file, &common_events);
+        let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "Data/CommonEvents.rxdata#[1].@name",
+                "Data/CommonEvents.rxdata#[1].@list[0].@parameters[0]",
+                "Data/CommonEvents.rxdata#[1].@list[1].@parameters[0]",
+            ]
+        );
+        assert_eq!(units[1].source_text, "\\n[1]、起きて！");
+        // VX and VX Ace keep the window setup in 101
+        assert_eq!(extract_file(&VX, file, &common_events).len(), 2);
+
+        let words = object(
+            "RPG::System::Words",
+            vec![
+                ("@gold", Value::String("G".as_bytes().to_vec())),
+                ("@attack", Value::String("攻撃".as_bytes().to_vec())),
+            ],
+        );
+        let system = object("RPG::System", vec![("@words", words)]);
+        let units = extract_file(&XP, "Data/System.rxdata", &system);
+        let fields: Vec<&str> = units.iter().map(|u| u.field_type.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "gold:Data/System.rxdata:@words.@gold",
+                "attack:Data/System.rxdata:@words.@attack",
+            ]
+        );
+        assert!(has_text(&XP, "Data/Map001.rxdata"));
+        assert!(!has_text(&XP, "Data/Scripts.rxdata"));
+        assert_eq!(rgss_generation("RGSS102J.dll"), Some(1));
+        assert_eq!(rgss_generation("System\\RGSS301.dll"), Some(3));
+    }
+}
@@ -0,0 +1,832 @@
+//! Ruby Marshal (format 4.8) reader and writer for RGSS data files.
+//!
+//! Values are kept exactly as stored, including object links and instance
+//! variable wrappers, so a file that is loaded and dumped again without edits
+//! comes out byte for byte identical. Only strings are meant to be edited in
+//! place: replacing one never adds or removes objects, so the object indices
+//! used by links stay valid.
+
+use std::collections::HashMap;
+
+use crate::core::error::{AppError, AppResult};
+
+const MAJOR_VERSION: u8 = 4;
+const MINOR_VERSION: u8 = 8;
+
+/// A Ruby value read from a Marshal stream.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    True,
+    False,
+    Int(i32),
+    /// Float in Ruby's textual form (e.g. `1.5`, `inf`, `nan`)
+    Float(Vec<u8>),
+    /// Integer beyond the fixnum range: sign and little-endian magnitude
+    Bignum {
+        negative: bool,
+        bytes: Vec<u8>,
+    },
+    Symbol(Symbol),
+    /// Raw string bytes; the encoding lives in an enclosing `Ivars`
+    String(Vec<u8>),
+    Regexp {
+        source: Vec<u8>,
+        options: u8,
+    },
+    Array(Vec<Value>),
+    Hash {
+        pairs: Vec<(Value, Value)>,
+        default: Option<Box<Value>>,
+    },
+    Object {
+        class: Symbol,
+        ivars: Vec<(Symbol, Value)>,
+    },
+    Struct {
+        class: Symbol,
+        members: Vec<(Symbol, Value)>,
+    },
+    /// Object serialized by its class's `_dump` (RGSS `Table`, `Color`, `Tone`)
+    UserDef {
+        class: Symbol,
+        data: Vec<u8>,
+    },
+    /// Object serialized by its class's `marshal_dump`
+    UserMarshal {
+        class: Symbol,
+        data: Box<Value>,
+    },
+    Data {
+        class: Symbol,
+        data: Box<Value>,
+    },
+    Class(Vec<u8>),
+    Module(Vec<u8>),
+    OldModule(Vec<u8>),
+    /// Object extended with a module
+    Extended {
+        module: Symbol,
+        value: Box<Value>,
+    },
+    /// Instance of a subclass of String, Array, Hash or Regexp
+    UserClass {
+        class: Symbol,
+        value: Box<Value>,
+    },
+    /// Value carrying instance variables, such as a string with its encoding
+    Ivars {
+        value: Box<Value>,
+        ivars: Vec<(Symbol, Value)>,
+    },
+    /// Reference to an object that appeared earlier in the stream
+    Link(usize),
+}
+
+/// A symbol, with the instance variables of its encoding when it has one
+/// (Ruby 1.9+ writes `E: true` with non-ASCII names; Ruby 1.8 writes none)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub ivars: Vec<(Symbol, Value)>,
+}
+
+impl Symbol {
+    /// Symbol without an encoding
+    pub fn new(name: &str) -> Self {
+        Symbol {
+            name: name.to_string(),
+            ivars: Vec::new(),
+        }
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.name == other
+    }
+}
+
+impl Value {
+    /// Instance variable of an object (`name` includes the `@`)
+    pub fn ivar(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object { ivars, .. } => ivars.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Mutable instance variable of an object
+    pub fn ivar_mut(&mut self, name: &str) -> Option<&mut Value> {
+        match self {
+            Value::Object { ivars, .. } => {
+                ivars.iter_mut().find(|(k, _)| k == name).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            Value::Ivars { value, .. } | Value::UserClass { value, .. } => value.as_array(),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Hash entries, in stored order
+    pub fn as_hash(&self) -> Option<&[(Value, Value)]> {
+        match self {
+            Value::Hash { pairs, .. } => Some(pairs),
+            Value::Ivars { value, .. } | Value::UserClass { value, .. } => value.as_hash(),
+            _ => None,
+        }
+    }
+
+    /// Text of a UTF-8 string (RGSS strings are UTF-8 whether or not they carry
+    /// an encoding); `None` for other values or other encodings
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(bytes) => std::str::from_utf8(bytes).ok(),
+            Value::Ivars { value, ivars } => {
+                let utf8 = ivars.iter().all(|(name, v)| match name.name.as_str() {
+                    "encoding" => v.as_str().is_some_and(|e| e.eq_ignore_ascii_case("UTF-8")),
+                    _ => true,
+                });
+                if utf8 {
+                    value.as_str()
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Replace the text of a string, keeping its encoding; returns false for
+    /// values that are not strings
+    pub fn set_str(&mut self, text: &str) -> bool {
+        match self {
+            Value::String(bytes) => {
+                *bytes = text.as_bytes().to_vec();
+                true
+            }
+            Value::Ivars { value, .. } => value.set_str(text),
+            _ => false,
+        }
+    }
+
+    /// UTF-8 string as written by Ruby 1.9+ (`"text".force_encoding("UTF-8")`)
+    #[cfg(test)]
+    pub fn utf8_string(text: &str) -> Value {
+        Value::Ivars {
+            value: Box::new(Value::String(text.as_bytes().to_vec())),
+            ivars: vec![(Symbol::new("E"), Value::True)],
+        }
+    }
+
+    /// Value at a path made of `[index]`, `{hash key}` and `.@ivar` steps, the
+    /// leading dot being optional (e.g. `@events{5}.@pages[0].@list[3]`)
+    pub fn pointer(&self, path: &str) -> Option<&Value> {
+        let mut current = self;
+        for step in parse_path(path)? {
+            current = match step {
+                PathStep::Index(index) => current.as_array()?.get(index)?,
+                PathStep::Key(key) => current
+                    .as_hash()?
+                    .iter()
+                    .find(|(k, _)| k.as_int() == Some(key))
+                    .map(|(_, v)| v)?,
+                PathStep::Ivar(name) => current.ivar(name)?,
+            };
+        }
+        Some(current)
+    }
+
+    /// Mutable value at a path, see `pointer`
+    pub fn pointer_mut(&mut self, path: &str) -> Option<&mut Value> {
+        let mut current = self;
+        for step in parse_path(path)? {
+            current = match step {
+                PathStep::Index(index) => current.array_mut()?.get_mut(index)?,
+                PathStep::Key(key) => current
+                    .hash_mut()?
+                    .iter_mut()
+                    .find(|(k, _)| k.as_int() == Some(key))
+                    .map(|(_, v)| v)?,
+                PathStep::Ivar(name) => current.ivar_mut(name)?,
+            };
+        }
+        Some(current)
+    }
+
+    fn array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(items) => Some(items),
+            Value::Ivars { value, .. } | Value::UserClass { value, .. } => value.array_mut(),
+            _ => None,
+        }
+    }
+
+    fn hash_mut(&mut self) -> Option<&mut Vec<(Value, Value)>> {
+        match self {
+            Value::Hash { pairs, .. } => Some(pairs),
+            Value::Ivars { value, .. } | Value::UserClass { value, .. } => value.hash_mut(),
+            _ => None,
+        }
+    }
+}
+
+enum PathStep<'a> {
+    Index(usize),
+    Key(i32),
+    Ivar(&'a str),
+}
+
+fn parse_path(path: &str) -> Option<Vec<PathStep<'_>>> {
+    let mut steps = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            steps.push(PathStep::Index(after[..end].parse().ok()?));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('{') {
+            let end = after.find('}')?;
+            steps.push(PathStep::Key(after[..end].parse().ok()?));
+            rest = &after[end + 1..];
+        } else if rest.starts_with('@') || rest.starts_with(".@") {
+            let after = rest.trim_start_matches('.');
+            let end = after.find(['.', '[', '{']).unwrap_or(after.len());
+            steps.push(PathStep::Ivar(&after[..end]));
+            rest = &after[end..];
+        } else {
+            return None;
+        }
+    }
+    Some(steps)
+}
+
+/// Parse a Marshal stream (the whole content of an RGSS data file)
+pub fn load(bytes: &[u8]) -> AppResult<Value> {
+    let mut reader = Reader {
+        bytes,
+        pos: 0,
+        symbols: Vec::new(),
+        depth: 0,
+    };
+    let major = reader.byte()?;
+    let minor = reader.byte()?;
+    if major != MAJOR_VERSION || minor > MINOR_VERSION {
+        return Err(AppError::Parsing(format!(
+            "Unsupported Marshal version {}.{}",
+            major, minor
+        )));
+    }
+    let value = reader.value()?;
+    if reader.pos != bytes.len() {
+        return Err(AppError::Parsing(format!(
+            "Unexpected data after Marshal value at offset {}",
+            reader.pos
+        )));
+    }
+    Ok(value)
+}
+
+/// Serialize a value into a Marshal stream
+pub fn dump(value: &Value) -> Vec<u8> {
+    let mut writer = Writer {
+        out: vec![MAJOR_VERSION, MINOR_VERSION],
+        symbols: HashMap::new(),
+    };
+    writer.value(value);
+    writer.out
+}
+
+/// Nesting limit of values, so that hostile files cannot overflow the stack
+const MAX_DEPTH: usize = 64;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    symbols: Vec<Symbol>,
+    /// Values and symbols being read
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self, message: &str) -> AppError {
+        AppError::Parsing(format!("Marshal: {} at offset {}", message, self.pos))
+    }
+
+    /// Run `read` one nesting level deeper
+    fn nested<T>(&mut self, read: impl FnOnce(&mut Self) -> AppResult<T>) -> AppResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("values nested too deeply"));
+        }
+        self.depth += 1;
+        let result = read(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn byte(&mut self) -> AppResult<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> AppResult<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Packed integer (`w_long` in marshal.c)
+    fn long(&mut self) -> AppResult<i32> {
+        let c = self.byte()? as i8;
+        let value = match c {
+            0 => 0,
+            1..=4 => {
+                let mut x: i64 = 0;
+                for i in 0..c as u32 {
+                    x |= (self.byte()? as i64) << (8 * i);
+                }
+                x
+            }
+            -4..=-1 => {
+                let mut x: i64 = -1;
+                for i in 0..(-c) as u32 {
+                    x &= !(0xff << (8 * i));
+                    x |= (self.byte()? as i64) << (8 * i);
+                }
+                x
+            }
+            5.. => c as i64 - 5,
+            _ => c as i64 + 5,
+        };
+        i32::try_from(value).map_err(|_| self.error("integer out of range"))
+    }
+
+    fn len(&mut self) -> AppResult<usize> {
+        usize::try_from(self.long()?).map_err(|_| self.error("negative length"))
+    }
+
+    fn byte_string(&mut self) -> AppResult<Vec<u8>> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn symbol(&mut self) -> AppResult<Symbol> {
+        match self.byte()? {
+            b':' => self.symbol_body(),
+            b';' => {
+                let index = self.len()?;
+                self.symbols
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| self.error("invalid symbol link"))
+            }
+            b'I' => {
+                // Symbol with an encoding, which is only written with its first
+                // occurrence: keep it in the table so that links resolve to it
+                let index = self.symbols.len();
+                let mut symbol = self.nested(Self::symbol)?;
+                if index >= self.symbols.len() {
+                    return Err(self.error("symbol link with an encoding"));
+                }
+                symbol.ivars = self.ivars()?;
+                self.symbols[index] = symbol.clone();
+                Ok(symbol)
+            }
+            _ => Err(self.error("expected a symbol")),
+        }
+    }
+
+    fn symbol_body(&mut self) -> AppResult<Symbol> {
+        let bytes = self.byte_string()?;
+        let name = String::from_utf8(bytes).map_err(|_| self.error("symbol is not UTF-8"))?;
+        let symbol = Symbol {
+            name,
+            ivars: Vec::new(),
+        };
+        self.symbols.push(symbol.clone());
+        Ok(symbol)
+    }
+
+    fn ivars(&mut self) -> AppResult<Vec<(Symbol, Value)>> {
+        let count = self.len()?;
+        let mut ivars = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let name = self.symbol()?;
+            ivars.push((name, self.value()?));
+        }
+        Ok(ivars)
+    }
+
+    fn value(&mut self) -> AppResult<Value> {
+        self.nested(Self::tagged_value)
+    }
+
+    fn tagged_value(&mut self) -> AppResult<Value> {
+        let value = match self.byte()? {
+            b'0' => Value::Nil,
+            b'T' => Value::True,
+            b'F' => Value::False,
+            b'i' => Value::Int(self.long()?),
+            b':' => Value::Symbol(self.symbol_body()?),
+            b';' => {
+                self.pos -= 1;
+                Value::Symbol(self.symbol()?)
+            }
+            b'@' => Value::Link(self.len()?),
+            b'I' if self.bytes.get(self.pos) == Some(&b':') => {
+                self.pos -= 1;
+                Value::Symbol(self.symbol()?)
+            }
+            b'I' => {
+                let value = Box::new(self.value()?);
+                Value::Ivars {
+                    value,
+                    ivars: self.ivars()?,
+                }
+            }
+            b'"' => Value::String(self.byte_string()?),
+            b'f' => Value::Float(self.byte_string()?),
+            b'l' => {
+                let negative = match self.byte()? {
+                    b'-' => true,
+                    b'+' => false,
+                    _ => return Err(self.error("invalid bignum sign")),
+                };
+                let len = self.len()?;
+                let bytes = self.take(len * 2)?.to_vec();
+                Value::Bignum { negative, bytes }
+            }
+            b'/' => {
+                let source = self.byte_string()?;
+                Value::Regexp {
+                    source,
+                    options: self.byte()?,
+                }
+            }
+            b'[' => {
+                let count = self.len()?;
+                let mut items = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    items.push(self.value()?);
+                }
+                Value::Array(items)
+            }
+            tag @ (b'{' | b'}') => {
+                let count = self.len()?;
+                let mut pairs = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    let key = self.value()?;
+                    pairs.push((key, self.value()?));
+                }
+                let default = if tag == b'}' {
+                    Some(Box::new(self.value()?))
+                } else {
+                    None
+                };
+                Value::Hash { pairs, default }
+            }
+            b'o' => {
+                let class = self.symbol()?;
+                Value::Object {
+                    class,
+                    ivars: self.ivars()?,
+                }
+            }
+            b'S' => {
+                let class = self.symbol()?;
+                Value::Struct {
+                    class,
+                    members: self.ivars()?,
+                }
+            }
+            b'u' => {
+                let class = self.symbol()?;
+                Value::UserDef {
+                    class,
+                    data: self.byte_string()?,
+                }
+            }
+            b'U' => {
+                let class = self.symbol()?;
+                Value::UserMarshal {
+                    class,
+                    data: Box::new(self.value()?),
+                }
+            }
+            b'd' => {
+                let class = self.symbol()?;
+                Value::Data {
+                    class,
+                    data: Box::new(self.value()?),
+                }
+            }
+            b'c' => Value::Class(self.byte_string()?),
+            b'm' => Value::Module(self.byte_string()?),
+            b'M' => Value::OldModule(self.byte_string()?),
+            b'e' => {
+                let module = self.symbol()?;
+                Value::Extended {
+                    module,
+                    value: Box::new(self.value()?),
+                }
+            }
+            b'C' => {
+                let class = self.symbol()?;
+                Value::UserClass {
+                    class,
+                    value: Box::new(self.value()?),
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("unknown type tag"));
+            }
+        };
+        Ok(value)
+    }
+}
+
+struct Writer {
+    out: Vec<u8>,
+    symbols: HashMap<String, usize>,
+}
+
+impl Writer {
+    fn long(&mut self, value: i32) {
+        match value {
+            0 => self.out.push(0),
+            1..=122 => self.out.push((value + 5) as u8),
+            -123..=-1 => self.out.push((value - 5) as u8),
+            _ => {
+                let bytes = value.to_le_bytes();
+                let fill = if value < 0 { 0xff } else { 0 };
+                let len = 4 - bytes.iter().rev().take_while(|b| **b == fill).count();
+                let count = len as i8;
+                self.out.push(if value < 0 { -count } else { count } as u8);
+                self.out.extend_from_slice(&bytes[..len]);
+            }
+        }
+    }
+
+    fn byte_string(&mut self, bytes: &[u8]) {
+        self.long(bytes.len() as i32);
+        self.out.extend_from_slice(bytes);
+    }
+
+    /// Symbol, or a link to it after its first occurrence (which alone
+    /// carries the encoding)
+    fn symbol(&mut self, symbol: &Symbol) {
+        if let Some(index) = self.symbols.get(&symbol.name) {
+            self.out.push(b';');
+            self.long(*index as i32);
+            return;
+        }
+        self.symbols.insert(symbol.name.clone(), self.symbols.len());
+        let encoded = !symbol.ivars.is_empty();
+        if encoded {
+            self.out.push(b'I');
+        }
+        self.out.push(b':');
+        self.byte_string(symbol.name.as_bytes());
+        if encoded {
+            self.ivars(&symbol.ivars);
+        }
+    }
+
+    fn ivars(&mut self, ivars: &[(Symbol, Value)]) {
+        self.long(ivars.len() as i32);
+        for (name, value) in ivars {
+            self.symbol(name);
+            self.value(value);
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Nil => self.out.push(b'0'),
+            Value::True => self.out.push(b'T'),
+            Value::False => self.out.push(b'F'),
+            Value::Int(i) => {
+                self.out.push(b'i');
+                self.long(*i);
+            }
+            Value::Float(text) => {
+                self.out.push(b'f');
+                self.byte_string(text);
+            }
+            Value::Bignum { negative, bytes } => {
+                self.out.push(b'l');
+                self.out.push(if *negative { b'-' } else { b'+' });
+                self.long((bytes.len() / 2) as i32);
+                self.out.extend_from_slice(bytes);
+            }
+            Value::Symbol(symbol) => self.symbol(symbol),
+            Value::String(bytes) => {
+                self.out.push(b'"');
+                self.byte_string(bytes);
+            }
+            Value::Regexp { source, options } => {
+                self.out.push(b'/');
+                self.byte_string(source);
+                self.out.push(*options);
+            }
+            Value::Array(items) => {
+                self.out.push(b'[');
+                self.long(items.len() as i32);
+                for item in items {
+                    self.value(item);
+                }
+            }
+            Value::Hash { pairs, default } => {
+                self.out.push(if default.is_some() { b'}' } else { b'{' });
+                self.long(pairs.len() as i32);
+                for (key, value) in pairs {
+                    self.value(key);
+                    self.value(value);
+                }
+                if let Some(default) = default {
+                    self.value(default);
+                }
+            }
+            Value::Object { class, ivars } => {
+                self.out.push(b'o');
+                self.symbol(class);
+                self.ivars(ivars);
+            }
+            Value::Struct { class, members } => {
+                self.out.push(b'S');
+                self.symbol(class);
+                self.ivars(members);
+            }
+            Value::UserDef { class, data } => {
+                self.out.push(b'u');
+                self.symbol(class);
+                self.byte_string(data);
+            }
+            Value::UserMarshal { class, data } => {
+                self.out.push(b'U');
+                self.symbol(class);
+                self.value(data);
+            }
+            Value::Data { class, data } => {
+                self.out.push(b'd');
+                self.symbol(class);
+                self.value(data);
+            }
+            Value::Class(name) => {
+                self.out.push(b'c');
+                self.byte_string(name);
+            }
+            Value::Module(name) => {
+                self.out.push(b'm');
+                self.byte_string(name);
+            }
+            Value::OldModule(name) => {
+                self.out.push(b'M');
+                self.byte_string(name);
+            }
+            Value::Extended { module, value } => {
+                self.out.push(b'e');
+                self.symbol(module);
+                self.value(value);
+            }
+            Value::UserClass { class, value } => {
+                self.out.push(b'C');
+                self.symbol(class);
+                self.value(value);
+            }
+            Value::Ivars { value, ivars } => {
+                self.out.push(b'I');
+                self.value(value);
+                self.ivars(ivars);
+            }
+            Value::Link(index) => {
+                self.out.push(b'@');
+                self.long(*index as i32);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_integers_and_symbols_round_trip() {
+        // Marshal.dump([1, -1, 300, -300, 70000, :a, :a])
+        let bytes = [
+            0x04, 0x08, 0x5b, 0x0c, 0x69, 0x06, 0x69, 0xfa, 0x69, 0x02, 0x2c, 0x01, 0x69, 0xfe,
+            0xd4, 0xfe, 0x69, 0x03, 0x70, 0x11, 0x01, 0x3a, 0x06, 0x61, 0x3b, 0x00,
+        ];
+        let value = load(&bytes).unwrap();
+        let items = value.as_array().unwrap();
+        let ints: Vec<i32> = items.iter().filter_map(Value::as_int).collect();
+        assert_eq!(ints, vec![1, -1, 300, -300, 70000]);
+        assert_eq!(items[5], Value::Symbol(Symbol::new("a")));
+        assert_eq!(items[6], Value::Symbol(Symbol::new("a")));
+        assert_eq!(dump(&value), bytes);
+    }
+
+    #[test]
+    fn test_encoded_symbols_round_trip() {
+        // Marshal.dump({:名前 => 1, :a => :名前}) from Ruby 1.9+: the first
+        // occurrence carries the encoding, the second is a plain link
+        let bytes = [
+            0x04, 0x08, 0x7b, 0x07, 0x49, 0x3a, 0x0b, 0xe5, 0x90, 0x8d, 0xe5, 0x89, 0x8d, 0x06,
+            0x3a, 0x06, 0x45, 0x54, 0x69, 0x06, 0x3a, 0x06, 0x61, 0x3b, 0x00,
+        ];
+        let value = load(&bytes).unwrap();
+        let pairs = value.as_hash().unwrap();
+        let name = Symbol {
+            name: "名前".to_string(),
+            ivars: vec![(Symbol::new("E"), Value::True)],
+        };
+        assert_eq!(pairs[0].0, Value::Symbol(name.clone()));
+        assert_eq!(pairs[1].1, Value::Symbol(name));
+        assert_eq!(dump(&value), bytes);
+
+        // [:A, object of class I;0] puts an encoding on a link, which Ruby rejects
+        let linked = [
+            0x04, 0x08, 0x5b, 0x07, 0x3a, 0x06, 0x41, 0x6f, 0x49, 0x3b, 0x00, 0x00,
+        ];
+        let error = load(&linked).unwrap_err();
+        assert!(
+            error.to_string().contains("link with an encoding"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting_is_an_error() {
+        // 100000 nested one-element arrays, then the same with encoded symbols
+        let mut arrays = vec![MAJOR_VERSION, MINOR_VERSION];
+        arrays.extend([b'[', 0x06].repeat(100_000));
+        arrays.push(b'0');
+        let error = load(&arrays).unwrap_err();
+        assert!(error.to_string().contains("nested too deeply"), "{}", error);
+
+        let mut symbols = vec![MAJOR_VERSION, MINOR_VERSION, b'o'];
+        symbols.extend([b'I'].repeat(100_000));
+        symbols.extend([b':', 0x06, b'A']);
+        let error = load(&symbols).unwrap_err();
+        assert!(error.to_string().contains("nested too deeply"), "{}", error);
+    }
+
+    #[test]
+    fn test_object_strings_edit_in_place() {
+        // [nil, RPG::Actor with @name = "勇者" (UTF-8) and a shared @nickname link]
+        let actor = Value::Object {
+            class: Symbol::new("RPG::Actor"),
+            ivars: vec![
+                (Symbol::new("@id"), Value::Int(1)),
+                (Symbol::new("@name"), Value::utf8_string("勇者")),
+                (Symbol::new("@nickname"), Value::Link(2)),
+                (Symbol::new("@exp"), Value::Float(b"1.5".to_vec())),
+            ],
+        };
+        let data = Value::Array(vec![Value::Nil, actor]);
+        let bytes = dump(&data);
+        let mut loaded = load(&bytes).unwrap();
+        assert_eq!(loaded, data);
+        assert_eq!(
+            loaded.pointer("[1].@name").and_then(Value::as_str),
+            Some("勇者")
+        );
+
+        assert!(loaded.pointer_mut("[1].@name").unwrap().set_str("Hero"));
+        let edited = dump(&loaded);
+        let reloaded = load(&edited).unwrap();
+        assert_eq!(
+            reloaded.pointer("[1].@name").and_then(Value::as_str),
+            Some("Hero")
+        );
+        assert_eq!(reloaded.pointer("[1].@nickname"), Some(&Value::Link(2)));
+        // The encoding flag and the symbol table are written back unchanged
+        assert!(edited.ends_with(b"\x3a\x09@expf\x081.5"));
+        assert!(edited.windows(5).any(|w| w == b"\x06:\x06ET"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod engine;
+pub mod files;
+pub mod marshal;
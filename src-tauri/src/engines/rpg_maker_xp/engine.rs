@@ -0,0 +1,114 @@
+use std::any::Any;
+use std::path::Path;
+
+use crate::core::engine::Engine;
+use crate::core::error::AppResult;
+use crate::engines::registry::{EngineCapabilities, EngineRegistration};
+use crate::engines::rpg_maker_vxace::files::{self, read_game_ini, XP};
+use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
+use crate::models::language::Language;
+use crate::models::translation::TextUnit;
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::rpg_maker_xp_formatter::RpgMakerXpFormatter;
+use crate::utils::text::types::RawTextUnit;
+
+/// Implementation of the Engine trait for RPG Maker XP games.
+///
+/// Shares the Marshal layer of VX Ace; only the class layouts and the
+/// "Show Text" command, which carries the first message line, differ.
+/// Games packed into `Game.rgssad` must be unpacked first.
+pub struct RpgMakerXpEngine {
+    detection_criteria: EngineCriteria,
+}
+
+impl RpgMakerXpEngine {
+    pub fn new() -> Self {
+        Self {
+            detection_criteria: Self::get_detection_criteria(),
+        }
+    }
+
+    /// An unpacked XP project: `Game.ini` next to a `Data` folder of `.rxdata` files
+    pub fn get_detection_criteria() -> EngineCriteria {
+        EngineCriteria {
+            required_files: vec!["Game.ini".to_string(), "Data/System.rxdata".to_string()],
+            required_folders: vec!["Data".to_string()],
+            extra_files: vec!["Game.exe".to_string(), "Game.rgssad".to_string()],
+            export_data_roots: vec!["Data".to_string()],
+        }
+    }
+
+    /// Registration of the engine: unpacked `.rxdata` data, or an RGSS1 library for packed games
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "RpgMakerXp",
+            engine_type: EngineType::RpgMakerXp,
+            create: || Box::new(Self::new()),
+            detect: |path| files::rgss_detection_score(path, &Self::get_detection_criteria(), 1),
+            formatter: EngineTextFormatter::of::<RpgMakerXpFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: false,
+                minimal_export: true,
+                replacement_font: false,
+            },
+        }
+    }
+}
+
+impl Engine for RpgMakerXpEngine {
+    fn load_project_info(
+        &self,
+        path: &Path,
+        source_language: Language,
+        target_language: Language,
+    ) -> AppResult<EngineInfo> {
+        let game_ini = read_game_ini(path);
+        let name = game_ini.title.unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("XP Project")
+                .to_string()
+        });
+
+        Ok(EngineInfo {
+            name,
+            path: path.to_path_buf(),
+            engine_type: EngineType::RpgMakerXp,
+            source_language,
+            target_language,
+            version: game_ini.library,
+            detection_criteria: self.detection_criteria.clone(),
+            manifest_hash: None,
+            settings: None,
+        })
+    }
+
+    fn get_detection_criteria(&self) -> EngineCriteria {
+        self.detection_criteria.clone()
+    }
+
+    fn extract_raw_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<RawTextUnit>> {
+        files::extract_project(&XP, &project_info.path)
+    }
+
+    fn inject_raw_text_units(
+        &self,
+        project_info: &EngineInfo,
+        raw_units: &[RawTextUnit],
+    ) -> AppResult<()> {
+        files::inject_project(&project_info.path, raw_units)
+    }
+
+    fn reconstruct_text_unit_id(
+        &self,
+        field_type: &str,
+        source_text: &str,
+        translated_text: &str,
+    ) -> AppResult<TextUnit> {
+        files::reconstruct_text_unit(&XP, field_type, source_text, translated_text)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,285 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::core::engine::Engine;
+use crate::core::error::{AppError, AppResult};
+use crate::engines::registry::{criteria_score, EngineCapabilities, EngineRegistration};
+use crate::engines::tyrano_script::script::{self, EntryKind, ScriptEntry};
+use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
+use crate::models::language::Language;
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus, UnitPosition};
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::tyrano_script_formatter::TyranoScriptFormatter;
+use crate::utils::text::types::RawTextUnit;
+
+/// Folder holding the scenario scripts
+const SCENARIO_DIR: &str = "data/scenario";
+
+/// Project settings, holding the game title
+const CONFIG_FILE: &str = "data/system/Config.tjs";
+
+/// Scenario paths with their sources
+type Scenarios = Vec<(String, String)>;
+
+/// `;System.title = ...;` in `Config.tjs`
+static TITLE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^;System\.title\s*=\s*(.*?)\s*;?\s*$").unwrap());
+
+/// Implementation of the Engine trait for TyranoScript and TyranoBuilder games.
+///
+/// Scenarios (`data/scenario/**/*.ks`) are read by the `script` module.
+/// Translations are written over the text they replace only, so tags, code
+/// blocks and every other line keep their exact bytes.
+pub struct TyranoScriptEngine {
+    detection_criteria: EngineCriteria,
+}
+
+impl TyranoScriptEngine {
+    pub fn new() -> Self {
+        Self {
+            detection_criteria: Self::get_detection_criteria(),
+        }
+    }
+
+    /// A TyranoScript game: a `data/scenario` folder with `.ks` scenarios
+    pub fn get_detection_criteria() -> EngineCriteria {
+        EngineCriteria {
+            required_files: vec![],
+            required_folders: vec![SCENARIO_DIR.to_string()],
+            extra_files: vec![CONFIG_FILE.to_string(), "index.html".to_string()],
+            export_data_roots: vec![format!("{}/**/*.ks", SCENARIO_DIR)],
+        }
+    }
+
+    /// Registration of the engine: `.ks` scenarios in `data/scenario`
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "TyranoScript",
+            engine_type: EngineType::TyranoScript,
+            create: || Box::new(Self::new()),
+            detect: |path| {
+                if !Self::is_tyrano_project(path) {
+                    return Ok(0);
+                }
+                criteria_score(path, &Self::get_detection_criteria(), 70)
+            },
+            formatter: EngineTextFormatter::of::<TyranoScriptFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: false,
+                minimal_export: true,
+                replacement_font: false,
+            },
+        }
+    }
+
+    /// Check if this directory holds TyranoScript scenarios
+    pub fn is_tyrano_project(path: &Path) -> bool {
+        !Self::scenario_files(path).is_empty()
+    }
+
+    /// Scenarios of the game, relative to the project root with `/` separators
+    fn scenario_files(project_path: &Path) -> Vec<String> {
+        let mut files: Vec<String> = walkdir::WalkDir::new(project_path.join(SCENARIO_DIR))
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("ks")
+            })
+            .map(|path| {
+                path.strip_prefix(project_path)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn read_scenario(project_path: &Path, file_path: &str) -> AppResult<String> {
+        std::fs::read_to_string(project_path.join(file_path))
+            .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", file_path, e)))
+    }
+
+    /// Every scenario with its source, and the characters declared across
+    /// the game (usually in `first.ks`)
+    fn read_scenarios(project_path: &Path) -> AppResult<(Scenarios, HashMap<String, String>)> {
+        let mut scenarios = Vec::new();
+        let mut characters = HashMap::new();
+        for file_path in Self::scenario_files(project_path) {
+            let source = Self::read_scenario(project_path, &file_path)?;
+            characters.extend(script::character_names(&source));
+            scenarios.push((file_path, source));
+        }
+        Ok((scenarios, characters))
+    }
+
+    fn unit_id(file_path: &str, key: &str) -> String {
+        format!("tyrano:{}#{}", file_path, key)
+    }
+
+    /// Field type label of an entry (`dialogue`, `glink.text`)
+    fn field_label(entry: &ScriptEntry) -> String {
+        match &entry.kind {
+            EntryKind::Dialogue => "dialogue".to_string(),
+            EntryKind::Link => "link".to_string(),
+            EntryKind::Speaker => "speaker".to_string(),
+            EntryKind::Attribute { tag, name } => format!("{}.{}", tag, name),
+        }
+    }
+
+    fn prompt_type_for(label: &str) -> PromptType {
+        match label {
+            "dialogue" | "link" => PromptType::Dialogue,
+            "speaker" | "chara_new.jname" => PromptType::Character,
+            _ => PromptType::System,
+        }
+    }
+}
+
+impl Engine for TyranoScriptEngine {
+    fn load_project_info(
+        &self,
+        path: &Path,
+        source_language: Language,
+        target_language: Language,
+    ) -> AppResult<EngineInfo> {
+        let title = std::fs::read_to_string(path.join(CONFIG_FILE))
+            .ok()
+            .and_then(|config| {
+                TITLE_REGEX
+                    .captures(&config)
+                    .map(|caps| caps[1].trim_matches(|c| c == '"' || c == '\'').to_string())
+            })
+            .filter(|title| !title.is_empty());
+        let name = title.unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("TyranoScript Project")
+                .to_string()
+        });
+
+        Ok(EngineInfo {
+            name,
+            path: path.to_path_buf(),
+            engine_type: EngineType::TyranoScript,
+            source_language,
+            target_language,
+            version: None,
+            detection_criteria: self.detection_criteria.clone(),
+            manifest_hash: None,
+            settings: None,
+        })
+    }
+
+    fn get_detection_criteria(&self) -> EngineCriteria {
+        self.detection_criteria.clone()
+    }
+
+    fn extract_raw_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<RawTextUnit>> {
+        let (scenarios, characters) = Self::read_scenarios(&project_info.path)?;
+        let mut raw_units = Vec::new();
+        for (file_path, source) in scenarios {
+            let entries = script::parse_script(&source, &characters);
+            log::debug!("Extracted {} text units from {}", entries.len(), file_path);
+            for entry in entries {
+                let label = Self::field_label(&entry);
+                raw_units.push(RawTextUnit {
+                    id: Self::unit_id(&file_path, &entry.key),
+                    source_text: entry.text,
+                    field_type: format!("{}:{}:{}", label, file_path, entry.key),
+                    prompt_type: Self::prompt_type_for(&label),
+                    position: Some(UnitPosition {
+                        event_ref: format!(
+                            "{}#{}",
+                            file_path,
+                            entry.label.as_deref().unwrap_or_default()
+                        ),
+                        page_index: 0,
+                        command_index: entry.line as u32,
+                        speaker: entry.speaker,
+                    }),
+                });
+            }
+        }
+        Ok(raw_units)
+    }
+
+    fn inject_raw_text_units(
+        &self,
+        project_info: &EngineInfo,
+        raw_units: &[RawTextUnit],
+    ) -> AppResult<()> {
+        // Group translations by scenario, keyed as in the scenario
+        let mut units_by_file: BTreeMap<&str, HashMap<&str, &str>> = BTreeMap::new();
+        for unit in raw_units {
+            if let Some((file_path, key)) = unit
+                .id
+                .strip_prefix("tyrano:")
+                .and_then(|id| id.split_once('#'))
+            {
+                units_by_file
+                    .entry(file_path)
+                    .or_default()
+                    .insert(key, unit.source_text.as_str());
+            }
+        }
+
+        let (scenarios, characters) = Self::read_scenarios(&project_info.path)?;
+        for (file_path, source) in &scenarios {
+            let Some(translations) = units_by_file.get(file_path.as_str()) else {
+                continue;
+            };
+            let entries = script::parse_script(source, &characters);
+            let translated = script::apply_translations(source, &entries, translations);
+            if translated != *source {
+                std::fs::write(project_info.path.join(file_path), translated).map_err(|e| {
+                    AppError::FileSystem(format!("Failed to write {}: {}", file_path, e))
+                })?;
+            }
+            log::info!(
+                "Injected {} translations into {}",
+                translations.len(),
+                file_path
+            );
+        }
+        Ok(())
+    }
+
+    fn reconstruct_text_unit_id(
+        &self,
+        field_type: &str,
+        source_text: &str,
+        translated_text: &str,
+    ) -> AppResult<TextUnit> {
+        // Field types are "label:file:key", e.g. "glink.text:data/scenario/scene1.ks:L14:0:text"
+        let mut parts = field_type.splitn(3, ':');
+        let (Some(label), Some(file_path), Some(key)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(AppError::Other(format!(
+                "Invalid TyranoScript field_type format: {}",
+                field_type
+            )));
+        };
+
+        Ok(TextUnit {
+            id: Self::unit_id(file_path, key),
+            source_text: source_text.to_string(),
+            translated_text: translated_text.to_string(),
+            field_type: field_type.to_string(),
+            status: TranslationStatus::MachineTranslated,
+            prompt_type: Self::prompt_type_for(label),
+            position: None,
+            detected_language: None,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
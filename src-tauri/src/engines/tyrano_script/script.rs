@@ -0,0 +1,383 @@
+//! TyranoScript scenario (`.ks`) reading and writing.
+//!
+//! Scenarios are line based: `;` starts a comment, `*name` a label, `@tag` a
+//! tag on its own line and `#name` the speaker of the following text; other
+//! lines are text with inline `[tag]`s. `[iscript]`…`[endscript]`,
+//! `[html]`…`[endhtml]` and `/* */` blocks are skipped.
+//!
+//! Entries record the byte range of their text, so writing a translation
+//! replaces exactly that range and every other byte of the file is kept.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Kind of translatable scenario text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Text line: dialogue or narration
+    Dialogue,
+    /// Text line holding a `[link]` choice
+    Link,
+    /// `#name` line naming a speaker that is not a declared character
+    Speaker,
+    /// Attribute shown to the player (`[glink text="..."]`)
+    Attribute { tag: String, name: String },
+}
+
+/// Translatable text of a scenario
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptEntry {
+    pub kind: EntryKind,
+    /// 1-based line of the text
+    pub line: usize,
+    /// Key of the text within its file (`L12`, `L30:0:text`)
+    pub key: String,
+    /// Scenario label the text belongs to
+    pub label: Option<String>,
+    /// Speaker shown with the text, as displayed
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Bytes replaced by the translation (the quoted value for attributes)
+    span: Range<usize>,
+}
+
+/// Tag attributes holding text shown to the player, as (tag, attribute)
+const TEXT_ATTRIBUTES: &[(&str, &str)] = &[
+    ("chara_new", "jname"),
+    ("glink", "text"),
+    ("ptext", "text"),
+    ("mtext", "text"),
+    ("dialog", "text"),
+];
+
+/// Tags opening a block of code, with the tag closing it
+const CODE_BLOCKS: &[(&str, &str)] = &[("iscript", "endscript"), ("html", "endhtml")];
+
+/// Inline tag: name and attributes (quoted values may contain `]`)
+static TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\[([A-Za-z_][A-Za-z0-9_]*)((?:[^\[\]"'\n]|"[^"\n]*"|'[^'\n]*')*)\]"#).unwrap()
+});
+
+/// Tag on its own line: `@bg storage=room.jpg`
+static LINE_TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^@([A-Za-z_][A-Za-z0-9_]*)(.*)$").unwrap());
+
+static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"([A-Za-z_][A-Za-z0-9_-]*)\s*=\s*("[^"]*"|'[^']*'|[^\s"'\]]+)"#).unwrap()
+});
+
+/// A tag of a line, with its attribute values and their offsets in the line
+struct Tag<'a> {
+    name: &'a str,
+    attributes: Vec<(&'a str, Range<usize>)>,
+}
+
+/// Translatable entries of a scenario, in order
+///
+/// `characters` maps character names to their display names (see
+/// `character_names`); `#name` lines naming one of them are not entries, the
+/// display name is translated where it is declared.
+pub fn parse_script(source: &str, characters: &HashMap<String, String>) -> Vec<ScriptEntry> {
+    let mut entries = Vec::new();
+    let mut label: Option<String> = None;
+    let mut speaker: Option<String> = None;
+    let mut block_end: Option<&str> = None;
+    let mut in_comment = false;
+
+    for (line, text, text_start) in lines(source) {
+        if in_comment {
+            in_comment = !text.contains("*/");
+            continue;
+        }
+        if let Some(end) = block_end {
+            if tags(text).iter().any(|tag| tag.name == end) {
+                block_end = None;
+            }
+            continue;
+        }
+        if text.starts_with("/*") {
+            in_comment = !text.contains("*/");
+            continue;
+        }
+        if text.is_empty() || text.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = text.strip_prefix('*') {
+            label = name.split('|').next().map(|name| name.trim().to_string());
+            continue;
+        }
+        if let Some(name) = text.strip_prefix('#') {
+            // `#name:face` shows `name` with a face; a lone `#` clears it
+            let name_part = name.split(':').next().unwrap_or_default();
+            let name = name_part.trim();
+            speaker = match characters.get(name) {
+                _ if name.is_empty() => None,
+                Some(display_name) => Some(display_name.clone()),
+                None => {
+                    let start = text_start + 1 + (name_part.len() - name_part.trim_start().len());
+                    entries.push(ScriptEntry {
+                        kind: EntryKind::Speaker,
+                        line,
+                        key: format!("L{}", line),
+                        label: label.clone(),
+                        speaker: None,
+                        text: name.to_string(),
+                        span: start..start + name.len(),
+                    });
+                    Some(name.to_string())
+                }
+            };
+            continue;
+        }
+
+        let line_tags = tags(text);
+        if let Some((_, end)) = CODE_BLOCKS
+            .iter()
+            .find(|(start, _)| line_tags.iter().any(|tag| tag.name == *start))
+        {
+            if !line_tags.iter().any(|tag| tag.name == *end) {
+                block_end = Some(end);
+            }
+            continue;
+        }
+
+        let visible = TAG_REGEX.replace_all(text, "");
+        if !text.starts_with('@') && !visible.trim().is_empty() {
+            // Choices are not said by the current speaker
+            let (kind, speaker) = if line_tags.iter().any(|tag| tag.name == "link") {
+                (EntryKind::Link, None)
+            } else {
+                (EntryKind::Dialogue, speaker.clone())
+            };
+            entries.push(ScriptEntry {
+                kind,
+                line,
+                key: format!("L{}", line),
+                label: label.clone(),
+                speaker,
+                text: text.to_string(),
+                span: text_start..text_start + text.len(),
+            });
+            continue;
+        }
+
+        for (tag_index, tag) in line_tags.iter().enumerate() {
+            for (name, range) in &tag.attributes {
+                if !TEXT_ATTRIBUTES.contains(&(tag.name, *name)) {
+                    continue;
+                }
+                let value = unquote(&text[range.clone()]);
+                // `&` makes the value an expression rather than text
+                if value.trim().is_empty() || value.starts_with('&') {
+                    continue;
+                }
+                entries.push(ScriptEntry {
+                    kind: EntryKind::Attribute {
+                        tag: tag.name.to_string(),
+                        name: name.to_string(),
+                    },
+                    line,
+                    key: format!("L{}:{}:{}", line, tag_index, name),
+                    label: label.clone(),
+                    speaker: None,
+                    text: value.to_string(),
+                    span: text_start + range.start..text_start + range.end,
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Display names of the characters declared with `chara_new`, by name
+pub fn character_names(source: &str) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for (_, text, _) in lines(source) {
+        for tag in tags(text).iter().filter(|tag| tag.name == "chara_new") {
+            let attribute = |wanted: &str| {
+                tag.attributes
+                    .iter()
+                    .find(|(name, _)| *name == wanted)
+                    .map(|(_, range)| unquote(&text[range.clone()]).to_string())
+            };
+            if let (Some(name), Some(jname)) = (attribute("name"), attribute("jname")) {
+                names.insert(name, jname);
+            }
+        }
+    }
+    names
+}
+
+/// The scenario with translations, by entry key, written over their text
+///
+/// Line breaks in a translation become `[r]` so a text line stays one line.
+pub fn apply_translations(
+    source: &str,
+    entries: &[ScriptEntry],
+    translations: &HashMap<&str, &str>,
+) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut copied = 0;
+    for entry in entries {
+        let Some(translation) = translations.get(entry.key.as_str()) else {
+            continue;
+        };
+        let replacement = match &entry.kind {
+            EntryKind::Attribute { .. } => quote(translation),
+            EntryKind::Speaker => translation.trim().to_string(),
+            EntryKind::Dialogue | EntryKind::Link => {
+                translation.replace("\r\n", "[r]").replace('\n', "[r]")
+            }
+        };
+        out.push_str(&source[copied..entry.span.start]);
+        out.push_str(&replacement);
+        copied = entry.span.end;
+    }
+    out.push_str(&source[copied..]);
+    out
+}
+
+/// Lines of a scenario as (1-based number, trimmed text, offset of the text)
+fn lines(source: &str) -> impl Iterator<Item = (usize, &str, usize)> {
+    let mut offset = 0;
+    source
+        .split_inclusive('\n')
+        .enumerate()
+        .map(move |(index, raw)| {
+            let mut start = offset;
+            offset += raw.len();
+            let mut content = raw.trim_end_matches(['\r', '\n']);
+            if index == 0 {
+                if let Some(rest) = content.strip_prefix('\u{feff}') {
+                    start += '\u{feff}'.len_utf8();
+                    content = rest;
+                }
+            }
+            let leading = content.len() - content.trim_start().len();
+            (index + 1, content.trim(), start + leading)
+        })
+}
+
+/// Tags of a trimmed line: the `@` tag or the inline ones
+fn tags(text: &str) -> Vec<Tag<'_>> {
+    if let Some(caps) = LINE_TAG_REGEX.captures(text) {
+        return caps
+            .get(1)
+            .zip(caps.get(2))
+            .map(|(name, attributes)| vec![tag(name, attributes)])
+            .unwrap_or_default();
+    }
+    TAG_REGEX
+        .captures_iter(text)
+        .filter_map(|caps| Some(tag(caps.get(1)?, caps.get(2)?)))
+        .collect()
+}
+
+fn tag<'a>(name: regex::Match<'a>, attributes: regex::Match<'a>) -> Tag<'a> {
+    Tag {
+        name: name.as_str(),
+        attributes: ATTRIBUTE_REGEX
+            .captures_iter(attributes.as_str())
+            .filter_map(|caps| {
+                let name = caps.get(1)?.as_str();
+                let value = caps.get(2)?;
+                let start = attributes.start() + value.start();
+                Some((name, start..start + value.len()))
+            })
+            .collect(),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote_char in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote_char)
+            .and_then(|v| v.strip_suffix(quote_char))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Quote an attribute value; values have no escapes, so a value holding
+/// double quotes is single-quoted, and a value holding both loses its
+/// double quotes
+fn quote(value: &str) -> String {
+    match (value.contains('"'), value.contains('\'')) {
+        (false, _) => format!("\"{}\"", value),
+        (true, false) => format!("'{}'", value),
+        (true, true) => format!("\"{}\"", value.replace('"', "'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = "\u{feff};ゲーム開始\r\n\
+*start|タイトル\r\n\
+[chara_new name=\"akane\" storage=\"chara/akane.png\" jname=\"あかね\"]\r\n\
+@bg storage=room.jpg time=500\r\n\
+#akane:happy\r\n\
+こんにちは。[l][r]\r\n\
+  今日はいい天気ですね。[p]\r\n\
+#店員\r\n\
+いらっしゃいませ。[p]\r\n\
+[iscript]\r\n\
+f.text = \"スクリプト\";\r\n\
+[endscript]\r\n\
+[link target=*yes]はい[endlink][r]\r\n\
+[glink text=\"いいえ\" target=*no x=100]\r\n\
+[ptext text=&f.name layer=0]\r\n";
+
+    #[test]
+    fn test_parse_scenario() {
+        let characters = character_names(SCENARIO);
+        assert_eq!(characters.get("akane").map(String::as_str), Some("あかね"));
+
+        let entries = parse_script(SCENARIO, &characters);
+        let summary: Vec<(&str, &str, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.key.as_str(), e.text.as_str(), e.speaker.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("L3:0:jname", "あかね", None),
+                ("L6", "こんにちは。[l][r]", Some("あかね")),
+                ("L7", "今日はいい天気ですね。[p]", Some("あかね")),
+                ("L8", "店員", None),
+                ("L9", "いらっしゃいませ。[p]", Some("店員")),
+                ("L13", "[link target=*yes]はい[endlink][r]", None),
+                ("L14:0:text", "いいえ", None),
+            ]
+        );
+        assert_eq!(entries[5].kind, EntryKind::Link);
+        assert_eq!(entries[1].label.as_deref(), Some("start"));
+    }
+
+    #[test]
+    fn test_apply_translations_keeps_other_bytes() {
+        let entries = parse_script(SCENARIO, &character_names(SCENARIO));
+        let translations = HashMap::from([
+            ("L3:0:jname", "Akane"),
+            ("L7", "Nice weather\ntoday.[p]"),
+            ("L8", "Clerk"),
+            ("L14:0:text", "No \"thanks\""),
+        ]);
+        let written = apply_translations(SCENARIO, &entries, &translations);
+
+        let expected = SCENARIO
+            .replace("jname=\"あかね\"", "jname=\"Akane\"")
+            .replace("今日はいい天気ですね。[p]", "Nice weather[r]today.[p]")
+            .replace("#店員", "#Clerk")
+            .replace("text=\"いいえ\"", "text='No \"thanks\"'");
+        assert_eq!(written, expected);
+        assert!(written.starts_with("\u{feff};ゲーム開始\r\n"));
+    }
+}
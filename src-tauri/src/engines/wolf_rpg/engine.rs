@@ -3,11 +3,34 @@ use std::path::{Path, PathBuf};
 
 use crate::core::engine::Engine;
 use crate::core::error::{AppError, AppResult};
+use crate::engines::registry::{EngineCapabilities, EngineRegistration};
 use crate::models::engine::{EngineCriteria, EngineInfo, EngineType};
 use crate::models::language::Language;
 use crate::models::translation::TextUnit;
+use crate::utils::text::engines::formatter_trait::EngineTextFormatter;
+use crate::utils::text::engines::wolf_rpg_formatter::WolfRpgFormatter;
 use crate::utils::text::types::RawTextUnit;
 
+use super::files::common_events::CommonEvents;
+use super::files::database::Database;
+use super::files::game_dat::GameDat;
+use super::files::map_data::MapData;
+
+/// Unencrypted data folders read when there is no WolfTL dump
+const MAP_DATA_DIR: &str = "Data/MapData";
+const BASIC_DATA_DIR: &str = "Data/BasicData";
+const PROJECT_EXTENSION: &str = "project";
+
+const COMMON_EVENT_FILE: &str = "CommonEvent.dat";
+const GAME_DAT_FILE: &str = "Game.dat";
+
+/// Common events and Game.dat settings inside a WolfTL dump
+const DUMP_COMMON_DIR: &str = "common";
+const DUMP_GAME_DAT_FILE: &str = "GameDat.json";
+
+/// Databases holding translatable text
+const DATABASE_NAMES: [&str; 3] = ["CDataBase", "DataBase", "SysDatabase"];
+
 pub struct WolfRpgEngine {
     detection_criteria: EngineCriteria,
 }
@@ -19,21 +42,58 @@ impl WolfRpgEngine {
         }
     }
 
-    /// A Wolf RPG project with manually created dump folder.
-    /// User handles .wolf decryption and WolfTL dump creation externally.
+    /// A Wolf RPG project, either with a dump folder created by WolfTL or
+    /// with an unencrypted `Data` folder read directly.
+    /// Encrypted `.wolf` archives must be extracted externally first.
     pub fn get_detection_criteria() -> EngineCriteria {
         EngineCriteria {
             required_files: vec![],
-            required_folders: vec!["dump".to_string()], // Require dump folder
+            required_folders: vec![],
             extra_files: vec![],
-            export_data_roots: vec!["dump".to_string()],
+            export_data_roots: vec![
+                "dump".to_string(),
+                "Data/MapData".to_string(),
+                "Data/BasicData".to_string(),
+            ],
+        }
+    }
+
+    /// Registration of the engine: unencrypted data files, or only a WolfTL dump folder
+    pub fn registration() -> EngineRegistration {
+        EngineRegistration {
+            id: "WolfRpg",
+            engine_type: EngineType::WolfRpg,
+            create: || Box::new(Self::new()),
+            detect: |path| {
+                let database = path
+                    .join(BASIC_DATA_DIR)
+                    .join(format!("DataBase.{}", PROJECT_EXTENSION));
+                Ok(if database.is_file() {
+                    80
+                } else if path.join("dump").is_dir() {
+                    50
+                } else {
+                    0
+                })
+            },
+            formatter: EngineTextFormatter::of::<WolfRpgFormatter>(),
+            capabilities: EngineCapabilities {
+                game_data_files: false,
+                minimal_export: true,
+                replacement_font: false,
+            },
         }
     }
 
-    /// Check if this directory contains a Wolf RPG project with dump folder
+    /// Check if this directory contains a Wolf RPG project with a dump
+    /// folder or unencrypted data files
     pub fn is_wolf_rpg_project(path: &Path) -> bool {
-        // Check for dump folder (created by user with WolfTL)
+        // Dump folder (created by user with WolfTL)
         path.join("dump").exists()
+            || path
+                .join(BASIC_DATA_DIR)
+                .join(format!("DataBase.{}", PROJECT_EXTENSION))
+                .is_file()
     }
 
     fn dump_dir(&self, project_path: &Path) -> PathBuf {
@@ -41,6 +101,259 @@ impl WolfRpgEngine {
         project_path.join("dump")
     }
 
+    /// Relative path used in unit IDs
+    fn relative_path(path: &Path, project_path: &Path) -> String {
+        path.strip_prefix(project_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Map files of an unencrypted `Data/MapData` folder
+    fn native_map_files(project_path: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(project_path.join(MAP_DATA_DIR))
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("mps")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// `.project`/`.dat` pairs of an unencrypted `Data/BasicData` folder
+    fn native_database_files(project_path: &Path) -> Vec<(PathBuf, PathBuf)> {
+        let basic_data = project_path.join(BASIC_DATA_DIR);
+        DATABASE_NAMES
+            .iter()
+            .map(|name| {
+                (
+                    basic_data.join(format!("{}.{}", name, PROJECT_EXTENSION)),
+                    basic_data.join(format!("{}.dat", name)),
+                )
+            })
+            .filter(|(project, dat)| project.is_file() && dat.is_file())
+            .collect()
+    }
+
+    fn read_file(path: &Path) -> AppResult<Vec<u8>> {
+        std::fs::read(path)
+            .map_err(|e| AppError::FileSystem(format!("Failed to read {}: {}", path.display(), e)))
+    }
+
+    fn write_file(path: &Path, bytes: &[u8]) -> AppResult<()> {
+        std::fs::write(path, bytes)
+            .map_err(|e| AppError::FileSystem(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Extract text units straight from the binary map and database files
+    fn extract_from_native_files(
+        &self,
+        out: &mut Vec<TextUnit>,
+        project_path: &Path,
+    ) -> AppResult<()> {
+        for path in Self::native_map_files(project_path) {
+            let map = match MapData::read(&Self::read_file(&path)?) {
+                Ok(map) => map,
+                Err(e) => {
+                    log::warn!("Skipping unreadable map {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let rel_path = Self::relative_path(&path, project_path);
+            let mut mps_units =
+                super::files::mps::extract_text_units_from_mps(&map.to_json(), &rel_path);
+            out.append(&mut mps_units);
+        }
+
+        for (project_file, dat_file) in Self::native_database_files(project_path) {
+            let database = Database::read(
+                &Self::read_file(&project_file)?,
+                &Self::read_file(&dat_file)?,
+            )?;
+            let rel_path = Self::relative_path(&dat_file, project_path);
+            let mut db_units =
+                super::files::db::extract_text_units_from_db(&database.to_json(), &rel_path);
+            out.append(&mut db_units);
+        }
+
+        let common_file = project_path.join(BASIC_DATA_DIR).join(COMMON_EVENT_FILE);
+        if common_file.is_file() {
+            match CommonEvents::read(&Self::read_file(&common_file)?) {
+                Ok(common_events) => {
+                    let rel_path = Self::relative_path(&common_file, project_path);
+                    let mut common_units = super::files::common::extract_text_units_from_common(
+                        &common_events.to_json(),
+                        &rel_path,
+                    );
+                    out.append(&mut common_units);
+                }
+                Err(e) => log::warn!("Skipping unreadable {}: {}", common_file.display(), e),
+            }
+        }
+
+        let game_file = project_path.join(BASIC_DATA_DIR).join(GAME_DAT_FILE);
+        if game_file.is_file() {
+            match GameDat::read(&Self::read_file(&game_file)?) {
+                Ok(game) => {
+                    let rel_path = Self::relative_path(&game_file, project_path);
+                    let mut game_units = super::files::game::extract_text_units_from_game_dat(
+                        &game.to_json(),
+                        &rel_path,
+                    );
+                    out.append(&mut game_units);
+                }
+                Err(e) => log::warn!("Skipping unreadable {}: {}", game_file.display(), e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inject text units into the binary map and database files
+    fn inject_into_native_files(
+        &self,
+        text_unit_map: &std::collections::HashMap<String, &TextUnit>,
+        project_path: &Path,
+    ) -> AppResult<()> {
+        for path in Self::native_map_files(project_path) {
+            let mut map = match MapData::read(&Self::read_file(&path)?) {
+                Ok(map) => map,
+                Err(e) => {
+                    log::warn!("Skipping unreadable map {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let rel_path = Self::relative_path(&path, project_path);
+            let mut json = map.to_json();
+            super::files::mps::inject_text_units_into_mps(&mut json, text_unit_map, &rel_path);
+            map.apply_json(&json)?;
+            Self::write_file(&path, &map.write())?;
+        }
+
+        for (project_file, dat_file) in Self::native_database_files(project_path) {
+            let mut database = Database::read(
+                &Self::read_file(&project_file)?,
+                &Self::read_file(&dat_file)?,
+            )?;
+            let rel_path = Self::relative_path(&dat_file, project_path);
+            let mut json = database.to_json();
+            super::files::db::inject_text_units_into_db(&mut json, text_unit_map, &rel_path);
+            database.apply_json(&json)?;
+            let (project_bytes, dat_bytes) = database.write();
+            Self::write_file(&project_file, &project_bytes)?;
+            Self::write_file(&dat_file, &dat_bytes)?;
+        }
+
+        let common_file = project_path.join(BASIC_DATA_DIR).join(COMMON_EVENT_FILE);
+        if common_file.is_file() {
+            match CommonEvents::read(&Self::read_file(&common_file)?) {
+                Ok(mut common_events) => {
+                    let rel_path = Self::relative_path(&common_file, project_path);
+                    let mut json = common_events.to_json();
+                    super::files::common::inject_text_units_into_common(
+                        &mut json,
+                        text_unit_map,
+                        &rel_path,
+                    );
+                    common_events.apply_json(&json)?;
+                    Self::write_file(&common_file, &common_events.write())?;
+                }
+                Err(e) => log::warn!("Skipping unreadable {}: {}", common_file.display(), e),
+            }
+        }
+
+        let game_file = project_path.join(BASIC_DATA_DIR).join(GAME_DAT_FILE);
+        if game_file.is_file() {
+            match GameDat::read(&Self::read_file(&game_file)?) {
+                Ok(mut game) => {
+                    let rel_path = Self::relative_path(&game_file, project_path);
+                    let mut json = game.to_json();
+                    super::files::game::inject_text_units_into_game_dat(
+                        &mut json,
+                        text_unit_map,
+                        &rel_path,
+                    );
+                    game.apply_json(&json)?;
+                    Self::write_file(&game_file, &game.write())?;
+                }
+                Err(e) => log::warn!("Skipping unreadable {}: {}", game_file.display(), e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Dumped JSON files: every `.json` under a directory, or a single file
+    fn dump_json_files(path: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Extract text units from dumped JSON files with the given walker
+    fn extract_from_dump_files(
+        &self,
+        out: &mut Vec<TextUnit>,
+        files: &[PathBuf],
+        project_path: &Path,
+        extract: fn(&serde_json::Value, &str) -> Vec<TextUnit>,
+    ) -> AppResult<()> {
+        for path in files {
+            let raw = std::fs::read_to_string(path).map_err(|e| {
+                AppError::FileSystem(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            let json: serde_json::Value = match serde_json::from_str(&raw) {
+                Ok(v) => v,
+                Err(_) => {
+                    log::debug!("Skipping invalid JSON: {}", path.display());
+                    continue;
+                }
+            };
+            let rel_path = Self::relative_path(path, project_path);
+            out.append(&mut extract(&json, &rel_path));
+        }
+        Ok(())
+    }
+
+    /// Inject text units into dumped JSON files with the given walker
+    fn inject_into_dump_files(
+        &self,
+        text_unit_map: &std::collections::HashMap<String, &TextUnit>,
+        files: &[PathBuf],
+        project_path: &Path,
+        inject: fn(&mut serde_json::Value, &std::collections::HashMap<String, &TextUnit>, &str),
+    ) -> AppResult<()> {
+        for path in files {
+            let raw = std::fs::read_to_string(path).map_err(|e| {
+                AppError::FileSystem(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            let mut json: serde_json::Value = match serde_json::from_str(&raw) {
+                Ok(v) => v,
+                Err(_) => {
+                    log::debug!("Skipping invalid JSON: {}", path.display());
+                    continue;
+                }
+            };
+            let rel_path = Self::relative_path(path, project_path);
+            inject(&mut json, text_unit_map, &rel_path);
+
+            let updated_content = serde_json::to_string_pretty(&json)
+                .map_err(|e| AppError::Parsing(format!("Failed to serialize JSON: {}", e)))?;
+            Self::write_file(path, updated_content.as_bytes())?;
+        }
+        Ok(())
+    }
+
     /// Extract text units from MPS directory using selective processing
     fn extract_from_mps_directory(
         &self,
@@ -259,6 +572,7 @@ impl Engine for WolfRpgEngine {
             version: None,
             detection_criteria: self.detection_criteria.clone(),
             manifest_hash: None,
+            settings: None,
         })
     }
 
@@ -269,17 +583,20 @@ impl Engine for WolfRpgEngine {
     fn extract_raw_text_units(&self, project_info: &EngineInfo) -> AppResult<Vec<RawTextUnit>> {
         let project_path = &project_info.path;
         let dump_dir = self.dump_dir(project_path);
+        let mut out: Vec<TextUnit> = Vec::new();
 
-        // Parse dump JSONs → RawTextUnit (user created dump with WolfTL externally)
+        // Without a WolfTL dump, read the unencrypted data files directly
         if !dump_dir.exists() {
-            return Err(AppError::FileSystem(format!(
-                "WolfTL dump directory not found at {}. Please create dump folder using WolfTL first.",
-                dump_dir.display()
-            )));
+            if !Self::is_wolf_rpg_project(project_path) {
+                return Err(AppError::FileSystem(format!(
+                    "Neither a WolfTL dump at {} nor unencrypted data in {} was found. Extract Data.wolf or create a dump with WolfTL first.",
+                    dump_dir.display(),
+                    project_path.join(BASIC_DATA_DIR).display()
+                )));
+            }
+            self.extract_from_native_files(&mut out, project_path)?;
         }
 
-        let mut out: Vec<TextUnit> = Vec::new();
-
         // Focus on MPS files first (Wolf RPG's main event/map script files)
         let mps_dir = dump_dir.join("mps");
         if mps_dir.exists() {
@@ -292,6 +609,20 @@ impl Engine for WolfRpgEngine {
             self.extract_from_db_directory(&mut out, &db_dir, project_path)?;
         }
 
+        // Common events and Game.dat settings
+        self.extract_from_dump_files(
+            &mut out,
+            &Self::dump_json_files(&dump_dir.join(DUMP_COMMON_DIR)),
+            project_path,
+            super::files::common::extract_text_units_from_common,
+        )?;
+        self.extract_from_dump_files(
+            &mut out,
+            &Self::dump_json_files(&dump_dir.join(DUMP_GAME_DAT_FILE)),
+            project_path,
+            super::files::game::extract_text_units_from_game_dat,
+        )?;
+
         // Convert TextUnits to RawTextUnits
         let raw_units: Vec<RawTextUnit> = out
             .into_iter()
@@ -300,6 +631,7 @@ impl Engine for WolfRpgEngine {
                 source_text: text_unit.source_text,
                 field_type: text_unit.field_type,
                 prompt_type: text_unit.prompt_type,
+                position: text_unit.position,
             })
             .collect();
 
@@ -313,9 +645,9 @@ impl Engine for WolfRpgEngine {
     ) -> AppResult<()> {
         let project_path = &project_info.path;
         let dump_dir = self.dump_dir(project_path);
-        if !dump_dir.exists() {
+        if !Self::is_wolf_rpg_project(project_path) {
             return Err(AppError::FileSystem(
-                "WolfTL dump not found; please create dump folder using WolfTL first".into(),
+                "Neither a WolfTL dump nor unencrypted Wolf RPG data files were found".into(),
             ));
         }
 
@@ -325,10 +657,13 @@ impl Engine for WolfRpgEngine {
             .map(|raw_unit| TextUnit {
                 id: raw_unit.id.clone(),
                 source_text: raw_unit.source_text.clone(),
-                translated_text: String::new(), // Will be set during injection
+                // Raw units carry the text to write back
+                translated_text: raw_unit.source_text.clone(),
                 field_type: raw_unit.field_type.clone(),
                 status: crate::models::translation::TranslationStatus::NotTranslated,
                 prompt_type: raw_unit.prompt_type,
+                position: raw_unit.position.clone(),
+                detected_language: None,
             })
             .collect();
 
@@ -338,6 +673,10 @@ impl Engine for WolfRpgEngine {
             .map(|unit| (unit.id.clone(), unit))
             .collect();
 
+        if !dump_dir.exists() {
+            self.inject_into_native_files(&text_unit_map, project_path)?;
+        }
+
         // Focus on MPS files first (matching the extraction logic)
         let mps_dir = dump_dir.join("mps");
         if mps_dir.exists() {
@@ -350,6 +689,20 @@ impl Engine for WolfRpgEngine {
             self.inject_into_db_directory(&text_unit_map, &db_dir, project_path)?;
         }
 
+        // Common events and Game.dat settings
+        self.inject_into_dump_files(
+            &text_unit_map,
+            &Self::dump_json_files(&dump_dir.join(DUMP_COMMON_DIR)),
+            project_path,
+            super::files::common::inject_text_units_into_common,
+        )?;
+        self.inject_into_dump_files(
+            &text_unit_map,
+            &Self::dump_json_files(&dump_dir.join(DUMP_GAME_DAT_FILE)),
+            project_path,
+            super::files::game::inject_text_units_into_game_dat,
+        )?;
+
         Ok(())
     }
 
@@ -361,48 +714,70 @@ impl Engine for WolfRpgEngine {
     ) -> AppResult<TextUnit> {
         // Wolf RPG uses JSON-pointer style IDs and colon-separated field types
         // Examples:
-        // MPS files: field_type = "command_101:dump/mps/Map001.json:events[0].pages[0].list[0]"
-        // DB files: field_type = "Database value (DataBase.json)" or "Database entry name (SysDatabase.json)"
+        // MPS files: field_type = "command_101:Data/MapData/Map001.mps:events[0].pages[0].list[0].stringArgs[0]"
+        // DB files: field_type = "Database value (dump/db/DataBase.json):types[0]:data[1]:data[2]"
+        //        or "Database entry name (Data/BasicData/SysDatabase.dat):types[0]:data[1]"
 
         // Parse the field_type to determine the correct ID format and prompt type
         let prompt_type = determine_prompt_type_from_field_type(field_type);
 
         // Generate the ID based on field_type format
         let id = if field_type.starts_with("command_") {
-            // MPS file format: command_CODE:file_path:event_indices
-            // Reconstruct as: wolf_json:file_path#event_indices.stringArgs[0]
-            // Note: We need to determine the arg_idx, for now assume 0
+            // MPS file format: command_CODE:file_path:command_path
+            // Reconstruct as: wolf_json:file_path#command_path
             let parts: Vec<&str> = field_type.split(':').collect();
             if parts.len() >= 3 {
                 let file_path = parts[1];
-                let event_path = parts[2];
-                format!("wolf_json:{}#{}.stringArgs[0]", file_path, event_path)
+                let command_path = parts[2];
+                if command_path.contains(".stringArgs[") {
+                    format!("wolf_json:{}#{}", file_path, command_path)
+                } else {
+                    // Older records stop at the command; their text is the first argument
+                    format!("wolf_json:{}#{}.stringArgs[0]", file_path, command_path)
+                }
             } else {
                 return Err(AppError::Other(format!(
                     "Invalid Wolf RPG MPS field_type format: {}",
                     field_type
                 )));
             }
+        } else if field_type.starts_with("Game setting") {
+            // Game.dat format: "Game setting (file_path):key"
+            // Reconstruct as: file_path:key
+            match field_type
+                .split_once('(')
+                .and_then(|(_, rest)| rest.split_once("):"))
+            {
+                Some((file_path, key)) => format!("{}:{}", file_path, key),
+                None => {
+                    return Err(AppError::Other(format!(
+                        "Invalid Wolf RPG game setting field_type: {}",
+                        field_type
+                    )))
+                }
+            }
         } else if field_type.contains("Database") {
-            // DB file format: "Database value (FileName.json)" or "Database entry name (FileName.json)"
-            // Reconstruct as: file_path:types[0]:data[0]:field[0]:value/name
-            // Note: We need to determine the exact indices, for now use placeholders
-            let file_name = if field_type.contains("DataBase.json") {
-                "dump/db/DataBase.json"
-            } else if field_type.contains("SysDatabase.json") {
-                "dump/db/SysDatabase.json"
-            } else if field_type.contains("CDataBase.json") {
-                "dump/db/CDataBase.json"
+            // DB file format: "Database value (file_path):location" or
+            // "Database entry name (file_path):location"
+            // Reconstruct as: file_path:location:value/name
+            let suffix = if field_type.starts_with("Database entry name") {
+                "name"
             } else {
-                return Err(AppError::Other(format!(
-                    "Unknown Wolf RPG database file in field_type: {}",
-                    field_type
-                )));
+                "value"
             };
-
-            // For database entries, we need the actual indices from the original extraction
-            // For now, create a generic format that matches the extraction pattern
-            format!("{}:types[0]:data[0]:data[0]:value", file_name)
+            let located = field_type
+                .split_once('(')
+                .and_then(|(_, rest)| rest.split_once("):"))
+                .filter(|(_, location)| !location.is_empty());
+            match located {
+                Some((file_path, location)) => format!("{}:{}:{}", file_path, location, suffix),
+                None => {
+                    return Err(AppError::Other(format!(
+                        "Wolf RPG database field_type has no entry location (extract the project again): {}",
+                        field_type
+                    )))
+                }
+            }
         } else {
             return Err(AppError::Other(format!(
                 "Unknown Wolf RPG field_type format: {}",
@@ -417,6 +792,8 @@ impl Engine for WolfRpgEngine {
             status: crate::models::translation::TranslationStatus::MachineTranslated,
             field_type: field_type.to_string(),
             prompt_type,
+            position: None,
+            detected_language: None,
         })
     }
 
@@ -435,6 +812,9 @@ fn determine_prompt_type_from_field_type(
     } else if field_type.starts_with("command_") {
         // Other commands (210, 150, 122) - could be dialogue or other
         crate::models::translation::PromptType::Other
+    } else if field_type.starts_with("Game setting") {
+        // Game.dat title
+        crate::models::translation::PromptType::System
     } else if field_type.contains("Database") {
         // Database entries - typically character/item/skill names
         crate::models::translation::PromptType::Character
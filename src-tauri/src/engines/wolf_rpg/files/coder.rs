@@ -0,0 +1,197 @@
+//! Byte-level reading and writing of Wolf RPG Editor data files.
+//!
+//! Wolf RPG Editor 2 files are little-endian: integers are 4 bytes, strings
+//! are a length (including a trailing NUL) followed by Shift-JIS bytes.
+//! Encrypted files must be decrypted first.
+
+use encoding_rs::SHIFT_JIS;
+
+use crate::core::error::{AppError, AppResult};
+
+/// A string as stored in a data file, with its decoded text
+///
+/// The original bytes are written back unless the text is changed, so strings
+/// that are not valid Shift-JIS survive a round trip untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WolfString {
+    raw: Vec<u8>,
+    text: String,
+}
+
+impl WolfString {
+    pub fn from_bytes(raw: Vec<u8>) -> Self {
+        let (text, _) = SHIFT_JIS.decode_without_bom_handling(&raw);
+        Self {
+            text: text.into_owned(),
+            raw,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text; fails when Shift-JIS cannot represent a character
+    pub fn set_text(&mut self, text: &str) -> AppResult<()> {
+        if text == self.text {
+            return Ok(());
+        }
+        let (raw, _, had_errors) = SHIFT_JIS.encode(text);
+        if had_errors {
+            let mut buffer = [0u8; 4];
+            let unencodable = text
+                .chars()
+                .find(|c| SHIFT_JIS.encode(c.encode_utf8(&mut buffer)).2)
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            return Err(AppError::Translation(format!(
+                "'{}' cannot be encoded in Shift_JIS: {}",
+                unencodable, text
+            )));
+        }
+        self.raw = raw.into_owned();
+        self.text = text.to_string();
+        Ok(())
+    }
+}
+
+impl From<&str> for WolfString {
+    fn from(text: &str) -> Self {
+        let (raw, _, _) = SHIFT_JIS.encode(text);
+        Self {
+            raw: raw.into_owned(),
+            text: text.to_string(),
+        }
+    }
+}
+
+/// Cursor over the bytes of a data file
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Bytes from `start` up to the cursor
+    pub fn since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.pos]
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+
+    pub fn bytes(&mut self, count: usize) -> AppResult<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(count)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| {
+                AppError::Parsing(format!(
+                    "Unexpected end of Wolf RPG data reading {} bytes at byte {}",
+                    count, self.pos
+                ))
+            })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> AppResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> AppResult<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A count of items, each at least `min_item_size` bytes long, checked
+    /// against the remaining data so corrupt counts fail early
+    pub fn count(&mut self, min_item_size: usize) -> AppResult<usize> {
+        let count = self.u32()? as usize;
+        let remaining = self.data.len() - self.pos;
+        if count.saturating_mul(min_item_size) > remaining {
+            return Err(AppError::Parsing(format!(
+                "Invalid Wolf RPG item count {} at byte {}",
+                count,
+                self.pos - 4
+            )));
+        }
+        Ok(count)
+    }
+
+    pub fn string(&mut self) -> AppResult<WolfString> {
+        let start = self.pos;
+        let length = self.u32()? as usize;
+        if length == 0 {
+            return Err(AppError::Parsing(format!(
+                "Empty Wolf RPG string at byte {}",
+                start
+            )));
+        }
+        let bytes = self.bytes(length)?;
+        if bytes[length - 1] != 0 {
+            return Err(AppError::Parsing(format!(
+                "Wolf RPG string at byte {} is not NUL-terminated",
+                start
+            )));
+        }
+        Ok(WolfString::from_bytes(bytes[..length - 1].to_vec()))
+    }
+
+    /// Check a fixed marker
+    pub fn expect(&mut self, expected: &[u8], what: &str) -> AppResult<()> {
+        let start = self.pos;
+        let found = self.bytes(expected.len())?;
+        if found != expected {
+            return Err(AppError::Parsing(format!(
+                "Invalid {} at byte {}: expected {:02X?}, found {:02X?}",
+                what, start, expected, found
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn write_string(out: &mut Vec<u8>, string: &WolfString) {
+    write_u32(out, string.raw.len() as u32 + 1);
+    out.extend_from_slice(&string.raw);
+    out.push(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strings_round_trip_and_refuse_unencodable_text() {
+        let mut out = Vec::new();
+        write_string(&mut out, &WolfString::from("はじめから"));
+        let mut reader = Reader::new(&out);
+        let mut string = reader.string().unwrap();
+        assert!(reader.at_end());
+        assert_eq!(string.text(), "はじめから");
+
+        assert!(string.set_text("Nouvelle partie").is_ok());
+        assert!(string.set_text("Théâtre").is_err());
+        assert_eq!(string.text(), "Nouvelle partie");
+    }
+}
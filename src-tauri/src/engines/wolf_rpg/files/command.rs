@@ -0,0 +1,149 @@
+//! Event commands shared by map events and common events.
+
+use serde_json::{json, Value};
+
+use super::coder::{write_string, write_u32, Reader, WolfString};
+use crate::core::error::{AppError, AppResult};
+
+/// Ends a route command
+const ROUTE_COMMAND_END: [u8; 2] = [0x01, 0x00];
+
+/// One event command
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub code: u32,
+    pub int_args: Vec<u32>,
+    pub indent: u8,
+    pub string_args: Vec<WolfString>,
+    /// Route of a "Move" command, kept as stored
+    pub move_route: Option<Vec<u8>>,
+}
+
+impl Command {
+    pub fn read(reader: &mut Reader) -> AppResult<Self> {
+        // The first byte counts the code and the integer arguments
+        let int_count = reader.u8()?.checked_sub(1).ok_or_else(|| {
+            AppError::Parsing(format!(
+                "Invalid Wolf RPG command at byte {}",
+                reader.pos() - 1
+            ))
+        })?;
+        let code = reader.u32()?;
+        let int_args = (0..int_count)
+            .map(|_| reader.u32())
+            .collect::<AppResult<Vec<u32>>>()?;
+        let indent = reader.u8()?;
+        let string_count = reader.u8()?;
+        let string_args = (0..string_count)
+            .map(|_| reader.string())
+            .collect::<AppResult<Vec<WolfString>>>()?;
+
+        let move_route = match reader.u8()? {
+            0x00 => None,
+            0x01 => {
+                let start = reader.pos();
+                // Unknown bytes and flags, then the route commands
+                reader.bytes(6)?;
+                let route_length = reader.count(4)?;
+                for _ in 0..route_length {
+                    read_route_command(reader)?;
+                }
+                Some(reader.since(start).to_vec())
+            }
+            other => {
+                return Err(AppError::Parsing(format!(
+                    "Invalid Wolf RPG command terminator {:#04X} at byte {}",
+                    other,
+                    reader.pos() - 1
+                )))
+            }
+        };
+
+        Ok(Self {
+            code,
+            int_args,
+            indent,
+            string_args,
+            move_route,
+        })
+    }
+
+    pub fn write(&self, out: &mut Vec<u8>) {
+        out.push(self.int_args.len() as u8 + 1);
+        write_u32(out, self.code);
+        for arg in &self.int_args {
+            write_u32(out, *arg);
+        }
+        out.push(self.indent);
+        out.push(self.string_args.len() as u8);
+        for arg in &self.string_args {
+            write_string(out, arg);
+        }
+        match &self.move_route {
+            Some(route) => {
+                out.push(0x01);
+                out.extend_from_slice(route);
+            }
+            None => out.push(0x00),
+        }
+    }
+
+    /// JSON form walked by the extractors (`code`, `intArgs`, `stringArgs`)
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code,
+            "indent": self.indent,
+            "intArgs": self.int_args.iter().map(|arg| *arg as i32).collect::<Vec<i32>>(),
+            "stringArgs": self.string_args.iter().map(WolfString::text).collect::<Vec<&str>>(),
+        })
+    }
+
+    /// Take the string arguments of the JSON form
+    pub fn apply_json(&mut self, json: &Value) -> AppResult<()> {
+        let Some(args) = json.get("stringArgs").and_then(Value::as_array) else {
+            return Ok(());
+        };
+        for (arg, value) in self.string_args.iter_mut().zip(args) {
+            if let Some(text) = value.as_str() {
+                arg.set_text(text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_route_command(reader: &mut Reader) -> AppResult<()> {
+    reader.u8()?;
+    let arg_count = reader.u8()?;
+    reader.bytes(arg_count as usize * 4)?;
+    reader.expect(&ROUTE_COMMAND_END, "route command terminator")
+}
+
+/// A command list preceded by its length
+pub fn read_commands(reader: &mut Reader) -> AppResult<Vec<Command>> {
+    let count = reader.count(8)?;
+    (0..count).map(|_| Command::read(reader)).collect()
+}
+
+pub fn write_commands(out: &mut Vec<u8>, commands: &[Command]) {
+    write_u32(out, commands.len() as u32);
+    for command in commands {
+        command.write(out);
+    }
+}
+
+/// JSON form of a command list
+pub fn commands_to_json(commands: &[Command]) -> Value {
+    Value::Array(commands.iter().map(Command::to_json).collect())
+}
+
+/// Take the string arguments of a JSON command list
+pub fn apply_commands_json(commands: &mut [Command], json: Option<&Value>) -> AppResult<()> {
+    let Some(list) = json.and_then(Value::as_array) else {
+        return Ok(());
+    };
+    for (command, value) in commands.iter_mut().zip(list) {
+        command.apply_json(value)?;
+    }
+    Ok(())
+}
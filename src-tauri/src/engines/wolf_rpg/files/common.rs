@@ -0,0 +1,140 @@
+// Text processing now handled by unified pipeline
+use crate::models::translation::{TextUnit, UnitPosition};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::mps::{extract_from_wolf_command, inject_into_wolf_command};
+
+/// Extract text units from Wolf RPG common events
+/// Handles CommonEvent.dat read natively (`events[].list[]`) and dumped
+/// common events (one event per file, with a `list` or `commands` array).
+/// Commands go through the same selective extractor as map events.
+pub fn extract_text_units_from_common(common_data: &Value, file_path: &str) -> Vec<TextUnit> {
+    let mut text_units = Vec::new();
+    let normalized_path = file_path.replace('\\', "/");
+
+    for (list_path, event_ref, commands) in command_lists(common_data) {
+        for (cmd_idx, command) in commands.iter().enumerate() {
+            extract_from_wolf_command(
+                &mut text_units,
+                command,
+                file_path,
+                &format!("{}[{}]", list_path, cmd_idx),
+                UnitPosition {
+                    event_ref: format!("{}#{}", normalized_path, event_ref),
+                    page_index: 0,
+                    command_index: cmd_idx as u32,
+                    speaker: None,
+                },
+            );
+        }
+    }
+
+    text_units
+}
+
+/// Inject translated text back into Wolf RPG common events
+pub fn inject_text_units_into_common(
+    common_data: &mut Value,
+    text_units: &HashMap<String, &TextUnit>,
+    file_path: &str,
+) {
+    if let Some(events) = common_data.get_mut("events").and_then(|v| v.as_array_mut()) {
+        for (event_idx, event) in events.iter_mut().enumerate() {
+            if let Some(commands) = event.get_mut("list").and_then(|v| v.as_array_mut()) {
+                for (cmd_idx, command) in commands.iter_mut().enumerate() {
+                    inject_into_wolf_command(
+                        command,
+                        text_units,
+                        file_path,
+                        &format!("events[{}].list[{}]", event_idx, cmd_idx),
+                    );
+                }
+            }
+        }
+        return;
+    }
+
+    for key in ["list", "commands"] {
+        if let Some(commands) = common_data.get_mut(key).and_then(|v| v.as_array_mut()) {
+            for (cmd_idx, command) in commands.iter_mut().enumerate() {
+                inject_into_wolf_command(
+                    command,
+                    text_units,
+                    file_path,
+                    &format!("{}[{}]", key, cmd_idx),
+                );
+            }
+        }
+    }
+}
+
+/// Command lists of a common event file: path of the list, event reference
+/// and commands
+fn command_lists(common_data: &Value) -> Vec<(String, String, &Vec<Value>)> {
+    if let Some(events) = common_data.get("events").and_then(|v| v.as_array()) {
+        return events
+            .iter()
+            .enumerate()
+            .filter_map(|(event_idx, event)| {
+                event
+                    .get("list")
+                    .and_then(|v| v.as_array())
+                    .map(|commands| {
+                        (
+                            format!("events[{}].list", event_idx),
+                            format!("events[{}]", event_idx),
+                            commands,
+                        )
+                    })
+            })
+            .collect();
+    }
+
+    ["list", "commands"]
+        .iter()
+        .filter_map(|key| {
+            common_data
+                .get(*key)
+                .and_then(|v| v.as_array())
+                .map(|commands| (key.to_string(), "event".to_string(), commands))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_common_event_commands_round_trip() {
+        let mut data = json!({
+            "events": [{
+                "id": 0,
+                "name": "会話",
+                "list": [
+                    { "code": 101, "stringArgs": ["ようこそ"] },
+                    { "code": 210, "intArgs": [500001], "stringArgs": ["", "宿屋"] },
+                    { "code": 300, "stringArgs": ["会話"] }
+                ]
+            }]
+        });
+        let units = extract_text_units_from_common(&data, "Data/BasicData/CommonEvent.dat");
+        let ids: Vec<&str> = units.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "wolf_json:Data/BasicData/CommonEvent.dat#events[0].list[0].stringArgs[0]",
+                "wolf_json:Data/BasicData/CommonEvent.dat#events[0].list[1].stringArgs[1]",
+            ]
+        );
+
+        let mut translated = units[1].clone();
+        translated.translated_text = "Inn".to_string();
+        let map = HashMap::from([(translated.id.clone(), &translated)]);
+        inject_text_units_into_common(&mut data, &map, "Data/BasicData/CommonEvent.dat");
+        assert_eq!(data["events"][0]["list"][1]["stringArgs"][1], "Inn");
+        assert_eq!(data["events"][0]["list"][0]["stringArgs"][0], "ようこそ");
+    }
+}
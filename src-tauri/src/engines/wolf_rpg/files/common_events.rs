@@ -0,0 +1,207 @@
+//! Common events (`Data/BasicData/CommonEvent.dat`).
+//!
+//! The file is a header, an event count and the events. Each event starts
+//! with a marker and its ID, then its name and command list; the settings
+//! after the command list (memo, description, arguments) are kept as stored.
+
+use serde_json::{json, Value};
+
+use super::coder::{write_string, write_u32, Reader, WolfString};
+use super::command::{
+    apply_commands_json, commands_to_json, read_commands, write_commands, Command,
+};
+use crate::core::error::{AppError, AppResult};
+
+const EVENT_START: u8 = 0x8E;
+const EVENTS_END: u8 = 0x8F;
+
+/// A parsed common event file
+#[derive(Debug, Clone)]
+pub struct CommonEvents {
+    /// Bytes before the event count
+    head: Vec<u8>,
+    pub events: Vec<CommonEvent>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommonEvent {
+    pub id: u32,
+    /// Trigger settings before the name
+    settings: Vec<u8>,
+    pub name: WolfString,
+    pub commands: Vec<Command>,
+    /// Everything up to the next event
+    tail: Vec<u8>,
+}
+
+impl CommonEvents {
+    pub fn read(bytes: &[u8]) -> AppResult<Self> {
+        let count_at = find_first_event(bytes).ok_or_else(|| {
+            AppError::Parsing("No common events found in CommonEvent.dat".to_string())
+        })?;
+        let mut reader = Reader::new(bytes);
+        reader.bytes(count_at)?;
+        let count = reader.count(1)?;
+
+        let mut events = Vec::with_capacity(count);
+        for index in 0..count {
+            reader.expect(&[EVENT_START], "common event marker")?;
+            let id = reader.u32()?;
+            let start = reader.pos();
+            reader.bytes(4 + 7)?;
+            let settings = reader.since(start).to_vec();
+            let name = reader.string()?;
+            let commands = read_commands(&mut reader)?;
+
+            // The tail has no length of its own: it runs up to the next
+            // event, or up to the final marker for the last one
+            let start = reader.pos();
+            let rest = &bytes[start..];
+            let tail_length = if index + 1 < count {
+                let mut next = vec![EVENT_START];
+                next.extend_from_slice(&(id + 1).to_le_bytes());
+                rest.windows(next.len()).position(|window| window == next)
+            } else {
+                rest.iter().rposition(|byte| *byte == EVENTS_END)
+            }
+            .ok_or_else(|| {
+                AppError::Parsing(format!("Cannot find the end of common event {}", id))
+            })?;
+            let tail = reader.bytes(tail_length)?.to_vec();
+
+            events.push(CommonEvent {
+                id,
+                settings,
+                name,
+                commands,
+                tail,
+            });
+        }
+        reader.expect(&[EVENTS_END], "common event list terminator")?;
+        if !reader.at_end() {
+            return Err(AppError::Parsing(format!(
+                "Unexpected data after the common events at byte {}",
+                reader.pos()
+            )));
+        }
+
+        Ok(Self {
+            head: bytes[..count_at].to_vec(),
+            events,
+        })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut out = self.head.clone();
+        write_u32(&mut out, self.events.len() as u32);
+        for event in &self.events {
+            out.push(EVENT_START);
+            write_u32(&mut out, event.id);
+            out.extend_from_slice(&event.settings);
+            write_string(&mut out, &event.name);
+            write_commands(&mut out, &event.commands);
+            out.extend_from_slice(&event.tail);
+        }
+        out.push(EVENTS_END);
+        out
+    }
+
+    /// JSON form with the same command shape as maps (`events[].list[]`)
+    pub fn to_json(&self) -> Value {
+        let events: Vec<Value> = self
+            .events
+            .iter()
+            .map(|event| {
+                json!({
+                    "id": event.id,
+                    "name": event.name.text(),
+                    "list": commands_to_json(&event.commands),
+                })
+            })
+            .collect();
+        json!({ "events": events })
+    }
+
+    /// Take the command strings of the JSON form
+    pub fn apply_json(&mut self, json: &Value) -> AppResult<()> {
+        let Some(events) = json.get("events").and_then(Value::as_array) else {
+            return Ok(());
+        };
+        for (event, event_json) in self.events.iter_mut().zip(events) {
+            apply_commands_json(&mut event.commands, event_json.get("list"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Offset of the event count, found as the count followed by event 0
+fn find_first_event(bytes: &[u8]) -> Option<usize> {
+    let marker = [EVENT_START, 0, 0, 0, 0];
+    let mut from = 0;
+    while let Some(found) = bytes[from..]
+        .windows(marker.len())
+        .position(|window| window == marker)
+    {
+        let at = from + found;
+        if at >= 4 {
+            let count =
+                u32::from_le_bytes([bytes[at - 4], bytes[at - 3], bytes[at - 2], bytes[at - 1]]);
+            if count > 0 && (count as usize) < bytes.len() {
+                return Some(at - 4);
+            }
+        }
+        from = at + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_events_round_trip_and_translation() {
+        let mut bytes = vec![
+            0x00, 0x57, 0x00, 0x00, 0x4F, 0x4C, 0x00, 0x46, 0x43, 0x00, 0x8F,
+        ];
+        write_u32(&mut bytes, 2);
+        for (id, name, text) in [(0u32, "回復", "全回復した！"), (1, "空", "")] {
+            bytes.push(EVENT_START);
+            write_u32(&mut bytes, id);
+            bytes.extend_from_slice(&[0; 11]);
+            write_string(&mut bytes, &WolfString::from(name));
+            write_commands(
+                &mut bytes,
+                &[Command {
+                    code: 101,
+                    int_args: vec![],
+                    indent: 0,
+                    string_args: vec![WolfString::from(text)],
+                    move_route: None,
+                }],
+            );
+            bytes.extend_from_slice(&[0x8F, 0, 0, 0, 0x91]);
+            write_string(&mut bytes, &WolfString::from("メモ"));
+            bytes.push(0x92);
+        }
+        bytes.push(EVENTS_END);
+
+        let mut events = CommonEvents::read(&bytes).unwrap();
+        assert_eq!(events.events.len(), 2);
+        assert_eq!(events.write(), bytes);
+
+        let mut json = events.to_json();
+        assert_eq!(
+            json["events"][0]["list"][0]["stringArgs"][0],
+            "全回復した！"
+        );
+        json["events"][0]["list"][0]["stringArgs"][0] = json!("Fully healed!");
+        events.apply_json(&json).unwrap();
+        let translated = CommonEvents::read(&events.write()).unwrap();
+        assert_eq!(
+            translated.events[0].commands[0].string_args[0].text(),
+            "Fully healed!"
+        );
+        assert_eq!(translated.events[1].name.text(), "空");
+    }
+}
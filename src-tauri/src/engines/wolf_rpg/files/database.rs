@@ -0,0 +1,374 @@
+//! Databases (`Data/BasicData/{DataBase,CDataBase,SysDatabase}.project/.dat`).
+//!
+//! A database is split in two files: the `.project` holds the type, field and
+//! entry names, the `.dat` holds the values. Each field of a type is either an
+//! integer or a string (`indexinfo` of 2000 and above); an entry stores all of
+//! its integers, then all of its strings.
+
+use serde_json::{json, Value};
+
+use super::coder::{write_string, write_u32, Reader, WolfString};
+use crate::core::error::{AppError, AppResult};
+
+/// Starts each type in the `.dat` file
+const DAT_TYPE_MAGIC: [u8; 4] = [0xFE, 0xFF, 0xFF, 0xFF];
+
+/// `indexinfo` values from this one up are string fields
+const STRING_FIELD_BASE: u32 = 2000;
+
+/// A parsed `.project`/`.dat` pair
+#[derive(Debug, Clone)]
+pub struct Database {
+    pub types: Vec<DatabaseType>,
+    /// `.dat` bytes before the first type
+    dat_head: Vec<u8>,
+    /// `.dat` bytes after the last type
+    dat_tail: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseType {
+    pub name: WolfString,
+    pub field_names: Vec<WolfString>,
+    pub entry_names: Vec<WolfString>,
+    pub description: WolfString,
+    /// Field settings after the description in the `.project`, kept as stored
+    project_tail: Vec<u8>,
+    dat_unknown: u32,
+    /// `indexinfo` of the fields stored in the `.dat`
+    field_indices: Vec<u32>,
+    pub entries: Vec<DatabaseEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseEntry {
+    pub ints: Vec<u32>,
+    pub strings: Vec<WolfString>,
+}
+
+impl Database {
+    pub fn read(project: &[u8], dat: &[u8]) -> AppResult<Self> {
+        let mut reader = Reader::new(project);
+        let type_count = reader.count(4)?;
+        let mut types = Vec::with_capacity(type_count);
+        for _ in 0..type_count {
+            types.push(DatabaseType::read_project(&mut reader)?);
+        }
+
+        let dat_start = find_dat_types(dat, type_count as u32).ok_or_else(|| {
+            AppError::Parsing(format!(
+                "Database data file does not match its project ({} types)",
+                type_count
+            ))
+        })?;
+        let mut reader = Reader::new(dat);
+        reader.bytes(dat_start + 4)?;
+        for database_type in &mut types {
+            database_type.read_dat(&mut reader)?;
+        }
+
+        Ok(Self {
+            types,
+            dat_head: dat[..dat_start].to_vec(),
+            dat_tail: reader.rest().to_vec(),
+        })
+    }
+
+    /// The `.project` and `.dat` contents
+    pub fn write(&self) -> (Vec<u8>, Vec<u8>) {
+        let mut project = Vec::new();
+        write_u32(&mut project, self.types.len() as u32);
+        let mut dat = self.dat_head.clone();
+        write_u32(&mut dat, self.types.len() as u32);
+        for database_type in &self.types {
+            database_type.write_project(&mut project);
+            database_type.write_dat(&mut dat);
+        }
+        dat.extend_from_slice(&self.dat_tail);
+        (project, dat)
+    }
+
+    /// JSON form walked by the database extractor
+    /// (`types[].data[].name` and `types[].data[].data[].value`)
+    pub fn to_json(&self) -> Value {
+        let types: Vec<Value> = self
+            .types
+            .iter()
+            .map(|database_type| {
+                let data: Vec<Value> = database_type
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .map(|(entry_idx, entry)| {
+                        let fields: Vec<Value> = database_type
+                            .field_indices
+                            .iter()
+                            .enumerate()
+                            .map(|(field_idx, index)| {
+                                json!({
+                                    "name": database_type.field_names.get(field_idx).map(WolfString::text),
+                                    "value": match field_value(entry, *index) {
+                                        Some(FieldValue::Int(value)) => json!(value as i32),
+                                        Some(FieldValue::String(value)) => json!(value.text()),
+                                        None => Value::Null,
+                                    },
+                                })
+                            })
+                            .collect();
+                        json!({
+                            "name": database_type.entry_names.get(entry_idx).map(WolfString::text),
+                            "data": fields,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "name": database_type.name.text(),
+                    "description": database_type.description.text(),
+                    "data": data,
+                })
+            })
+            .collect();
+        json!({ "types": types })
+    }
+
+    /// Take entry names and string values of the JSON form
+    pub fn apply_json(&mut self, json: &Value) -> AppResult<()> {
+        let Some(types) = json.get("types").and_then(Value::as_array) else {
+            return Ok(());
+        };
+        for (database_type, type_json) in self.types.iter_mut().zip(types) {
+            let Some(data) = type_json.get("data").and_then(Value::as_array) else {
+                continue;
+            };
+            for (entry_idx, entry_json) in data.iter().enumerate() {
+                if let (Some(name), Some(text)) = (
+                    database_type.entry_names.get_mut(entry_idx),
+                    entry_json.get("name").and_then(Value::as_str),
+                ) {
+                    name.set_text(text)?;
+                }
+
+                let (Some(entry), Some(fields)) = (
+                    database_type.entries.get_mut(entry_idx),
+                    entry_json.get("data").and_then(Value::as_array),
+                ) else {
+                    continue;
+                };
+                for (index, field_json) in database_type.field_indices.iter().zip(fields) {
+                    let Some(text) = field_json.get("value").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    if let Some(value) = index
+                        .checked_sub(STRING_FIELD_BASE)
+                        .and_then(|slot| entry.strings.get_mut(slot as usize))
+                    {
+                        value.set_text(text)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum FieldValue<'a> {
+    Int(u32),
+    String(&'a WolfString),
+}
+
+fn field_value(entry: &DatabaseEntry, index: u32) -> Option<FieldValue<'_>> {
+    if index >= STRING_FIELD_BASE {
+        entry
+            .strings
+            .get((index - STRING_FIELD_BASE) as usize)
+            .map(FieldValue::String)
+    } else {
+        // Integer fields are numbered from 1000 (or from 0 in old files)
+        let slot = index.checked_sub(1000).unwrap_or(index);
+        entry.ints.get(slot as usize).copied().map(FieldValue::Int)
+    }
+}
+
+/// Offset of the type count, found as the count followed by the first type
+fn find_dat_types(dat: &[u8], type_count: u32) -> Option<usize> {
+    if type_count == 0 {
+        return None;
+    }
+    let mut pattern = type_count.to_le_bytes().to_vec();
+    pattern.extend_from_slice(&DAT_TYPE_MAGIC);
+    dat.windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+impl DatabaseType {
+    fn read_project(reader: &mut Reader) -> AppResult<Self> {
+        let name = reader.string()?;
+        let field_count = reader.count(5)?;
+        let field_names = (0..field_count)
+            .map(|_| reader.string())
+            .collect::<AppResult<Vec<WolfString>>>()?;
+        let entry_count = reader.count(5)?;
+        let entry_names = (0..entry_count)
+            .map(|_| reader.string())
+            .collect::<AppResult<Vec<WolfString>>>()?;
+        let description = reader.string()?;
+
+        let start = reader.pos();
+        // Field types, then per field: a string, a string list, an integer
+        // list and a default value
+        let type_list_size = reader.count(1)?;
+        reader.bytes(type_list_size)?;
+        let count = reader.count(5)?;
+        for _ in 0..count {
+            reader.string()?;
+        }
+        let count = reader.count(4)?;
+        for _ in 0..count {
+            let strings = reader.count(5)?;
+            for _ in 0..strings {
+                reader.string()?;
+            }
+        }
+        let count = reader.count(4)?;
+        for _ in 0..count {
+            let ints = reader.count(4)?;
+            reader.bytes(ints * 4)?;
+        }
+        let count = reader.count(4)?;
+        reader.bytes(count * 4)?;
+        let project_tail = reader.since(start).to_vec();
+
+        Ok(Self {
+            name,
+            field_names,
+            entry_names,
+            description,
+            project_tail,
+            dat_unknown: 0,
+            field_indices: Vec::new(),
+            entries: Vec::new(),
+        })
+    }
+
+    fn read_dat(&mut self, reader: &mut Reader) -> AppResult<()> {
+        reader.expect(&DAT_TYPE_MAGIC, "database type header")?;
+        self.dat_unknown = reader.u32()?;
+        let field_count = reader.count(4)?;
+        self.field_indices = (0..field_count)
+            .map(|_| reader.u32())
+            .collect::<AppResult<Vec<u32>>>()?;
+        let string_count = self
+            .field_indices
+            .iter()
+            .filter(|index| **index >= STRING_FIELD_BASE)
+            .count();
+        let int_count = field_count - string_count;
+
+        let entry_count = reader.count(int_count * 4 + string_count * 5)?;
+        self.entries = (0..entry_count)
+            .map(|_| {
+                let ints = (0..int_count)
+                    .map(|_| reader.u32())
+                    .collect::<AppResult<Vec<u32>>>()?;
+                let strings = (0..string_count)
+                    .map(|_| reader.string())
+                    .collect::<AppResult<Vec<WolfString>>>()?;
+                Ok(DatabaseEntry { ints, strings })
+            })
+            .collect::<AppResult<Vec<DatabaseEntry>>>()?;
+        Ok(())
+    }
+
+    fn write_project(&self, out: &mut Vec<u8>) {
+        write_string(out, &self.name);
+        write_u32(out, self.field_names.len() as u32);
+        for name in &self.field_names {
+            write_string(out, name);
+        }
+        write_u32(out, self.entry_names.len() as u32);
+        for name in &self.entry_names {
+            write_string(out, name);
+        }
+        write_string(out, &self.description);
+        out.extend_from_slice(&self.project_tail);
+    }
+
+    fn write_dat(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&DAT_TYPE_MAGIC);
+        write_u32(out, self.dat_unknown);
+        write_u32(out, self.field_indices.len() as u32);
+        for index in &self.field_indices {
+            write_u32(out, *index);
+        }
+        write_u32(out, self.entries.len() as u32);
+        for entry in &self.entries {
+            for value in &entry.ints {
+                write_u32(out, *value);
+            }
+            for value in &entry.strings {
+                write_string(out, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_database() -> (Vec<u8>, Vec<u8>) {
+        let mut project = Vec::new();
+        write_u32(&mut project, 1);
+        write_string(&mut project, &WolfString::from("アイテム"));
+        write_u32(&mut project, 2);
+        write_string(&mut project, &WolfString::from("価格"));
+        write_string(&mut project, &WolfString::from("説明"));
+        write_u32(&mut project, 1);
+        write_string(&mut project, &WolfString::from("薬草"));
+        write_string(&mut project, &WolfString::from(""));
+        write_u32(&mut project, 2);
+        project.extend_from_slice(&[0, 1]);
+        for _ in 0..4 {
+            write_u32(&mut project, 0);
+        }
+
+        let mut dat = vec![0x57, 0x00, 0x00, 0x4F, 0x4C, 0x00, 0x46, 0x4D, 0x00, 0xC1];
+        write_u32(&mut dat, 1);
+        dat.extend_from_slice(&DAT_TYPE_MAGIC);
+        write_u32(&mut dat, 0);
+        write_u32(&mut dat, 2);
+        write_u32(&mut dat, 1000);
+        write_u32(&mut dat, 2000);
+        write_u32(&mut dat, 1);
+        write_u32(&mut dat, 50);
+        write_string(&mut dat, &WolfString::from("HPを回復する"));
+        dat.push(0xC1);
+        (project, dat)
+    }
+
+    #[test]
+    fn test_database_round_trip_and_translation() {
+        let (project, dat) = sample_database();
+        let mut database = Database::read(&project, &dat).unwrap();
+        assert_eq!(database.write(), (project, dat));
+
+        let mut json = database.to_json();
+        assert_eq!(json["types"][0]["data"][0]["name"], "薬草");
+        assert_eq!(json["types"][0]["data"][0]["data"][0]["value"], 50);
+        assert_eq!(
+            json["types"][0]["data"][0]["data"][1]["value"],
+            "HPを回復する"
+        );
+
+        json["types"][0]["data"][0]["name"] = json!("Herb");
+        json["types"][0]["data"][0]["data"][1]["value"] = json!("Restores HP");
+        database.apply_json(&json).unwrap();
+        let (project, dat) = database.write();
+        let translated = Database::read(&project, &dat).unwrap().to_json();
+        assert_eq!(translated["types"][0]["data"][0]["name"], "Herb");
+        assert_eq!(
+            translated["types"][0]["data"][0]["data"][1]["value"],
+            "Restores HP"
+        );
+    }
+}
@@ -5,7 +5,8 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 /// Extract text units from Wolf RPG database JSON files
-/// Handles CDataBase.json, DataBase.json, and SysDatabase.json
+/// Handles CDataBase, DataBase, and SysDatabase, whether dumped to JSON or
+/// read from the game's `.dat` files
 pub fn extract_text_units_from_db(db_data: &Value, file_path: &str) -> Vec<TextUnit> {
     let mut text_units = Vec::new();
 
@@ -36,13 +37,13 @@ fn extract_from_db_data_entry(
     type_idx: usize,
     data_idx: usize,
 ) {
-    let file_name = std::path::Path::new(file_path)
-        .file_name()
+    let database = std::path::Path::new(file_path)
+        .file_stem()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown");
 
     // For SysDatabase.json: extract types=>data=>name
-    if file_name == "SysDatabase.json" {
+    if database == "SysDatabase" {
         if let Some(name) = data_obj.get("name").and_then(|v| v.as_str()) {
             if is_translatable_sys_db_name(name, data_obj) {
                 let processed_text = name.to_string(); // Raw text, no processing
@@ -54,9 +55,14 @@ fn extract_from_db_data_entry(
                     ),
                     source_text: processed_text,
                     translated_text: String::new(),
-                    field_type: format!("Database entry name ({})", file_name),
+                    field_type: format!(
+                        "Database entry name ({}):types[{}]:data[{}]",
+                        normalized_path, type_idx, data_idx
+                    ),
                     status: TranslationStatus::NotTranslated,
                     prompt_type: PromptType::Other,
+                    position: None,
+                    detected_language: None,
                 };
                 text_units.push(text_unit);
             }
@@ -64,7 +70,7 @@ fn extract_from_db_data_entry(
     }
 
     // For CDataBase.json and DataBase.json: extract types=>data=>data=>value
-    if file_name == "CDataBase.json" || file_name == "DataBase.json" {
+    if database == "CDataBase" || database == "DataBase" {
         if let Some(data_data_array) = data_obj.get("data").and_then(|v| v.as_array()) {
             for (data_data_idx, data_data_obj) in data_data_array.iter().enumerate() {
                 if let Some(value) = data_data_obj.get("value").and_then(|v| v.as_str()) {
@@ -78,9 +84,14 @@ fn extract_from_db_data_entry(
                             ),
                             source_text: processed_text,
                             translated_text: String::new(),
-                            field_type: format!("Database value ({})", file_name),
+                            field_type: format!(
+                                "Database value ({}):types[{}]:data[{}]:data[{}]",
+                                normalized_path, type_idx, data_idx, data_data_idx
+                            ),
                             status: TranslationStatus::NotTranslated,
                             prompt_type: PromptType::Other,
+                            position: None,
+                            detected_language: None,
                         };
                         text_units.push(text_unit);
                     }
@@ -170,13 +181,13 @@ fn inject_into_db_data_entry(
     type_idx: usize,
     data_idx: usize,
 ) {
-    let file_name = std::path::Path::new(file_path)
-        .file_name()
+    let database = std::path::Path::new(file_path)
+        .file_stem()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown");
 
     // For SysDatabase.json: inject into types=>data=>name
-    if file_name == "SysDatabase.json" {
+    if database == "SysDatabase" {
         let normalized_path = file_path.replace('\\', "/");
         let name_id = format!(
             "{}:types[{}]:data[{}]:name",
@@ -190,7 +201,7 @@ fn inject_into_db_data_entry(
     }
 
     // For CDataBase.json and DataBase.json: inject into types=>data=>data=>value
-    if file_name == "CDataBase.json" || file_name == "DataBase.json" {
+    if database == "CDataBase" || database == "DataBase" {
         if let Some(data_data_array) = data_obj.get_mut("data").and_then(|v| v.as_array_mut()) {
             for (data_data_idx, data_data_obj) in data_data_array.iter_mut().enumerate() {
                 let normalized_path = file_path.replace('\\', "/");
@@ -0,0 +1,64 @@
+// Text processing now handled by unified pipeline
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Game.dat settings shown to the player (matched case-insensitively, so
+/// dumped `Title` keys work too)
+const GAME_TEXT_FIELDS: [&str; 1] = ["title"];
+
+/// Extract text units from Wolf RPG Game.dat settings
+pub fn extract_text_units_from_game_dat(game_data: &Value, file_path: &str) -> Vec<TextUnit> {
+    let mut text_units = Vec::new();
+    let normalized_path = file_path.replace('\\', "/");
+
+    if let Some(obj) = game_data.as_object() {
+        for (key, value) in obj {
+            if !is_game_text_field(key) {
+                continue;
+            }
+            if let Some(text) = value.as_str().filter(|t| !t.trim().is_empty()) {
+                text_units.push(TextUnit {
+                    id: format!("{}:{}", normalized_path, key),
+                    source_text: text.to_string(),
+                    translated_text: String::new(),
+                    field_type: format!("Game setting ({}):{}", normalized_path, key),
+                    status: TranslationStatus::NotTranslated,
+                    prompt_type: PromptType::System,
+                    position: None,
+                    detected_language: None,
+                });
+            }
+        }
+    }
+
+    text_units
+}
+
+/// Inject translated text back into Wolf RPG Game.dat settings
+pub fn inject_text_units_into_game_dat(
+    game_data: &mut Value,
+    text_units: &HashMap<String, &TextUnit>,
+    file_path: &str,
+) {
+    let normalized_path = file_path.replace('\\', "/");
+
+    if let Some(obj) = game_data.as_object_mut() {
+        for (key, value) in obj.iter_mut() {
+            if !is_game_text_field(key) {
+                continue;
+            }
+            if let Some(text_unit) = text_units.get(&format!("{}:{}", normalized_path, key)) {
+                if !text_unit.translated_text.is_empty() {
+                    *value = Value::String(text_unit.translated_text.clone());
+                }
+            }
+        }
+    }
+}
+
+fn is_game_text_field(key: &str) -> bool {
+    GAME_TEXT_FIELDS
+        .iter()
+        .any(|field| field.eq_ignore_ascii_case(key))
+}
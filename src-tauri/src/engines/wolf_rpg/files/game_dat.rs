@@ -0,0 +1,156 @@
+//! Game settings (`Data/BasicData/Game.dat`).
+//!
+//! Holds the title and font names shown by the game. The strings come after
+//! a short header; the file size stored after them is recomputed on write and
+//! the rest is kept as stored.
+
+use serde_json::{json, Value};
+
+use super::coder::{write_string, write_u32, Reader, WolfString};
+use crate::core::error::{AppError, AppResult};
+
+const GAME_MAGIC: &[u8] = b"W\0\0OL\0FM\0";
+
+/// Placeholder string that always follows the title
+const TITLE_SEPARATOR: &str = "0000-0000";
+
+/// A parsed `Game.dat`
+#[derive(Debug, Clone)]
+pub struct GameDat {
+    /// Magic, settings bytes and string count
+    head: Vec<u8>,
+    pub title: WolfString,
+    /// Bytes between the title and the font
+    middle: Vec<u8>,
+    pub font: WolfString,
+    pub subfonts: [WolfString; 3],
+    pub default_pc_graphic: WolfString,
+    pub version: WolfString,
+    tail: Vec<u8>,
+}
+
+impl GameDat {
+    pub fn read(bytes: &[u8]) -> AppResult<Self> {
+        let mut reader = Reader::new(bytes);
+        reader.expect(GAME_MAGIC, "Game.dat header")?;
+        let settings_length = reader.count(1)?;
+        reader.bytes(settings_length)?;
+        reader.u32()?;
+        let head = reader.since(0).to_vec();
+
+        let title = reader.string()?;
+        let start = reader.pos();
+        let separator = reader.string()?;
+        if separator.text() != TITLE_SEPARATOR {
+            return Err(AppError::Parsing(format!(
+                "Unsupported Game.dat layout: expected '{}' after the title, found '{}'",
+                TITLE_SEPARATOR,
+                separator.text()
+            )));
+        }
+        let length = reader.count(1)?;
+        reader.bytes(length)?;
+        let middle = reader.since(start).to_vec();
+
+        let font = reader.string()?;
+        let subfonts = [reader.string()?, reader.string()?, reader.string()?];
+        let default_pc_graphic = reader.string()?;
+        let version = reader.string()?;
+        // File size minus one, rewritten on save
+        reader.u32()?;
+        let tail = reader.rest().to_vec();
+
+        Ok(Self {
+            head,
+            title,
+            middle,
+            font,
+            subfonts,
+            default_pc_graphic,
+            version,
+            tail,
+        })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut out = self.head.clone();
+        write_string(&mut out, &self.title);
+        out.extend_from_slice(&self.middle);
+        write_string(&mut out, &self.font);
+        for subfont in &self.subfonts {
+            write_string(&mut out, subfont);
+        }
+        write_string(&mut out, &self.default_pc_graphic);
+        write_string(&mut out, &self.version);
+        let size = out.len() + 4 + self.tail.len();
+        write_u32(&mut out, size as u32 - 1);
+        out.extend_from_slice(&self.tail);
+        out
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "title": self.title.text(),
+            "font": self.font.text(),
+            "subfonts": self.subfonts.iter().map(WolfString::text).collect::<Vec<&str>>(),
+            "defaultPcGraphic": self.default_pc_graphic.text(),
+            "version": self.version.text(),
+        })
+    }
+
+    /// Take the title and font names of the JSON form
+    pub fn apply_json(&mut self, json: &Value) -> AppResult<()> {
+        if let Some(title) = json.get("title").and_then(Value::as_str) {
+            self.title.set_text(title)?;
+        }
+        if let Some(font) = json.get("font").and_then(Value::as_str) {
+            self.font.set_text(font)?;
+        }
+        if let Some(subfonts) = json.get("subfonts").and_then(Value::as_array) {
+            for (subfont, value) in self.subfonts.iter_mut().zip(subfonts) {
+                if let Some(text) = value.as_str() {
+                    subfont.set_text(text)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_dat_round_trip_and_size() {
+        let mut bytes = GAME_MAGIC.to_vec();
+        write_u32(&mut bytes, 2);
+        bytes.extend_from_slice(&[0x01, 0x02]);
+        write_u32(&mut bytes, 9);
+        write_string(&mut bytes, &WolfString::from("勇者の冒険"));
+        write_string(&mut bytes, &WolfString::from(TITLE_SEPARATOR));
+        write_u32(&mut bytes, 1);
+        bytes.push(0x00);
+        for text in ["ＭＳ ゴシック", "", "", "", "", "2.10"] {
+            write_string(&mut bytes, &WolfString::from(text));
+        }
+        let size = bytes.len() + 4 + 3;
+        write_u32(&mut bytes, size as u32 - 1);
+        bytes.extend_from_slice(&[0x0A, 0x0B, 0x0C]);
+
+        let mut game = GameDat::read(&bytes).unwrap();
+        assert_eq!(game.write(), bytes);
+
+        let mut json = game.to_json();
+        assert_eq!(json["title"], "勇者の冒険");
+        json["title"] = json!("Hero's Journey");
+        game.apply_json(&json).unwrap();
+        let written = game.write();
+        let stored_size = &written[written.len() - 7..written.len() - 3];
+        assert_eq!(stored_size, &(written.len() as u32 - 1).to_le_bytes());
+        assert_eq!(
+            GameDat::read(&written).unwrap().title.text(),
+            "Hero's Journey"
+        );
+    }
+}
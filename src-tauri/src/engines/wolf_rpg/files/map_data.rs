@@ -0,0 +1,297 @@
+//! Map files (`Data/MapData/*.mps`).
+//!
+//! A map is a header and tile layers, then a list of events. Each event has
+//! pages, and each page ends with its command list. Only events, pages and
+//! commands are decoded; the rest is kept as stored.
+
+use serde_json::{json, Value};
+
+use super::coder::{write_string, write_u32, Reader, WolfString};
+use super::command::{
+    apply_commands_json, commands_to_json, read_commands, write_commands, Command,
+};
+use crate::core::error::{AppError, AppResult};
+
+const MAP_MAGIC: &[u8] = b"WOLFM\0";
+const EVENT_START: u8 = 0x6F;
+const EVENTS_END: u8 = 0x66;
+const EVENT_MAGIC: [u8; 4] = [0x39, 0x30, 0x00, 0x00];
+const PAGE_START: u8 = 0x79;
+const PAGES_END: u8 = 0x70;
+const PAGE_END: u8 = 0x7A;
+
+/// A parsed map file
+#[derive(Debug, Clone)]
+pub struct MapData {
+    /// Header and tile layers
+    head: Vec<u8>,
+    pub events: Vec<MapEvent>,
+    /// Bytes after the event list
+    tail: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapEvent {
+    pub id: u32,
+    pub name: WolfString,
+    pub x: u32,
+    pub y: u32,
+    pub pages: Vec<MapPage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapPage {
+    /// Graphic, conditions, movement and route, kept as stored
+    head: Vec<u8>,
+    pub commands: Vec<Command>,
+    /// Shadow and collision settings after the command list
+    tail: Vec<u8>,
+}
+
+impl MapData {
+    pub fn read(bytes: &[u8]) -> AppResult<Self> {
+        let mut reader = Reader::new(bytes);
+        reader.bytes(10)?;
+        reader.expect(MAP_MAGIC, "map header")?;
+        // Version bytes and the default tileset name
+        reader.bytes(9)?;
+        reader.string()?;
+        reader.u32()?;
+        let width = reader.u32()? as usize;
+        let height = reader.u32()? as usize;
+        reader.u32()?;
+        reader.bytes(width.saturating_mul(height).saturating_mul(12))?;
+        let head = reader.since(0).to_vec();
+
+        let mut events = Vec::new();
+        loop {
+            match reader.u8()? {
+                EVENT_START => events.push(MapEvent::read(&mut reader)?),
+                EVENTS_END => break,
+                other => {
+                    return Err(AppError::Parsing(format!(
+                        "Invalid map event marker {:#04X} at byte {}",
+                        other,
+                        reader.pos() - 1
+                    )))
+                }
+            }
+        }
+
+        Ok(Self {
+            head,
+            events,
+            tail: reader.rest().to_vec(),
+        })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut out = self.head.clone();
+        for event in &self.events {
+            out.push(EVENT_START);
+            event.write(&mut out);
+        }
+        out.push(EVENTS_END);
+        out.extend_from_slice(&self.tail);
+        out
+    }
+
+    /// JSON form walked by the map extractor (`events[].pages[].list[]`)
+    pub fn to_json(&self) -> Value {
+        let events: Vec<Value> = self
+            .events
+            .iter()
+            .map(|event| {
+                json!({
+                    "id": event.id,
+                    "name": event.name.text(),
+                    "x": event.x,
+                    "y": event.y,
+                    "pages": event.pages.iter().map(|page| json!({
+                        "list": commands_to_json(&page.commands),
+                    })).collect::<Vec<Value>>(),
+                })
+            })
+            .collect();
+        json!({ "events": events })
+    }
+
+    /// Take the command strings of the JSON form
+    pub fn apply_json(&mut self, json: &Value) -> AppResult<()> {
+        let Some(events) = json.get("events").and_then(Value::as_array) else {
+            return Ok(());
+        };
+        for (event, event_json) in self.events.iter_mut().zip(events) {
+            let Some(pages) = event_json.get("pages").and_then(Value::as_array) else {
+                continue;
+            };
+            for (page, page_json) in event.pages.iter_mut().zip(pages) {
+                apply_commands_json(&mut page.commands, page_json.get("list"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MapEvent {
+    fn read(reader: &mut Reader) -> AppResult<Self> {
+        reader.expect(&EVENT_MAGIC, "map event header")?;
+        let id = reader.u32()?;
+        let name = reader.string()?;
+        let x = reader.u32()?;
+        let y = reader.u32()?;
+        reader.u32()?;
+        reader.expect(&[0; 4], "map event header")?;
+
+        let mut pages = Vec::new();
+        loop {
+            match reader.u8()? {
+                PAGE_START => pages.push(MapPage::read(reader)?),
+                PAGES_END => break,
+                other => {
+                    return Err(AppError::Parsing(format!(
+                        "Invalid map page marker {:#04X} at byte {}",
+                        other,
+                        reader.pos() - 1
+                    )))
+                }
+            }
+        }
+
+        Ok(Self {
+            id,
+            name,
+            x,
+            y,
+            pages,
+        })
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&EVENT_MAGIC);
+        write_u32(out, self.id);
+        write_string(out, &self.name);
+        write_u32(out, self.x);
+        write_u32(out, self.y);
+        write_u32(out, self.pages.len() as u32);
+        out.extend_from_slice(&[0; 4]);
+        for page in &self.pages {
+            out.push(PAGE_START);
+            page.write(out);
+        }
+        out.push(PAGES_END);
+    }
+}
+
+impl MapPage {
+    fn read(reader: &mut Reader) -> AppResult<Self> {
+        let start = reader.pos();
+        reader.u32()?;
+        reader.string()?;
+        // Graphic direction and frame, trigger conditions, movement settings,
+        // then the flags and the move route
+        reader.bytes(4 + 37 + 4 + 2)?;
+        let route_length = reader.count(4)?;
+        for _ in 0..route_length {
+            reader.u8()?;
+            let arg_count = reader.u8()?;
+            reader.bytes(arg_count as usize * 4)?;
+            reader.expect(&[0x01, 0x00], "route command terminator")?;
+        }
+        let head = reader.since(start).to_vec();
+
+        let commands = read_commands(reader)?;
+
+        let start = reader.pos();
+        reader.bytes(7)?;
+        reader.expect(&[PAGE_END], "map page terminator")?;
+        let tail = reader.since(start).to_vec();
+
+        Ok(Self {
+            head,
+            commands,
+            tail,
+        })
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.head);
+        write_commands(out, &self.commands);
+        out.extend_from_slice(&self.tail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_map() -> Vec<u8> {
+        let mut out = vec![0; 10];
+        out.extend_from_slice(MAP_MAGIC);
+        out.extend_from_slice(&[0x64, 0, 0, 0, 0x65, 0, 0, 0, 0x66]);
+        write_string(&mut out, &WolfString::from("なし"));
+        for value in [0, 1, 1, 1] {
+            write_u32(&mut out, value);
+        }
+        out.extend_from_slice(&[0; 12]);
+
+        out.push(EVENT_START);
+        out.extend_from_slice(&EVENT_MAGIC);
+        write_u32(&mut out, 0);
+        write_string(&mut out, &WolfString::from("村人"));
+        for value in [0, 0, 1, 0] {
+            write_u32(&mut out, value);
+        }
+
+        out.push(PAGE_START);
+        write_u32(&mut out, 0);
+        write_string(&mut out, &WolfString::from(""));
+        out.extend_from_slice(&[0; 47]);
+        write_u32(&mut out, 0);
+        write_commands(
+            &mut out,
+            &[
+                Command {
+                    code: 101,
+                    int_args: vec![],
+                    indent: 0,
+                    string_args: vec![WolfString::from("こんにちは")],
+                    move_route: None,
+                },
+                Command {
+                    code: 0,
+                    int_args: vec![],
+                    indent: 0,
+                    string_args: vec![],
+                    move_route: None,
+                },
+            ],
+        );
+        out.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0, PAGE_END]);
+        out.push(PAGES_END);
+        out.push(EVENTS_END);
+        out
+    }
+
+    #[test]
+    fn test_map_round_trip_and_translation() {
+        let bytes = sample_map();
+        let mut map = MapData::read(&bytes).unwrap();
+        assert_eq!(map.write(), bytes);
+
+        let mut json = map.to_json();
+        assert_eq!(json["events"][0]["name"], "村人");
+        assert_eq!(
+            json["events"][0]["pages"][0]["list"][0]["stringArgs"][0],
+            "こんにちは"
+        );
+
+        json["events"][0]["pages"][0]["list"][0]["stringArgs"][0] = json!("Hello");
+        map.apply_json(&json).unwrap();
+        let translated = MapData::read(&map.write()).unwrap();
+        assert_eq!(
+            translated.events[0].pages[0].commands[0].string_args[0].text(),
+            "Hello"
+        );
+    }
+}
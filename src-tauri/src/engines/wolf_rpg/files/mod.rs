@@ -1,3 +1,13 @@
 // Wolf RPG specific helpers
+pub mod common;
 pub mod db;
+pub mod game;
 pub mod mps;
+
+// Binary data files read and written without an external dump
+pub mod coder;
+pub mod command;
+pub mod common_events;
+pub mod database;
+pub mod game_dat;
+pub mod map_data;
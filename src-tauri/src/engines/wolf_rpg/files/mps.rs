@@ -1,5 +1,5 @@
 // Text processing now handled by unified pipeline
-use crate::models::translation::{PromptType, TextUnit, TranslationStatus};
+use crate::models::translation::{PromptType, TextUnit, TranslationStatus, UnitPosition};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -53,8 +53,21 @@ fn extract_from_wolf_page(
         // Extract from command list (similar to RPG Maker event commands)
         if let Some(commands) = page_obj.get("list").and_then(|v| v.as_array()) {
             for (cmd_idx, command) in commands.iter().enumerate() {
+                let normalized_path = file_path.replace('\\', "/");
                 extract_from_wolf_command(
-                    text_units, command, file_path, event_idx, page_idx, cmd_idx,
+                    text_units,
+                    command,
+                    file_path,
+                    &format!(
+                        "events[{}].pages[{}].list[{}]",
+                        event_idx, page_idx, cmd_idx
+                    ),
+                    UnitPosition {
+                        event_ref: format!("{}#events[{}]", normalized_path, event_idx),
+                        page_index: page_idx as u32,
+                        command_index: cmd_idx as u32,
+                        speaker: None,
+                    },
                 );
             }
         }
@@ -63,76 +76,43 @@ fn extract_from_wolf_page(
 
 /// Extract text from Wolf RPG commands based on specific command codes
 /// Only processes known translatable command codes: 101, 210, 150, 122
-fn extract_from_wolf_command(
+///
+/// `command_path` locates the command in its file (`events[0].pages[0].list[3]`);
+/// common events use the same extractor with their own paths.
+pub fn extract_from_wolf_command(
     text_units: &mut Vec<TextUnit>,
     command: &Value,
     file_path: &str,
-    event_idx: usize,
-    page_idx: usize,
-    cmd_idx: usize,
+    command_path: &str,
+    position: UnitPosition,
 ) {
     if let Some(cmd_obj) = command.as_object() {
         // Get command code (like RPG Maker's command codes)
         let code = cmd_obj.get("code").and_then(|v| v.as_i64()).unwrap_or(0);
 
         // Extract based on specific translatable command codes only
-        match code {
-            101 => {
-                // Message - extract all text from stringArgs
-                extract_command_strings(
-                    text_units,
-                    cmd_obj,
-                    file_path,
-                    event_idx,
-                    page_idx,
-                    cmd_idx,
-                    code,
-                    PromptType::Dialogue,
-                );
-            }
-            210 => {
-                // CommonEvent - extract text but skip files (.mp3, .png, etc)
-                extract_command_strings(
-                    text_units,
-                    cmd_obj,
-                    file_path,
-                    event_idx,
-                    page_idx,
-                    cmd_idx,
-                    code,
-                    PromptType::Dialogue,
-                );
-            }
-            150 => {
-                // Picture - extract text but skip files, handle Wolf codes like \\E\\c[2], \n
-                extract_command_strings(
-                    text_units,
-                    cmd_obj,
-                    file_path,
-                    event_idx,
-                    page_idx,
-                    cmd_idx,
-                    code,
-                    PromptType::Dialogue,
-                );
-            }
-            122 => {
-                // SetString - extract text only if not empty
-                extract_command_strings(
-                    text_units,
-                    cmd_obj,
-                    file_path,
-                    event_idx,
-                    page_idx,
-                    cmd_idx,
-                    code,
-                    PromptType::Other,
-                );
-            }
-            _ => {
-                // Skip all other command codes - they don't contain translatable text
-            }
-        }
+        let prompt_type = match code {
+            // Message - extract all text from stringArgs
+            101 => PromptType::Dialogue,
+            // CommonEvent - string arguments passed to the called event
+            210 => PromptType::Dialogue,
+            // Picture - text pictures, handle Wolf codes like \\E\\c[2], \n
+            150 => PromptType::Dialogue,
+            // SetString - extract text only if not empty
+            122 => PromptType::Other,
+            // Skip all other command codes - they don't contain translatable text
+            _ => return,
+        };
+
+        extract_command_strings(
+            text_units,
+            cmd_obj,
+            file_path,
+            command_path,
+            code,
+            prompt_type,
+            position,
+        );
     }
 }
 
@@ -141,11 +121,10 @@ fn extract_command_strings(
     text_units: &mut Vec<TextUnit>,
     cmd_obj: &serde_json::Map<String, Value>,
     file_path: &str,
-    event_idx: usize,
-    page_idx: usize,
-    cmd_idx: usize,
+    command_path: &str,
     code: i64,
     prompt_type: PromptType,
+    position: UnitPosition,
 ) {
     if let Some(string_args) = cmd_obj.get("stringArgs").and_then(|v| v.as_array()) {
         for (arg_idx, arg) in string_args.iter().enumerate() {
@@ -161,24 +140,25 @@ fn extract_command_strings(
                 let normalized_path = file_path.replace('\\', "/");
                 text_units.push(TextUnit {
                     id: format!(
-                        "wolf_json:{}#events[{}].pages[{}].list[{}].stringArgs[{}]",
-                        normalized_path, event_idx, page_idx, cmd_idx, arg_idx
+                        "wolf_json:{}#{}.stringArgs[{}]",
+                        normalized_path, command_path, arg_idx
                     ),
                     source_text: processed_text,
                     translated_text: String::new(),
                     field_type: format!(
-                        "command_{}:{}:events[{}].pages[{}].list[{}]",
-                        code, file_path, event_idx, page_idx, cmd_idx
+                        "command_{}:{}:{}.stringArgs[{}]",
+                        code, normalized_path, command_path, arg_idx
                     ),
                     status: TranslationStatus::NotTranslated,
                     prompt_type,
+                    position: Some(position.clone()),
+                    detected_language: None,
                 });
             }
         }
     }
 }
 
-
 /// Inject translated text back into Wolf RPG MPS structures
 pub fn inject_text_units_into_mps(
     mps_data: &mut Value,
@@ -227,7 +207,13 @@ fn inject_into_wolf_page(
         if let Some(commands) = page_obj.get_mut("list").and_then(|v| v.as_array_mut()) {
             for (cmd_idx, command) in commands.iter_mut().enumerate() {
                 inject_into_wolf_command(
-                    command, text_units, file_path, event_idx, page_idx, cmd_idx,
+                    command,
+                    text_units,
+                    file_path,
+                    &format!(
+                        "events[{}].pages[{}].list[{}]",
+                        event_idx, page_idx, cmd_idx
+                    ),
                 );
             }
         }
@@ -235,13 +221,11 @@ fn inject_into_wolf_page(
 }
 
 /// Inject translations into Wolf RPG commands
-fn inject_into_wolf_command(
+pub fn inject_into_wolf_command(
     command: &mut Value,
     text_units: &HashMap<String, &TextUnit>,
     file_path: &str,
-    event_idx: usize,
-    page_idx: usize,
-    cmd_idx: usize,
+    command_path: &str,
 ) {
     if let Some(cmd_obj) = command.as_object_mut() {
         // Inject into stringArgs if present
@@ -249,8 +233,8 @@ fn inject_into_wolf_command(
             for (arg_idx, arg) in string_args.iter_mut().enumerate() {
                 let normalized_path = file_path.replace('\\', "/");
                 let unit_id = format!(
-                    "wolf_json:{}#events[{}].pages[{}].list[{}].stringArgs[{}]",
-                    normalized_path, event_idx, page_idx, cmd_idx, arg_idx
+                    "wolf_json:{}#{}.stringArgs[{}]",
+                    normalized_path, command_path, arg_idx
                 );
                 if let Some(text_unit) = text_units.get(&unit_id) {
                     if !text_unit.translated_text.is_empty() {
@@ -51,12 +51,19 @@ pub fn run() {
             commands::handler::load_project,
             commands::handler::extract_text,
             commands::handler::extract_text_with_merge,
+            commands::handler::reextract_text,
             commands::handler::extract_game_data_files,
             commands::handler::export_translated_subset,
+            commands::handler::check_font_coverage,
             commands::handler::load_project_translations,
             commands::handler::get_available_projects,
             commands::handler::delete_project,
+            commands::handler::get_project_settings,
+            commands::handler::update_project_settings,
+            commands::handler::list_formatter_presets,
+            commands::handler::list_skipped_texts,
             commands::handler::translate_text_unit,
+            commands::handler::preview_translation_prompts,
             commands::handler::test_llm_connection,
             commands::handler::get_ollama_models,
             commands::handler::get_provider_models,
@@ -66,6 +73,11 @@ pub fn run() {
             commands::handler::glossary_delete_term,
             commands::handler::glossary_export_terms,
             commands::handler::glossary_import_terms,
+            commands::handler::list_character_profiles,
+            commands::handler::upsert_character_profile,
+            commands::handler::delete_character_profile,
+            commands::handler::get_style_guide,
+            commands::handler::save_style_guide,
             // Translation management commands
             commands::handler::list_translations_cmd,
             commands::handler::get_translation_cmd,
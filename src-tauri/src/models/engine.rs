@@ -1,4 +1,5 @@
 use crate::models::language::Language;
+use crate::models::settings::ProjectSettings;
 use crate::models::translation::TextUnit;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -7,7 +8,7 @@ use std::path::PathBuf;
 ///
 /// This enum identifies the specific RPG Maker version or other engine
 /// that was detected when analyzing a game project's files.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EngineType {
     /// RPG Maker MV engine (JavaScript-based)
     RpgMakerMv,
@@ -15,9 +16,27 @@ pub enum EngineType {
     /// RPG Maker MZ engine (newer JavaScript-based version)
     RpgMakerMz,
 
-    /// Wolf RPG Editor engine (Windows-only, orchestrated via external tools)
+    /// RPG Maker VX Ace engine (Ruby Marshal `.rvdata2` data)
+    RpgMakerVxAce,
+
+    /// RPG Maker VX engine (Ruby Marshal `.rvdata` data)
+    RpgMakerVx,
+
+    /// RPG Maker XP engine (Ruby Marshal `.rxdata` data)
+    RpgMakerXp,
+
+    /// RPG Maker 2000/2003 engine (binary LCF `.ldb`/`.lmu` data)
+    RpgMaker2k,
+
+    /// Wolf RPG Editor engine (unencrypted binary data or a WolfTL dump)
     WolfRpg,
 
+    /// Ren'Py engine (`.rpy` scripts, translated through `game/tl`)
+    RenPy,
+
+    /// TyranoScript/TyranoBuilder engine (`.ks` scenario scripts)
+    TyranoScript,
+
     /// Engine could not be determined or is not supported
     Unknown,
 }
@@ -45,7 +64,8 @@ pub struct EngineCriteria {
     pub extra_files: Vec<String>,
 
     /// Engine-declared data roots to export for minimal translated copies
-    /// Example: ["www/data"] for RPG Maker MV/MZ
+    /// Example: ["www/data"] for RPG Maker MV/MZ; a `*` in the file name
+    /// selects loose files instead (["Map*.lmu"] for RPG Maker 2000)
     pub export_data_roots: Vec<String>,
 }
 
@@ -81,6 +101,11 @@ pub struct EngineInfo {
     /// Project manifest hash for database operations (added dynamically)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manifest_hash: Option<String>,
+
+    /// Project settings for injection-time steps (added dynamically on export,
+    /// where the destination copy has no manifest to read them from)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<ProjectSettings>,
 }
 
 /// Represents a game data file containing text that needs translation.
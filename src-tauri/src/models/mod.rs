@@ -1,4 +1,5 @@
 pub mod engine;
 pub mod language;
 pub mod provider;
+pub mod settings;
 pub mod translation;
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-project settings stored in the project manifest (`.ludolingua.json`).
+///
+/// Every section falls back to its defaults when missing, so manifests written
+/// by older versions keep loading unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    /// Few-shot examples taken from reviewed translations
+    pub few_shot: FewShotSettings,
+
+    /// Surrounding dialogue lines shown as read-only context
+    pub context_window: ContextWindowSettings,
+
+    /// How control-code placeholders are presented to the model
+    pub placeholder_mode: PlaceholderMode,
+
+    /// Re-wrapping of translated dialogue to the message window at export
+    pub text_fit: TextFitSettings,
+
+    /// Game font used by the exported copy
+    pub fonts: FontSettings,
+
+    /// Extra control-code rules for message plugins
+    pub formatter: FormatterSettings,
+
+    /// Punctuation clean-up of translations for the target language at injection
+    pub typography: TypographySettings,
+
+    /// Patterns overriding the built-in extraction heuristics
+    pub extraction: ExtractionSettings,
+
+    /// Rhai script with per-project processing hooks
+    pub scripts: ScriptSettings,
+}
+
+/// Controls how many reviewed translations are shown to the model as examples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FewShotSettings {
+    /// Whether reviewed translations are added to prompts at all
+    pub enabled: bool,
+
+    /// Maximum number of example pairs per prompt
+    pub max_examples: usize,
+
+    /// Upper bound on the estimated tokens spent on examples per prompt
+    pub token_budget: usize,
+}
+
+impl Default for FewShotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_examples: 3,
+            token_budget: 300,
+        }
+    }
+}
+
+/// How many neighbouring lines of the same event page are shown with dialogue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextWindowSettings {
+    /// Number of lines before the translated one
+    pub previous_lines: usize,
+
+    /// Number of lines after the translated one
+    pub next_lines: usize,
+}
+
+impl Default for ContextWindowSettings {
+    fn default() -> Self {
+        Self {
+            previous_lines: 3,
+            next_lines: 1,
+        }
+    }
+}
+
+/// Message window fitting applied to translated dialogue during injection.
+///
+/// Widths are the usable text width in pixels; when unset, the engine's stock
+/// message window is assumed (with less room when a face graphic is shown).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextFitSettings {
+    /// Whether translated messages are re-wrapped at all
+    pub enabled: bool,
+
+    /// Lines per message page
+    pub max_lines: usize,
+
+    /// Text width of a message without a face graphic
+    pub width: Option<u32>,
+
+    /// Text width of a message with a face graphic
+    pub width_with_face: Option<u32>,
+
+    /// Font size in pixels (engine default when unset)
+    pub font_size: Option<u32>,
+
+    /// Font file measured instead of the game font, relative to the project root
+    pub font_file: Option<String>,
+}
+
+impl Default for TextFitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_lines: 4,
+            width: None,
+            width_with_face: None,
+            font_size: None,
+            font_file: None,
+        }
+    }
+}
+
+/// Font handling at export.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontSettings {
+    /// Font file (`.ttf`, `.otf` or `.woff`) installed as the main game font of
+    /// the exported copy, for target languages the bundled font cannot render
+    pub replacement_font: Option<String>,
+}
+
+/// Control-code rules applied on top of the engine's built-in ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatterSettings {
+    /// Enabled plugin presets (e.g. `yanfly_message_core`)
+    pub presets: Vec<String>,
+
+    /// JSON file with project-specific rules, relative to the project root
+    pub rules_file: Option<String>,
+
+    /// Re-extract the project on injection and refuse units whose source text
+    /// does not survive the formatter round trip (on by default)
+    pub verify_round_trip: bool,
+}
+
+impl Default for FormatterSettings {
+    fn default() -> Self {
+        Self {
+            presets: Vec::new(),
+            rules_file: None,
+            verify_round_trip: true,
+        }
+    }
+}
+
+/// Regex rules deciding which strings are extracted, ahead of the heuristics.
+///
+/// A string matching a deny pattern is always skipped; otherwise a string
+/// matching an allow pattern is always extracted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractionSettings {
+    /// Patterns of strings to extract even when the heuristics reject them
+    pub allow: Vec<String>,
+
+    /// Patterns of strings never to extract
+    pub deny: Vec<String>,
+}
+
+/// Project script hooking into extraction, prompting and injection.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptSettings {
+    /// Rhai script, relative to the project root (e.g. `ludolingua.rhai`)
+    pub file: Option<String>,
+}
+
+/// Target-language typography applied to translations during injection.
+///
+/// The quote style follows the target language unless set explicitly; the
+/// other passes can be switched off one by one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TypographySettings {
+    /// Whether the typography pass runs at all
+    pub enabled: bool,
+
+    /// Quotation marks to use (target language default when unset)
+    pub quotes: Option<QuoteStyle>,
+
+    /// Write ellipses as three periods instead of `…`
+    pub ascii_ellipsis: bool,
+
+    /// Turn full-width letters, digits, punctuation and spaces into half-width ones
+    pub half_width: bool,
+
+    /// Collapse repeated spaces and fix spacing around punctuation
+    pub fix_spacing: bool,
+
+    /// Capitalize the first letter of each sentence after the first
+    pub capitalize_sentences: bool,
+}
+
+impl Default for TypographySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quotes: None,
+            ascii_ellipsis: true,
+            half_width: true,
+            fix_spacing: true,
+            capitalize_sentences: true,
+        }
+    }
+}
+
+/// Quotation marks written into translated text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// `"..."` and `'...'`
+    Straight,
+
+    /// `“...”` and `‘...’`
+    Curly,
+
+    /// `«...»` and `“...”`
+    Guillemets,
+
+    /// `„...“` and `‚...‘`
+    Low,
+}
+
+/// Placeholder encoding used in prompts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceholderMode {
+    /// Descriptive placeholders such as `[COLOR_2]` or `[GOLD]`
+    #[default]
+    Named,
+
+    /// Opaque numbered tokens (`⟦1⟧`, `⟦2⟧`, ...) mapped back through a per-unit table
+    Opaque,
+}
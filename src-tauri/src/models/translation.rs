@@ -92,6 +92,168 @@ pub struct TextUnit {
 
     /// The type of prompt template that should be used when translating this text
     pub prompt_type: PromptType,
+
+    /// Where the text sits inside an event command list, for event-based text only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<UnitPosition>,
+
+    /// Language the source text was detected to be in at extraction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<DetectedLanguage>,
+}
+
+/// Result of the offline language identification of a text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code of the detected language (e.g. "ja", "fr")
+    pub language: String,
+
+    /// Confidence between 0 and 1
+    pub confidence: f32,
+}
+
+/// Location of an event-based text unit (dialogue line, choice, etc.).
+///
+/// Units that share `event_ref` and `page_index` belong to the same command list
+/// and can be ordered by `command_index`, which is what the prompt builder uses
+/// to show surrounding lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitPosition {
+    /// Identifies the event within its file (e.g. "www/data/Map001.json#map_1_event_5")
+    pub event_ref: String,
+
+    /// Page of the event the command list belongs to (0 for single-list events)
+    pub page_index: u32,
+
+    /// Index of the command within the page's command list
+    pub command_index: u32,
+
+    /// Speaker name declared by the message window, when the engine provides one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// Rule that kept a string out of extraction.
+///
+/// Built-in heuristics come from the source language's validation profile;
+/// `DenyRule` is a project deny pattern and `ScriptHook` the project script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Empty or whitespace-only text
+    Empty,
+
+    /// `EV001`-style event name
+    EventName,
+
+    /// `MAP001`-style map or switch name
+    MapName,
+
+    /// Quotation marks without any text
+    QuotesOnly,
+
+    /// A lone formatting code such as `\n[1]`
+    FormattingCode,
+
+    /// File name or path
+    FileName,
+
+    /// Script code or expression
+    Script,
+
+    /// Technical marker of the source language
+    TechnicalMarker,
+
+    /// Pipe-separated values (e.g. `はい|262|380`)
+    PipeSeparated,
+
+    /// Short Latin word in a game written in another script
+    ForeignWord,
+
+    /// Latin-only text in a game written in another script
+    ForeignText,
+
+    /// Variable name or identifier
+    Identifier,
+
+    /// Digits only
+    Numeric,
+
+    /// Too short to be translatable
+    TooShort,
+
+    /// No translatable letters, or ASCII text over the length limit
+    NotTranslatable,
+
+    /// Matched a deny pattern of the project
+    DenyRule,
+
+    /// Dropped by the `on_extract` hook of the project script
+    ScriptHook,
+}
+
+impl SkipReason {
+    /// Name stored in the database, same as the serialized form
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SkipReason::Empty => "empty",
+            SkipReason::EventName => "event_name",
+            SkipReason::MapName => "map_name",
+            SkipReason::QuotesOnly => "quotes_only",
+            SkipReason::FormattingCode => "formatting_code",
+            SkipReason::FileName => "file_name",
+            SkipReason::Script => "script",
+            SkipReason::TechnicalMarker => "technical_marker",
+            SkipReason::PipeSeparated => "pipe_separated",
+            SkipReason::ForeignWord => "foreign_word",
+            SkipReason::ForeignText => "foreign_text",
+            SkipReason::Identifier => "identifier",
+            SkipReason::Numeric => "numeric",
+            SkipReason::TooShort => "too_short",
+            SkipReason::NotTranslatable => "not_translatable",
+            SkipReason::DenyRule => "deny_rule",
+            SkipReason::ScriptHook => "script_hook",
+        }
+    }
+
+    /// Parse a stored name; unknown names (from newer versions) become `NotTranslatable`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "empty" => SkipReason::Empty,
+            "event_name" => SkipReason::EventName,
+            "map_name" => SkipReason::MapName,
+            "quotes_only" => SkipReason::QuotesOnly,
+            "formatting_code" => SkipReason::FormattingCode,
+            "file_name" => SkipReason::FileName,
+            "script" => SkipReason::Script,
+            "technical_marker" => SkipReason::TechnicalMarker,
+            "pipe_separated" => SkipReason::PipeSeparated,
+            "foreign_word" => SkipReason::ForeignWord,
+            "foreign_text" => SkipReason::ForeignText,
+            "identifier" => SkipReason::Identifier,
+            "numeric" => SkipReason::Numeric,
+            "too_short" => SkipReason::TooShort,
+            "deny_rule" => SkipReason::DenyRule,
+            "script_hook" => SkipReason::ScriptHook,
+            _ => SkipReason::NotTranslatable,
+        }
+    }
+}
+
+/// A raw string left out of extraction, with the rule that rejected it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedText {
+    /// Identifier the unit would have had
+    pub id: String,
+
+    /// Field type of the unit (e.g. "message:www/data/Map001.json:5")
+    pub field_type: String,
+
+    /// Text exactly as it appears in the game files
+    pub source_text: String,
+
+    /// Rule that rejected the text
+    pub reason: SkipReason,
 }
 
 /// Represents the current source language and target language for translation operations.
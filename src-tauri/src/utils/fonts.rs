@@ -0,0 +1,252 @@
+//! Font metrics for measuring and checking translated text.
+//!
+//! Loads TrueType/OpenType fonts (and WOFF 1.0, which RPG Maker MZ ships) and
+//! answers the two questions the exporters need: how wide a string renders at
+//! a given size, and whether the font has a glyph for a character at all.
+
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::ZlibDecoder;
+use ttf_parser::Face;
+
+use crate::core::error::{AppError, AppResult};
+
+/// Parsed font data with the face validated once at load time.
+#[derive(Debug, Clone)]
+pub struct FontMetrics {
+    data: Vec<u8>,
+    units_per_em: f32,
+}
+
+impl FontMetrics {
+    /// Load a `.ttf`, `.otf` or `.woff` file.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let data = std::fs::read(path).map_err(|e| {
+            AppError::FileSystem(format!("Failed to read font {}: {}", path.display(), e))
+        })?;
+        Self::from_bytes(data).map_err(|e| AppError::Parsing(format!("{} ({})", e, path.display())))
+    }
+
+    /// Parse font bytes, converting WOFF 1.0 to a plain sfnt first.
+    pub fn from_bytes(data: Vec<u8>) -> AppResult<Self> {
+        let data = if data.starts_with(b"wOFF") {
+            woff_to_sfnt(&data)?
+        } else if data.starts_with(b"wOF2") {
+            return Err(AppError::Parsing(
+                "WOFF2 fonts are not supported".to_string(),
+            ));
+        } else {
+            data
+        };
+
+        let units_per_em = Face::parse(&data, 0)
+            .map_err(|e| AppError::Parsing(format!("Invalid font: {}", e)))?
+            .units_per_em() as f32;
+        Ok(Self { data, units_per_em })
+    }
+
+    fn face(&self) -> Face<'_> {
+        // Validated in `from_bytes`
+        Face::parse(&self.data, 0).expect("font validated at load time")
+    }
+
+    /// Whether the font has a glyph for `c`.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.face().glyph_index(c).is_some()
+    }
+
+    /// Characters of `text` the font cannot render (whitespace and control
+    /// characters excluded), without duplicates and in order of appearance.
+    pub fn missing_glyphs(&self, text: &str) -> Vec<char> {
+        let face = self.face();
+        let mut missing: Vec<char> = Vec::new();
+        for c in text.chars() {
+            if c.is_whitespace() || c.is_control() || missing.contains(&c) {
+                continue;
+            }
+            if face.glyph_index(c).is_none() {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+
+    /// Width of `text` in pixels at `font_size`.
+    ///
+    /// Characters without a glyph are measured with `fallback_width` (in em),
+    /// since the browser will render them with some other font.
+    pub fn text_width(&self, text: &str, font_size: f32, fallback_width: f32) -> f32 {
+        let face = self.face();
+        let scale = font_size / self.units_per_em;
+        text.chars()
+            .map(|c| {
+                face.glyph_index(c)
+                    .and_then(|glyph| face.glyph_hor_advance(glyph))
+                    .map(|advance| advance as f32 * scale)
+                    .unwrap_or(fallback_width * font_size)
+            })
+            .sum()
+    }
+}
+
+/// Largest table count whose sfnt offset table still fits in 16-bit fields.
+const MAX_WOFF_TABLES: usize = 4095;
+
+/// Rebuild a plain sfnt (TrueType/OpenType) file from WOFF 1.0 data.
+fn woff_to_sfnt(woff: &[u8]) -> AppResult<Vec<u8>> {
+    let invalid = || AppError::Parsing("Invalid WOFF font".to_string());
+    let bytes_at = |offset: usize, len: usize| -> AppResult<&[u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| woff.get(offset..end))
+            .ok_or_else(invalid)
+    };
+    let u16_at = |offset: usize| -> AppResult<u16> {
+        bytes_at(offset, 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let u32_at = |offset: usize| -> AppResult<u32> {
+        bytes_at(offset, 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let flavor = u32_at(4)?;
+    let num_tables = u16_at(12)? as usize;
+    if num_tables == 0 || num_tables > MAX_WOFF_TABLES {
+        return Err(AppError::Parsing(format!(
+            "Invalid WOFF font: {} tables",
+            num_tables
+        )));
+    }
+
+    // sfnt offset table
+    let mut entry_selector = 0u16;
+    while (2usize << entry_selector) <= num_tables {
+        entry_selector += 1;
+    }
+    let search_range = 16u16
+        .checked_shl(u32::from(entry_selector))
+        .ok_or_else(invalid)?;
+    let range_shift = (num_tables as u16)
+        .checked_mul(16)
+        .and_then(|total| total.checked_sub(search_range))
+        .ok_or_else(invalid)?;
+    let mut out = Vec::new();
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(num_tables as u16).to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&range_shift.to_be_bytes());
+
+    let directory_len = 12 + 16 * num_tables;
+    out.resize(directory_len, 0);
+
+    for i in 0..num_tables {
+        let entry = 44 + i * 20;
+        let tag = u32_at(entry)?;
+        let offset = u32_at(entry + 4)? as usize;
+        let comp_length = u32_at(entry + 8)? as usize;
+        let orig_length = u32_at(entry + 12)? as usize;
+        let checksum = u32_at(entry + 16)?;
+
+        let compressed = bytes_at(offset, comp_length)?;
+        let table = if comp_length < orig_length {
+            let mut table = Vec::new();
+            ZlibDecoder::new(compressed)
+                .take(orig_length as u64)
+                .read_to_end(&mut table)
+                .map_err(|e| AppError::Parsing(format!("Invalid WOFF table data: {}", e)))?;
+            if table.len() != orig_length {
+                return Err(invalid());
+            }
+            table
+        } else {
+            compressed.to_vec()
+        };
+
+        let table_offset = u32::try_from(out.len()).map_err(|_| invalid())?;
+        let record = 12 + i * 16;
+        out[record..record + 4].copy_from_slice(&tag.to_be_bytes());
+        out[record + 4..record + 8].copy_from_slice(&checksum.to_be_bytes());
+        out[record + 8..record + 12].copy_from_slice(&table_offset.to_be_bytes());
+        out[record + 12..record + 16].copy_from_slice(&(table.len() as u32).to_be_bytes());
+        out.extend_from_slice(&table);
+        while out.len() % 4 != 0 {
+            out.push(0);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// WOFF 1.0 file holding the given (tag, data) tables, zlib-compressed.
+    fn woff(tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut directory = Vec::new();
+        let mut offset = 44 + 20 * tables.len();
+        for (tag, table) in tables {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(table).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let stored = if compressed.len() < table.len() {
+                compressed
+            } else {
+                table.to_vec()
+            };
+            directory.extend_from_slice(*tag);
+            directory.extend_from_slice(&(offset as u32).to_be_bytes());
+            directory.extend_from_slice(&(stored.len() as u32).to_be_bytes());
+            directory.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            directory.extend_from_slice(&0u32.to_be_bytes());
+            offset += stored.len();
+            data.extend_from_slice(&stored);
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(b"wOFF");
+        header.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        header.extend_from_slice(&(offset as u32).to_be_bytes());
+        header.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        header.resize(44, 0);
+        header.extend_from_slice(&directory);
+        header.extend_from_slice(&data);
+        header
+    }
+
+    #[test]
+    fn test_woff_to_sfnt() {
+        let repeated = [7u8; 64];
+        let sfnt = woff_to_sfnt(&woff(&[(b"aaaa", &repeated), (b"bbbb", b"xyz")])).unwrap();
+
+        assert_eq!(&sfnt[0..4], &0x0001_0000u32.to_be_bytes());
+        // numTables, searchRange, entrySelector, rangeShift
+        assert_eq!(&sfnt[4..12], &[0, 2, 0, 32, 0, 1, 0, 0]);
+        assert_eq!(&sfnt[12..16], b"aaaa");
+        assert_eq!(&sfnt[20..24], &44u32.to_be_bytes());
+        assert_eq!(&sfnt[24..28], &64u32.to_be_bytes());
+        assert_eq!(&sfnt[44..108], &repeated[..]);
+        assert_eq!(&sfnt[28..32], b"bbbb");
+        assert_eq!(&sfnt[36..40], &108u32.to_be_bytes());
+        assert_eq!(&sfnt[108..111], b"xyz");
+        assert_eq!(sfnt.len(), 112);
+    }
+
+    #[test]
+    fn test_truncated_woff_is_rejected() {
+        let data = woff(&[(b"aaaa", &[7u8; 64])]);
+        for len in [8, 40, 60, data.len() - 1] {
+            assert!(woff_to_sfnt(&data[..len]).is_err(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_woff_without_tables_is_rejected() {
+        assert!(woff_to_sfnt(&woff(&[])).is_err());
+        assert!(FontMetrics::from_bytes(woff(&[])).is_err());
+    }
+}
@@ -1,5 +1,9 @@
 // The utils module contains application-wide utilities and shared functionality
 // that can be reused across different parts of the application.
 
+pub mod fonts;
 pub mod prompts;
 pub mod text;
+
+#[cfg(test)]
+pub mod test_support;
@@ -6,6 +6,8 @@ use crate::core::error::{AppError, AppResult};
 use crate::db::glossary::model::GlossaryTerm;
 use crate::models::engine::EngineInfo;
 use crate::models::translation::{PromptType, TextUnit};
+use crate::utils::prompts::context::PromptContext;
+use crate::utils::prompts::template::render_template;
 
 /// Shared prompt builder utility for all LLM providers.
 ///
@@ -43,6 +45,7 @@ impl PromptBuilder {
         text_unit: &TextUnit,
         engine_info: &EngineInfo,
         terms: &[GlossaryTerm],
+        context: &PromptContext,
     ) -> String {
         debug!(
             "PromptBuilder: using DB glossary terms ({} terms) for prompt_type {:?}",
@@ -54,14 +57,12 @@ impl PromptBuilder {
             Err(_e) => return Self::build_fallback_prompt(text_unit, engine_info),
         };
 
-        // DB-only mode: use DB-rendered terms only (skip file vocabulary)
-        let db_vocab_src = Self::render_glossary_terms(terms);
-        let db_len = db_vocab_src.len();
-        let vocabulary_template =
-            Self::filter_vocabulary_sections(&db_vocab_src, text_unit.prompt_type);
+        // DB-only mode: use DB-rendered terms only (skip file vocabulary).
+        // Terms were already picked by occurrence in the source text, so no
+        // per-prompt-type section filtering is applied here.
+        let vocabulary_template = Self::render_glossary_terms(terms);
         debug!(
-            "PromptBuilder: DB-only vocabulary length: db={} bytes, filtered={} bytes",
-            db_len,
+            "PromptBuilder: DB-only vocabulary length: {} bytes",
             vocabulary_template.len()
         );
 
@@ -76,7 +77,8 @@ impl PromptBuilder {
         template.push_str("\n\n");
         template.push_str(&specific_content);
 
-        let mut final_prompt = Self::replace_template_variables(&template, text_unit, engine_info);
+        let mut final_prompt =
+            Self::replace_template_variables(&template, text_unit, engine_info, context);
         final_prompt.push_str("\n\n<<<INPUT_START>>>\n");
         final_prompt.push_str(&text_unit.source_text);
         final_prompt.push_str("\n<<<INPUT_END>>>\n");
@@ -91,6 +93,7 @@ impl PromptBuilder {
     ///
     /// * `text_unit` - The text unit to translate
     /// * `engine_info` - Information about the game engine and languages
+    /// * `context` - Per-unit context (speaker, surrounding lines) exposed to templates
     ///
     /// # Returns
     ///
@@ -98,6 +101,7 @@ impl PromptBuilder {
     pub async fn build_translation_prompt(
         text_unit: &TextUnit,
         engine_info: &EngineInfo,
+        context: &PromptContext,
     ) -> String {
         debug!(
             "PromptBuilder: no DB glossary provided; using file vocabulary only for prompt_type {:?}",
@@ -145,7 +149,7 @@ impl PromptBuilder {
 
         // Replace variables first
         let prompt_without_text =
-            Self::replace_template_variables(&template, text_unit, engine_info);
+            Self::replace_template_variables(&template, text_unit, engine_info, context);
 
         // Append raw text with explicit delimiters to prevent bleed
         let mut final_prompt = prompt_without_text;
@@ -155,6 +159,31 @@ impl PromptBuilder {
         final_prompt
     }
 
+    /// Add a strict placeholder reminder to an already built prompt.
+    ///
+    /// Used for the single retry after a translation lost or mangled placeholders;
+    /// the reminder lists every placeholder the output must contain and goes right
+    /// before the input block.
+    pub fn with_placeholder_reminder(prompt: &str, placeholders: &[String]) -> String {
+        let reminder = format!(
+            "**STRICT PLACEHOLDER CHECK:**\n\
+             Your previous answer lost or changed placeholders. The output MUST contain \
+             each of these tokens exactly as written, exactly as many times as listed, \
+             untranslated: {}\n\n",
+            placeholders.join(" ")
+        );
+        match prompt.rfind("<<<INPUT_START>>>") {
+            Some(index) => {
+                let mut strict = String::with_capacity(prompt.len() + reminder.len());
+                strict.push_str(&prompt[..index]);
+                strict.push_str(&reminder);
+                strict.push_str(&prompt[index..]);
+                strict
+            }
+            None => format!("{}\n\n{}", prompt, reminder),
+        }
+    }
+
     /// Filter the shared vocabulary to only include sections relevant to the prompt type.
     fn filter_vocabulary_sections(vocab: &str, prompt_type: PromptType) -> String {
         let wanted_sections: &[&str] = match prompt_type {
@@ -194,29 +223,29 @@ impl PromptBuilder {
 
     /// Replace template variables with actual content.
     ///
+    /// Besides `{source_language}` and `{target_language}`, templates can use
+    /// `{game_title}`, `{field_type}`, `{file_name}`, `{prompt_type}`, `{speaker}`,
+    /// `{previous_lines}` and `{next_lines}`, and wrap optional sections in
+    /// `{{#if var}} ... {{else}} ... {{/if}}`.
+    ///
     /// # Arguments
     ///
     /// * `template` - The template string with variables
     /// * `text_unit` - The text unit being translated
     /// * `engine_info` - Engine information
+    /// * `context` - Per-unit prompt context
     ///
     /// # Returns
     ///
     /// * `String` - The template with all variables replaced
     fn replace_template_variables(
         template: &str,
-        _text_unit: &TextUnit,
+        text_unit: &TextUnit,
         engine_info: &EngineInfo,
+        context: &PromptContext,
     ) -> String {
-        template
-            .replace(
-                "{source_language}",
-                &engine_info.source_language.native_name,
-            )
-            .replace(
-                "{target_language}",
-                &engine_info.target_language.native_name,
-            )
+        let vars = context.template_variables(text_unit, engine_info);
+        render_template(template, &vars)
     }
 
     /// Load a prompt template from the filesystem.
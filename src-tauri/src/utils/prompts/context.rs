@@ -0,0 +1,305 @@
+//! Per-unit context exposed to prompt templates.
+//!
+//! `PromptContext` carries the information a template can reference beyond the
+//! language pair: which game and file the text comes from, what kind of field it
+//! is, who is speaking and how that character talks, the project style guide,
+//! which lines surround it and which reviewed translations to imitate.
+//! `template_variables` flattens everything into the variable map consumed by
+//! `template::render_template`.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+use crate::db::profiles::model::CharacterProfile;
+use crate::models::engine::EngineInfo;
+use crate::models::translation::{PromptType, TextUnit};
+use crate::utils::prompts::examples::{render_examples, FewShotExample};
+use crate::utils::prompts::template::TemplateVars;
+
+/// Speaker given as a `【Name】` prefix.
+static BRACKET_SPEAKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*【([^】\n]{1,20})】").unwrap());
+
+/// Speaker given as a `Name「...」` prefix (short name directly before the quote).
+static QUOTE_SPEAKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*([^\s「『【】\[\]。、！？!?]{1,12})[「『]").unwrap());
+
+/// Particles ending narration before a quote (`彼は「...」`), never a name.
+const NARRATION_PARTICLES: &[char] = &['は', 'が', 'を', 'に', 'へ'];
+
+/// Trailing `(file)` annotation used by descriptive field types.
+static PAREN_FILE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(([^()]+)\)\s*$").unwrap());
+
+/// A neighbouring line shown to the model as read-only context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextLine {
+    /// Source text of the neighbouring unit
+    pub source_text: String,
+
+    /// Existing translation of that unit, if any
+    pub translated_text: Option<String>,
+}
+
+/// Extra context for a single prompt, on top of the text unit and engine info.
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    /// Name of the character speaking this line, if known
+    pub speaker: Option<String>,
+
+    /// Lines that come right before this one in the same event page (oldest first)
+    pub previous_lines: Vec<ContextLine>,
+
+    /// Lines that come right after this one in the same event page
+    pub next_lines: Vec<ContextLine>,
+
+    /// Reviewed translations from the same project used as demonstrations
+    pub examples: Vec<FewShotExample>,
+
+    /// Profile of the speaking character, when one is stored for the project
+    pub character_profile: Option<CharacterProfile>,
+
+    /// Project-wide style rules
+    pub style_guide: Option<String>,
+
+    /// Whether the source uses opaque `⟦n⟧` placeholder tokens
+    pub opaque_placeholders: bool,
+}
+
+impl PromptContext {
+    /// Build the default context for a unit using only what the unit itself carries.
+    ///
+    /// A speaker recorded with the unit's position wins over one guessed from the text.
+    pub fn for_text_unit(text_unit: &TextUnit) -> Self {
+        let recorded_speaker = text_unit
+            .position
+            .as_ref()
+            .and_then(|position| position.speaker.clone());
+        let speaker = if recorded_speaker.is_some() {
+            recorded_speaker
+        } else if text_unit.prompt_type == PromptType::Dialogue {
+            detect_speaker(&text_unit.source_text)
+        } else {
+            None
+        };
+
+        Self {
+            speaker,
+            ..Default::default()
+        }
+    }
+
+    /// Flatten the context into template variables.
+    pub fn template_variables(
+        &self,
+        text_unit: &TextUnit,
+        engine_info: &EngineInfo,
+    ) -> TemplateVars {
+        let mut vars = TemplateVars::new();
+        vars.insert(
+            "source_language".into(),
+            engine_info.source_language.native_name.clone(),
+        );
+        vars.insert(
+            "target_language".into(),
+            engine_info.target_language.native_name.clone(),
+        );
+        vars.insert("game_title".into(), engine_info.name.clone());
+        vars.insert("field_type".into(), field_label(&text_unit.field_type));
+        vars.insert(
+            "file_name".into(),
+            source_file_name(&text_unit.field_type).unwrap_or_default(),
+        );
+        vars.insert(
+            "prompt_type".into(),
+            format!("{:?}", text_unit.prompt_type).to_lowercase(),
+        );
+        vars.insert("speaker".into(), self.speaker.clone().unwrap_or_default());
+        vars.insert("previous_lines".into(), render_lines(&self.previous_lines));
+        vars.insert("next_lines".into(), render_lines(&self.next_lines));
+        vars.insert("examples".into(), render_examples(&self.examples));
+        vars.insert(
+            "character_profile".into(),
+            self.character_profile
+                .as_ref()
+                .map(render_profile)
+                .unwrap_or_default(),
+        );
+        vars.insert(
+            "opaque_placeholders".into(),
+            if self.opaque_placeholders {
+                "true".into()
+            } else {
+                String::new()
+            },
+        );
+        vars.insert(
+            "style_guide".into(),
+            self.style_guide.clone().unwrap_or_default(),
+        );
+        vars
+    }
+}
+
+/// Guess the speaker from common inline conventions (`【Name】...`, `Name「...」`).
+pub fn detect_speaker(source_text: &str) -> Option<String> {
+    let first_line = source_text.lines().next().unwrap_or("");
+    if let Some(caps) = BRACKET_SPEAKER_REGEX.captures(first_line) {
+        return Some(caps[1].trim().to_string()).filter(|name| !name.is_empty());
+    }
+    QUOTE_SPEAKER_REGEX
+        .captures(first_line)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !name.ends_with(NARRATION_PARTICLES))
+}
+
+/// Human-readable field label, i.e. the leading segment of a `field:file:index` field type.
+fn field_label(field_type: &str) -> String {
+    field_type
+        .split(':')
+        .next()
+        .unwrap_or(field_type)
+        .trim()
+        .to_string()
+}
+
+/// File (or map) name the unit was extracted from, without directory or extension.
+fn source_file_name(field_type: &str) -> Option<String> {
+    let from_segments = field_type.split(':').find_map(|segment| {
+        let path = Path::new(segment.trim());
+        let extension = path.extension()?.to_string_lossy();
+        if !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        path.file_stem().map(|s| s.to_string_lossy().to_string())
+    });
+
+    from_segments.or_else(|| {
+        PAREN_FILE_REGEX.captures(field_type).and_then(|caps| {
+            Path::new(caps[1].trim())
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        })
+    })
+}
+
+/// Render context lines as a bullet list, with the existing translation when known.
+fn render_lines(lines: &[ContextLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let source = line.source_text.replace('\n', " ");
+            match line
+                .translated_text
+                .as_deref()
+                .filter(|t| !t.trim().is_empty())
+            {
+                Some(translated) => format!("- {} => {}", source, translated.replace('\n', " ")),
+                None => format!("- {}", source),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render a character profile as a bullet list, skipping unset fields.
+fn render_profile(profile: &CharacterProfile) -> String {
+    let mut lines = vec![format!("- Name: {}", profile.name)];
+    let fields = [
+        ("Gender", &profile.gender),
+        ("Pronouns", &profile.pronouns),
+        ("Speech register", &profile.speech_register),
+        ("Honorifics", &profile.honorific_policy),
+        ("Notes", &profile.notes),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            lines.push(format!("- {}: {}", label, value));
+        }
+    }
+    let catchphrases: Vec<&str> = profile
+        .catchphrases
+        .iter()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if !catchphrases.is_empty() {
+        lines.push(format!("- Catchphrases: {}", catchphrases.join(" / ")));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_speaker() {
+        assert_eq!(
+            detect_speaker("【アリス】こんにちは"),
+            Some("アリス".into())
+        );
+        assert_eq!(detect_speaker("クロネ「殺すよ」"), Some("クロネ".into()));
+        assert_eq!(detect_speaker("「こんにちは」"), None);
+        assert_eq!(detect_speaker("主人公は勇気を振り絞って進んだ。"), None);
+        assert_eq!(detect_speaker("彼は「行くぞ」と叫んだ。"), None);
+        assert_eq!(detect_speaker("少女が『はい』"), None);
+        assert_eq!(detect_speaker("はるか「おはよう」"), Some("はるか".into()));
+    }
+
+    #[test]
+    fn test_source_file_name() {
+        assert_eq!(
+            source_file_name("message:www/data/Map001.json:5:12"),
+            Some("Map001".into())
+        );
+        assert_eq!(
+            source_file_name("Database entry name (DataBase.json)"),
+            Some("DataBase".into())
+        );
+        assert_eq!(source_file_name("name"), None);
+    }
+
+    #[test]
+    fn test_render_lines() {
+        let lines = vec![
+            ContextLine {
+                source_text: "おはよう".into(),
+                translated_text: Some("Good morning".into()),
+            },
+            ContextLine {
+                source_text: "元気？".into(),
+                translated_text: None,
+            },
+        ];
+        assert_eq!(render_lines(&lines), "- おはよう => Good morning\n- 元気？");
+    }
+
+    #[test]
+    fn test_field_label() {
+        assert_eq!(
+            field_label("description:www/data/Items.json:3"),
+            "description"
+        );
+        assert_eq!(
+            field_label("terms.basic[2]:data/System.json:0"),
+            "terms.basic[2]"
+        );
+    }
+
+    #[test]
+    fn test_render_profile() {
+        let profile = CharacterProfile {
+            name: "メイ".into(),
+            pronouns: Some("she/her".into()),
+            speech_register: Some("polite maid, always formal".into()),
+            notes: Some("  ".into()),
+            catchphrases: vec!["かしこまりました".into(), "".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            render_profile(&profile),
+            "- Name: メイ\n- Pronouns: she/her\n- Speech register: polite maid, always formal\n- Catchphrases: かしこまりました"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Few-shot example selection from reviewed translations.
+//!
+//! Candidates are reviewed pairs from the same project and prompt type. They are
+//! ranked by character-bigram similarity to the text being translated (which
+//! works for both spaced and unspaced scripts), most recent first on ties, and
+//! taken until either the example count or the token budget is exhausted.
+
+use std::collections::HashSet;
+
+use crate::models::settings::FewShotSettings;
+use crate::utils::prompts::tokens::estimate_tokens;
+
+/// Fixed per-example overhead for the `Input:`/`Output:` scaffolding.
+const EXAMPLE_OVERHEAD_TOKENS: usize = 6;
+
+/// A reviewed source/translation pair shown to the model as a demonstration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FewShotExample {
+    pub source_text: String,
+    pub translated_text: String,
+}
+
+impl FewShotExample {
+    fn estimated_tokens(&self) -> usize {
+        estimate_tokens(&self.source_text)
+            + estimate_tokens(&self.translated_text)
+            + EXAMPLE_OVERHEAD_TOKENS
+    }
+}
+
+/// Pick the examples to show for `source_text`.
+///
+/// `candidates` are expected in recency order (newest first); that order is
+/// kept between candidates with the same similarity score.
+pub fn select_examples(
+    source_text: &str,
+    candidates: Vec<FewShotExample>,
+    settings: &FewShotSettings,
+) -> Vec<FewShotExample> {
+    if !settings.enabled || settings.max_examples == 0 || settings.token_budget == 0 {
+        return Vec::new();
+    }
+
+    let target = bigrams(source_text);
+    let mut seen_sources = HashSet::new();
+    let mut scored: Vec<(f64, FewShotExample)> = candidates
+        .into_iter()
+        .filter(|c| !c.source_text.trim().is_empty() && !c.translated_text.trim().is_empty())
+        .filter(|c| seen_sources.insert(c.source_text.clone()))
+        .map(|c| (dice_similarity(&target, &bigrams(&c.source_text)), c))
+        .collect();
+
+    // Stable sort keeps recency order for equal scores
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut selected = Vec::new();
+    let mut used_tokens = 0usize;
+    for (_, example) in scored {
+        if selected.len() >= settings.max_examples {
+            break;
+        }
+        let cost = example.estimated_tokens();
+        if used_tokens + cost > settings.token_budget {
+            continue;
+        }
+        used_tokens += cost;
+        selected.push(example);
+    }
+    selected
+}
+
+/// Render examples in the same `Input:`/`Output:` layout the prompt files use.
+pub fn render_examples(examples: &[FewShotExample]) -> String {
+    examples
+        .iter()
+        .map(|e| format!("Input: {}\nOutput: {}", e.source_text, e.translated_text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Character bigrams of the text, ignoring whitespace.
+fn bigrams(text: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Sørensen–Dice coefficient between two bigram sets.
+fn dice_similarity(a: &HashSet<(char, char)>, b: &HashSet<(char, char)>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    (2 * shared) as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(source: &str, translated: &str) -> FewShotExample {
+        FewShotExample {
+            source_text: source.to_string(),
+            translated_text: translated.to_string(),
+        }
+    }
+
+    fn settings(max_examples: usize, token_budget: usize) -> FewShotSettings {
+        FewShotSettings {
+            enabled: true,
+            max_examples,
+            token_budget,
+        }
+    }
+
+    #[test]
+    fn test_prefers_similar_examples() {
+        let candidates = vec![
+            example("ゲームオーバー", "Game Over"),
+            example("薬草を使った！", "Used a medicinal herb!"),
+            example("ポーションを使った！", "Used a potion!"),
+        ];
+        let selected = select_examples("エリクサーを使った！", candidates, &settings(1, 500));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].translated_text, "Used a medicinal herb!");
+    }
+
+    #[test]
+    fn test_respects_count_and_budget() {
+        let candidates = vec![example("あ", "A"), example("い", "I"), example("う", "U")];
+        assert_eq!(
+            select_examples("え", candidates.clone(), &settings(2, 500)).len(),
+            2
+        );
+        // Each example costs 1 + 1 + overhead tokens
+        assert_eq!(
+            select_examples("え", candidates.clone(), &settings(3, 16)).len(),
+            2
+        );
+        assert!(select_examples("え", candidates, &settings(3, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_skips_duplicates_and_empty_translations() {
+        let candidates = vec![
+            example("はい", "Yes"),
+            example("はい", "Yeah"),
+            example("いいえ", ""),
+        ];
+        let selected = select_examples("はい", candidates, &settings(5, 500));
+        assert_eq!(selected, vec![example("はい", "Yes")]);
+    }
+
+    #[test]
+    fn test_render_examples() {
+        let rendered = render_examples(&[example("はい", "Yes"), example("いいえ", "No")]);
+        assert_eq!(
+            rendered,
+            "Input: はい\nOutput: Yes\n\nInput: いいえ\nOutput: No"
+        );
+    }
+}
@@ -5,3 +5,7 @@
 //! embed templates at compile time for robustness.
 
 pub mod builder;
+pub mod context;
+pub mod examples;
+pub mod template;
+pub mod tokens;
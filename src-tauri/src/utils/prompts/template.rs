@@ -0,0 +1,204 @@
+//! Minimal template renderer for prompt files.
+//!
+//! Supports two constructs:
+//! - `{name}` is replaced by the value of `name` when that variable is known.
+//!   Unknown `{...}` sequences are left untouched so literal braces in example
+//!   text (e.g. JSON snippets) survive rendering.
+//! - `{{#if name}} ... {{else}} ... {{/if}}` keeps the first branch when `name`
+//!   is set to a non-blank value, otherwise the optional `{{else}}` branch.
+//!   Blocks may be nested.
+
+use std::collections::HashMap;
+
+/// Variables available to a template, keyed by name (without braces).
+pub type TemplateVars = HashMap<String, String>;
+
+const IF_OPEN: &str = "{{#if ";
+const ELSE_TAG: &str = "{{else}}";
+const IF_CLOSE: &str = "{{/if}}";
+
+/// Render a template with the given variables.
+pub fn render_template(template: &str, vars: &TemplateVars) -> String {
+    let resolved = resolve_conditionals(template, vars);
+    substitute_variables(&resolved, vars)
+}
+
+/// Whether a variable counts as "set" for `{{#if}}` purposes.
+fn is_truthy(vars: &TemplateVars, name: &str) -> bool {
+    vars.get(name)
+        .map(|v| !v.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Expand all `{{#if}}` blocks, innermost content included.
+fn resolve_conditionals(template: &str, vars: &TemplateVars) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(IF_OPEN) {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + IF_OPEN.len()..];
+
+        let name_end = match after_open.find("}}") {
+            Some(pos) => pos,
+            None => {
+                // Unterminated tag: keep the remainder verbatim
+                output.push_str(&rest[start..]);
+                return output;
+            }
+        };
+        let name = after_open[..name_end].trim();
+        let body_start = &after_open[name_end + 2..];
+
+        let (then_branch, else_branch, consumed) = match split_block(body_start) {
+            Some(parts) => parts,
+            None => {
+                // Missing {{/if}}: keep the remainder verbatim
+                output.push_str(&rest[start..]);
+                return output;
+            }
+        };
+
+        let chosen = if is_truthy(vars, name) {
+            then_branch
+        } else {
+            else_branch.unwrap_or("")
+        };
+        output.push_str(&resolve_conditionals(chosen, vars));
+        rest = &body_start[consumed..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Split the body of an `{{#if}}` block into its branches.
+///
+/// Returns `(then, else, consumed_len)` where `consumed_len` covers the closing
+/// `{{/if}}` tag. Nested blocks are skipped over so their tags are not matched.
+fn split_block(body: &str) -> Option<(&str, Option<&str>, usize)> {
+    let mut depth = 0usize;
+    let mut else_at: Option<usize> = None;
+    let mut i = 0usize;
+
+    while i < body.len() {
+        let tail = &body[i..];
+        if tail.starts_with(IF_OPEN) {
+            depth += 1;
+            i += IF_OPEN.len();
+        } else if tail.starts_with(IF_CLOSE) {
+            if depth == 0 {
+                let then_end = else_at.unwrap_or(i);
+                let then_branch = &body[..then_end];
+                let else_branch = else_at.map(|e| &body[e + ELSE_TAG.len()..i]);
+                return Some((then_branch, else_branch, i + IF_CLOSE.len()));
+            }
+            depth -= 1;
+            i += IF_CLOSE.len();
+        } else if depth == 0 && else_at.is_none() && tail.starts_with(ELSE_TAG) {
+            else_at = Some(i);
+            i += ELSE_TAG.len();
+        } else {
+            i += tail.chars().next().map(char::len_utf8).unwrap_or(1);
+        }
+    }
+    None
+}
+
+/// Replace `{name}` occurrences for known variables.
+fn substitute_variables(template: &str, vars: &TemplateVars) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) if is_variable_name(&after[..close]) => {
+                let name = &after[..close];
+                match vars.get(name) {
+                    Some(value) => output.push_str(value),
+                    None => {
+                        output.push('{');
+                        output.push_str(name);
+                        output.push('}');
+                    }
+                }
+                rest = &after[close + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> TemplateVars {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_substitutes_known_variables() {
+        let v = vars(&[("target_language", "English"), ("game_title", "Quest")]);
+        assert_eq!(
+            render_template("{game_title} -> {target_language}", &v),
+            "Quest -> English"
+        );
+    }
+
+    #[test]
+    fn test_leaves_unknown_braces_untouched() {
+        let v = vars(&[("speaker", "Alice")]);
+        let template = r#"Config: { "enabled": true } {unknown} {speaker}"#;
+        assert_eq!(
+            render_template(template, &v),
+            r#"Config: { "enabled": true } {unknown} Alice"#
+        );
+    }
+
+    #[test]
+    fn test_conditional_blocks() {
+        let template = "A{{#if speaker}} said by {speaker}{{else}} narration{{/if}}.";
+        assert_eq!(
+            render_template(template, &vars(&[("speaker", "Bob")])),
+            "A said by Bob."
+        );
+        assert_eq!(render_template(template, &vars(&[])), "A narration.");
+        assert_eq!(
+            render_template(template, &vars(&[("speaker", "  ")])),
+            "A narration."
+        );
+    }
+
+    #[test]
+    fn test_nested_conditionals() {
+        let template = "{{#if a}}[a{{#if b}} b{{else}} no-b{{/if}}]{{/if}}";
+        assert_eq!(
+            render_template(template, &vars(&[("a", "1"), ("b", "1")])),
+            "[a b]"
+        );
+        assert_eq!(render_template(template, &vars(&[("a", "1")])), "[a no-b]");
+        assert_eq!(render_template(template, &vars(&[("b", "1")])), "");
+    }
+
+    #[test]
+    fn test_unterminated_block_is_kept() {
+        let template = "x {{#if a}} y";
+        assert_eq!(render_template(template, &vars(&[("a", "1")])), template);
+    }
+}
@@ -0,0 +1,32 @@
+//! Rough token estimation for prompt budgeting.
+//!
+//! Providers tokenize differently, so this is only meant for budgeting and cost
+//! projections: ASCII text averages about four characters per token, while CJK
+//! and other non-ASCII characters are counted as roughly one token each.
+
+/// Estimate the number of tokens `text` will use.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut ascii_chars = 0usize;
+    let mut other_chars = 0usize;
+    for c in text.chars() {
+        if c.is_ascii() {
+            ascii_chars += 1;
+        } else {
+            other_chars += 1;
+        }
+    }
+    ascii_chars.div_ceil(4) + other_chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Hello world!"), 3);
+        assert_eq!(estimate_tokens("こんにちは"), 5);
+        assert_eq!(estimate_tokens("HP回復"), 3);
+    }
+}
@@ -1,14 +1,17 @@
+use crate::engines::registry;
 use crate::models::engine::EngineType;
-use crate::models::translation::TextUnit;
+use crate::models::translation::{SkippedText, TextUnit, TranslationStatus};
 use crate::utils::text::engines::{
-    formatter_trait::EngineFormatter,
-    rpg_maker_formatter::RpgMakerFormatter,
+    formatter_trait::EngineTextFormatter,
     universal_formatter::UniversalFormatter,
-    wolf_rpg_formatter::WolfRpgFormatter,
 };
+use crate::utils::text::language_detection::detect_language;
 use crate::utils::text::llm_output::clean_llm_output;
+use crate::utils::text::placeholders::{strip_placeholders, PlaceholderTable};
+use crate::utils::text::rules::RuleSet;
 use crate::utils::text::types::RawTextUnit;
-use crate::utils::text::validation::ContentValidator;
+use crate::utils::text::typography::{corner_brackets_to_quotes, Typography};
+use crate::utils::text::validation::{ContentValidator, ExtractionFilter, ValidationProfile};
 
 /// Engine-specific text processor that routes to appropriate formatter
 /// 
@@ -20,36 +23,86 @@ impl EngineTextProcessor {
     /// Process raw text units for extraction using engine-specific formatter
     ///
     /// This method automatically selects the appropriate formatter based on engine type
-    /// and processes text units through the optimized pipeline.
+    /// and processes text units through the optimized pipeline. `rules` holds the
+    /// project's extra control-code rules, applied before the engine's own. Each
+    /// unit is tagged with the detected language of its text, and units already in
+    /// the target language start out ignored.
     pub fn process_for_extraction(
         raw_units: Vec<RawTextUnit>,
+        source_language: &str,
         target_language: &str,
         engine_type: &EngineType,
+        rules: &RuleSet,
     ) -> Vec<TextUnit> {
-        raw_units
-            .into_iter()
-            .filter(|raw_unit| ContentValidator::validate_text(&raw_unit.source_text))
-            .map(|raw_unit| {
-                let clean_text = Self::prepare_for_translation(&raw_unit.source_text, engine_type);
-                let initial_status =
-                    ContentValidator::get_initial_status(&raw_unit.source_text, target_language);
-
-                // Set translated_text based on initial status
-                let translated_text = match initial_status {
-                    crate::models::translation::TranslationStatus::Ignored => clean_text.clone(),
-                    _ => String::new(),
-                };
-
-                TextUnit {
+        Self::partition_for_extraction(
+            raw_units,
+            source_language,
+            target_language,
+            engine_type,
+            rules,
+            &ExtractionFilter::default(),
+        )
+        .0
+    }
+
+    /// Process raw text units for extraction, also returning the rejected ones
+    ///
+    /// Same pipeline as `process_for_extraction`, with the project's allow/deny
+    /// patterns in `filter` checked before the source language heuristics. Every
+    /// rejected unit is returned with the rule that rejected it.
+    pub fn partition_for_extraction(
+        raw_units: Vec<RawTextUnit>,
+        source_language: &str,
+        target_language: &str,
+        engine_type: &EngineType,
+        rules: &RuleSet,
+        filter: &ExtractionFilter,
+    ) -> (Vec<TextUnit>, Vec<SkippedText>) {
+        let profile = ValidationProfile::for_language(source_language);
+        let mut text_units = Vec::new();
+        let mut skipped = Vec::new();
+
+        for raw_unit in raw_units {
+            if let Some(reason) = filter.rejection(&raw_unit.source_text, profile) {
+                skipped.push(SkippedText {
                     id: raw_unit.id,
-                    source_text: clean_text,
-                    translated_text,
                     field_type: raw_unit.field_type,
-                    status: initial_status,
-                    prompt_type: raw_unit.prompt_type,
-                }
-            })
-            .collect()
+                    source_text: raw_unit.source_text,
+                    reason,
+                });
+                continue;
+            }
+
+            let clean_text =
+                Self::prepare_for_translation(&raw_unit.source_text, engine_type, rules);
+            let text_only = strip_placeholders(&clean_text);
+            let detected_language = detect_language(&text_only);
+            let initial_status = ContentValidator::get_initial_status(
+                &text_only,
+                detected_language.as_ref(),
+                source_language,
+                target_language,
+            );
+
+            // Set translated_text based on initial status
+            let translated_text = match initial_status {
+                TranslationStatus::Ignored => clean_text.clone(),
+                _ => String::new(),
+            };
+
+            text_units.push(TextUnit {
+                id: raw_unit.id,
+                source_text: clean_text,
+                translated_text,
+                field_type: raw_unit.field_type,
+                status: initial_status,
+                prompt_type: raw_unit.prompt_type,
+                position: raw_unit.position,
+                detected_language,
+            });
+        }
+
+        (text_units, skipped)
     }
 
     /// Process text units for injection using engine-specific formatter
@@ -59,17 +112,20 @@ impl EngineTextProcessor {
     pub fn process_for_injection(
         text_units: &[TextUnit],
         engine_type: &EngineType,
+        rules: &RuleSet,
     ) -> Vec<RawTextUnit> {
         text_units
             .iter()
             .map(|unit| {
-                let restored_text = Self::restore_after_translation(&unit.translated_text, engine_type);
+                let restored_text =
+                    Self::restore_after_translation(&unit.translated_text, engine_type, rules);
 
                 RawTextUnit {
                     id: unit.id.clone(),
                     source_text: restored_text,
                     field_type: unit.field_type.clone(),
                     prompt_type: unit.prompt_type.clone(),
+                    position: unit.position.clone(),
                 }
             })
             .collect()
@@ -82,11 +138,19 @@ impl EngineTextProcessor {
     #[allow(dead_code)]
     pub fn process_translation_pipeline(
         raw_units: Vec<RawTextUnit>,
+        source_language: &str,
         target_language: &str,
         engine_type: &EngineType,
+        rules: &RuleSet,
     ) -> Vec<TextUnit> {
         // Step 1: Process for extraction (filter, format, encode)
-        let mut text_units = Self::process_for_extraction(raw_units, target_language, engine_type);
+        let mut text_units = Self::process_for_extraction(
+            raw_units,
+            source_language,
+            target_language,
+            engine_type,
+            rules,
+        );
 
         // Step 2: Clean any existing translated text (in case of re-processing)
         for unit in &mut text_units {
@@ -101,10 +165,14 @@ impl EngineTextProcessor {
     /// Process translated text units back to raw format for file injection using engine-specific formatter
     ///
     /// This method takes translated TextUnits and converts them back to RawTextUnits
-    /// with all formatting codes restored, ready for file I/O operations.
+    /// with all formatting codes restored, ready for file I/O operations. With
+    /// `typography`, translated text is normalized for the target language while
+    /// its placeholders are still in place.
     pub fn process_injection_pipeline(
         text_units: &[TextUnit],
         engine_type: &EngineType,
+        rules: &RuleSet,
+        typography: Option<&Typography>,
     ) -> Vec<RawTextUnit> {
         // Step 1: Clean LLM output from translated text
         let cleaned_units: Vec<TextUnit> = text_units
@@ -118,8 +186,51 @@ impl EngineTextProcessor {
             })
             .collect();
 
-        // Step 2: Process for injection (restore, decode)
-        Self::process_for_injection(&cleaned_units, engine_type)
+        // Step 2: Target-language typography (translations only); Wolf RPG
+        // writes corner brackets as straight quotes first
+        let wolf_quotes = matches!(engine_type, EngineType::WolfRpg);
+        let cleaned_units: Vec<TextUnit> = cleaned_units
+            .into_iter()
+            .map(|mut unit| {
+                if matches!(
+                    unit.status,
+                    TranslationStatus::MachineTranslated | TranslationStatus::HumanReviewed
+                ) {
+                    if wolf_quotes {
+                        unit.translated_text = corner_brackets_to_quotes(&unit.translated_text);
+                    }
+                    if let Some(typography) = typography {
+                        unit.translated_text = typography.apply(&unit.translated_text);
+                    }
+                }
+                unit
+            })
+            .collect();
+
+        // Step 3: Process for injection (restore, decode)
+        Self::process_for_injection(&cleaned_units, engine_type, rules)
+    }
+
+    /// Swap the named placeholders of prepared text for opaque numbered tokens
+    ///
+    /// Used in the opaque placeholder mode; the returned table restores the named
+    /// placeholders from the model output with `decode_opaque_placeholders`.
+    pub fn encode_opaque_placeholders(prepared_text: &str) -> (String, PlaceholderTable) {
+        PlaceholderTable::encode(prepared_text)
+    }
+
+    /// Restore named placeholders from opaque tokens using the unit's side table
+    pub fn decode_opaque_placeholders(text: &str, table: &PlaceholderTable) -> String {
+        table.decode(text)
+    }
+
+    /// Whether raw text comes back unchanged from preparation and restoration
+    ///
+    /// A unit whose source fails this check cannot be injected safely: even an
+    /// untouched translation would be written back different from the original.
+    pub fn round_trips(text: &str, engine_type: &EngineType, rules: &RuleSet) -> bool {
+        let prepared = Self::prepare_for_translation(text, engine_type, rules);
+        Self::restore_after_translation(&prepared, engine_type, rules) == text
     }
 
     /// Enhanced LLM response cleaning and extraction
@@ -133,37 +244,25 @@ impl EngineTextProcessor {
     /// Prepare text for translation using engine-specific formatter
     ///
     /// This method automatically selects the appropriate formatter based on engine type.
-    fn prepare_for_translation(text: &str, engine_type: &EngineType) -> String {
-        match engine_type {
-            EngineType::RpgMakerMv | EngineType::RpgMakerMz => {
-                RpgMakerFormatter::prepare_for_translation(text)
-            }
-            EngineType::WolfRpg => {
-                WolfRpgFormatter::prepare_for_translation(text)
-            }
-            EngineType::Unknown => {
-                // Fallback to universal formatter for unknown engines
-                UniversalFormatter::prepare_for_translation(text)
-            }
-        }
+    /// Project rules run first so plugin codes are replaced before the engine's.
+    fn prepare_for_translation(text: &str, engine_type: &EngineType, rules: &RuleSet) -> String {
+        let text = &rules.prepare(text);
+        (Self::formatter(engine_type).prepare)(text)
     }
 
     /// Restore text after translation using engine-specific formatter
     ///
     /// This method automatically selects the appropriate formatter based on engine type.
-    fn restore_after_translation(text: &str, engine_type: &EngineType) -> String {
-        match engine_type {
-            EngineType::RpgMakerMv | EngineType::RpgMakerMz => {
-                RpgMakerFormatter::restore_after_translation(text)
-            }
-            EngineType::WolfRpg => {
-                WolfRpgFormatter::restore_after_translation(text)
-            }
-            EngineType::Unknown => {
-                // Fallback to universal formatter for unknown engines
-                UniversalFormatter::restore_after_translation(text)
-            }
-        }
+    fn restore_after_translation(text: &str, engine_type: &EngineType, rules: &RuleSet) -> String {
+        let restored = (Self::formatter(engine_type).restore)(text);
+        rules.restore(&restored)
+    }
+
+    /// Formatter registered for the engine, the universal one for unknown engines
+    fn formatter(engine_type: &EngineType) -> EngineTextFormatter {
+        registry::registration(engine_type)
+            .map(|registration| registration.formatter)
+            .unwrap_or_else(EngineTextFormatter::of::<UniversalFormatter>)
     }
 }
 
@@ -179,12 +278,15 @@ mod tests {
             source_text: "\\C[1]勇者\\C[0]は\\I[317]薬草\\I[317]を使った！".to_string(),
             field_type: "test".to_string(),
             prompt_type: PromptType::Other,
+            position: None,
         }];
 
         let result = EngineTextProcessor::process_for_extraction(
             raw_units,
+            "ja",
             "en",
             &EngineType::RpgMakerMv,
+            &RuleSet::default(),
         );
 
         assert_eq!(result.len(), 1);
@@ -198,12 +300,15 @@ mod tests {
             source_text: "\\E\\i[1]テスト@1\\f[2]".to_string(),
             field_type: "test".to_string(),
             prompt_type: PromptType::Other,
+            position: None,
         }];
 
         let result = EngineTextProcessor::process_for_extraction(
             raw_units,
+            "ja",
             "en",
             &EngineType::WolfRpg,
+            &RuleSet::default(),
         );
 
         assert_eq!(result.len(), 1);
@@ -217,16 +322,166 @@ mod tests {
             source_text: "\\C[1]勇者\\C[0]は\\I[317]薬草\\I[317]を使った！".to_string(),
             field_type: "test".to_string(),
             prompt_type: PromptType::Other,
+            position: None,
         }];
 
         let result = EngineTextProcessor::process_for_extraction(
             raw_units,
+            "ja",
             "en",
             &EngineType::Unknown,
+            &RuleSet::default(),
         );
 
         assert_eq!(result.len(), 1);
         // Should use universal formatter (same result as RPG Maker in this case)
         assert_eq!(result[0].source_text, "[COLOR_1]勇者[COLOR_0]は[ITEM_317]薬草[ITEM_317]を使った！");
     }
+
+    #[test]
+    fn test_injection_typography_keeps_codes() {
+        let typography = Typography::for_language(
+            "en",
+            &crate::models::settings::TypographySettings::default(),
+        );
+        let units = vec![TextUnit {
+            id: "test1".to_string(),
+            source_text: "[COLOR_1]「行くぞ！」".to_string(),
+            translated_text: "[COLOR_1]「Let's go！」　[NAME_1]。 now".to_string(),
+            field_type: "test".to_string(),
+            status: TranslationStatus::MachineTranslated,
+            prompt_type: PromptType::Other,
+            position: None,
+            detected_language: None,
+        }];
+
+        let result = EngineTextProcessor::process_injection_pipeline(
+            &units,
+            &EngineType::RpgMakerMv,
+            &RuleSet::default(),
+            typography.as_ref(),
+        );
+
+        assert_eq!(result[0].source_text, "\\C[1]\"Let's go!\" \\N[1]. Now");
+    }
+
+    #[test]
+    fn test_wolf_injection_straightens_corner_brackets() {
+        let units = vec![TextUnit {
+            id: "test1".to_string(),
+            source_text: "「行くぞ！」".to_string(),
+            translated_text: "「Let's go!」[NEWLINE]「Yes.」".to_string(),
+            field_type: "test".to_string(),
+            status: TranslationStatus::MachineTranslated,
+            prompt_type: PromptType::Other,
+            position: None,
+            detected_language: None,
+        }];
+
+        let result = EngineTextProcessor::process_injection_pipeline(
+            &units,
+            &EngineType::WolfRpg,
+            &RuleSet::default(),
+            None,
+        );
+
+        assert_eq!(result[0].source_text, "\"Let's go!\"\n\"Yes.\"");
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Codes shared by every formatter (`{n}` is replaced by a number)
+        const UNIVERSAL_CODES: &[&str] = &[
+            "%{n}", "％{n}", "\\.", "\\|", "\\^", "\\!", "\\{", " ", "  ", "　", "\t",
+        ];
+
+        const RPG_MAKER_CODES: &[&str] = &[
+            "\\C[{n}]", "\\c[{n}]", "\\C", "\\N[{n}]", "\\n[{n}]", "\\V[{n}]", "\\v[{n}]",
+            "\\V[\\V[{n}]]", "\\S[{n}]", "\\I[{n}]", "\\W[{n}]", "\\A[{n}]", "\\P[{n}]", "\\G",
+            "\\$", "\\F[{n}]", "\\F1[{n}]", "\\FS[{n}]", "\\AA[{n}]", "\\}", "en(v[{n}]>{n})",
+            "\n",
+        ];
+
+        const RPG_MAKER_XP_CODES: &[&str] = &[
+            "\\c[{n}]", "\\n[{n}]", "\\v[{n}]", "\\g", "\\\\", "\\C[{n}]", "\\N[{n}]",
+            "\\V[{n}]", "\\G", "\n",
+        ];
+
+        const WOLF_RPG_CODES: &[&str] = &[
+            "\\E", "\\i[{n}]", "\\f[{n}]", "@{n}", "\\s[{n}]", "\\cself[{n}]", "\\r", "\r",
+            "\n", "「", "」",
+        ];
+
+        const RENPY_CODES: &[&str] = &[
+            "{b}", "{/b}", "{color=#{n}}", "{/color}", "{w=0.{n}}", "{nw}", "[player_name]",
+            "[mc.name!t]", "[[", "{{", "\n",
+        ];
+
+        const TYRANO_SCRIPT_CODES: &[&str] = &[
+            "[l]", "[r]", "[p]", "[cm]", "[emb exp=\"f.name\"]", "[ruby text=\"{n}\"]",
+            "[font color=0x{n}]", "[resetfont]",
+        ];
+
+        /// Plain text, formatting codes and whitespace in any order
+        fn game_text(codes: &'static [&'static str]) -> impl Strategy<Value = String> {
+            let all_codes: Vec<&str> = codes.iter().chain(UNIVERSAL_CODES).copied().collect();
+            let code = (prop::sample::select(all_codes), 0u32..1000)
+                .prop_map(|(code, n)| code.replace("{n}", &n.to_string()));
+            let fragment = prop_oneof![
+                "[a-zA-Z!?.,']{1,8}",
+                "[ぁ-んァ-ン一-龥、。！？]{1,6}",
+                "[0-9０-９]{1,3}",
+                code,
+            ];
+            prop::collection::vec(fragment, 0..12).prop_map(|parts| parts.concat())
+        }
+
+        proptest! {
+            #[test]
+            fn rpg_maker_text_round_trips(text in game_text(RPG_MAKER_CODES)) {
+                let rules = RuleSet::default();
+                prop_assert_eq!(
+                    EngineTextProcessor::restore_after_translation(
+                        &EngineTextProcessor::prepare_for_translation(&text, &EngineType::RpgMakerMv, &rules),
+                        &EngineType::RpgMakerMv,
+                        &rules,
+                    ),
+                    text
+                );
+            }
+
+            #[test]
+            fn rpg_maker_xp_text_round_trips(text in game_text(RPG_MAKER_XP_CODES)) {
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::RpgMakerXp, &RuleSet::default()));
+            }
+
+            #[test]
+            fn wolf_rpg_text_round_trips(text in game_text(WOLF_RPG_CODES)) {
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::WolfRpg, &RuleSet::default()));
+            }
+
+            #[test]
+            fn renpy_text_round_trips(text in game_text(RENPY_CODES)) {
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::RenPy, &RuleSet::default()));
+            }
+
+            #[test]
+            fn tyrano_script_text_round_trips(text in game_text(TYRANO_SCRIPT_CODES)) {
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::TyranoScript, &RuleSet::default()));
+            }
+
+            #[test]
+            fn universal_text_round_trips(text in game_text(UNIVERSAL_CODES)) {
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::Unknown, &RuleSet::default()));
+            }
+
+            #[test]
+            fn numeric_prefix_round_trips(prefix in "[0-9０-９]{3}", sep in "[_＿]", name in "[a-zA-Zぁ-ん]{1,8}") {
+                let text = format!("{}{}{}", prefix, sep, name);
+                prop_assert!(EngineTextProcessor::round_trips(&text, &EngineType::WolfRpg, &RuleSet::default()));
+            }
+        }
+    }
 }
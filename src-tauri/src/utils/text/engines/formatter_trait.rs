@@ -15,3 +15,39 @@ pub trait EngineFormatter {
     /// Quick check if text contains engine-specific placeholder codes
     fn has_placeholder_codes(text: &str) -> bool;
 }
+
+/// Hex form of a code kept verbatim inside a placeholder (`[TAGX_7b62…]`),
+/// for codes whose text cannot appear in a placeholder name
+pub fn hex_encode(text: &str) -> String {
+    text.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Code stored by `hex_encode`, `None` when the hex was damaged
+pub fn hex_decode(hex: &str) -> Option<String> {
+    let bytes = hex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|p| p.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// An engine's formatter as plain functions, so it can be stored and chosen
+/// at run time (`EngineFormatter` has no receiver and is not object safe)
+#[derive(Clone, Copy)]
+pub struct EngineTextFormatter {
+    pub prepare: fn(&str) -> String,
+    pub restore: fn(&str) -> String,
+}
+
+impl EngineTextFormatter {
+    pub fn of<F: EngineFormatter>() -> Self {
+        Self {
+            prepare: F::prepare_for_translation,
+            restore: F::restore_after_translation,
+        }
+    }
+}
@@ -4,6 +4,9 @@
 /// that provide optimized performance by only processing relevant codes for each engine.
 
 pub mod formatter_trait;
+pub mod renpy_formatter;
 pub mod rpg_maker_formatter;
+pub mod rpg_maker_xp_formatter;
+pub mod tyrano_script_formatter;
 pub mod universal_formatter;
 pub mod wolf_rpg_formatter;
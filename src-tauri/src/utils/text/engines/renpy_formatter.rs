@@ -0,0 +1,147 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::formatter_trait::{hex_decode, hex_encode, EngineFormatter};
+use super::universal_formatter::UniversalFormatter;
+
+/// Ren'Py text tags (`{b}`, `{color=#f00}`), interpolations (`[name]`) and
+/// their doubled escapes (`{{`, `[[`), found in a single pass so an escape is
+/// never read as the start of a tag
+static RENPY_CODE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{|\[\[|\{[^{}\n]*\}|\[[^\[\]\n]*\]").unwrap());
+
+/// Placeholders produced by `prepare_for_translation`
+static RENPY_PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\[(?:LITERAL_BRACE|LITERAL_BRACKET|(TAG|END)_([a-z][a-z0-9]*)|(TAGX|VAR|VARX)_([A-Za-z0-9]+(?:_[A-Za-z0-9]+)*))\]",
+    )
+    .unwrap()
+});
+
+/// Simple tag names (`b`, `w`, `nw`)
+static TAG_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z][a-z0-9]*$").unwrap());
+
+/// Interpolated names that can be shown as they are (`player_name`)
+static VARIABLE_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9]+(?:_[A-Za-z0-9]+)*$").unwrap());
+
+/// Ren'Py specific text formatter
+///
+/// Ren'Py tags and interpolations carry arbitrary values, so they are not
+/// described as rules: simple ones keep their name in the placeholder
+/// (`{b}` → `[TAG_b]`, `{/b}` → `[END_b]`, `[name]` → `[VAR_name]`), and
+/// the others are stored hex-encoded (`{color=#f00}` → `[TAGX_…]`,
+/// `[mc.name!t]` → `[VARX_…]`) so they restore exactly.
+pub struct RenPyFormatter;
+
+impl EngineFormatter for RenPyFormatter {
+    /// Prepare Ren'Py text for translation
+    fn prepare_for_translation(text: &str) -> String {
+        if !Self::has_formatting_codes(text) {
+            return text.to_string();
+        }
+
+        let result = RENPY_CODE_REGEX
+            .replace_all(text, |caps: &Captures| {
+                let code = &caps[0];
+                match code {
+                    "{{" => "[LITERAL_BRACE]".to_string(),
+                    "[[" => "[LITERAL_BRACKET]".to_string(),
+                    _ if code.starts_with('{') => {
+                        let tag = &code[1..code.len() - 1];
+                        match tag.strip_prefix('/') {
+                            Some(name) if TAG_NAME_REGEX.is_match(name) => {
+                                format!("[END_{}]", name)
+                            }
+                            None if TAG_NAME_REGEX.is_match(tag) => format!("[TAG_{}]", tag),
+                            _ => format!("[TAGX_{}]", hex_encode(tag)),
+                        }
+                    }
+                    _ => {
+                        let expression = &code[1..code.len() - 1];
+                        if VARIABLE_NAME_REGEX.is_match(expression) {
+                            format!("[VAR_{}]", expression)
+                        } else {
+                            format!("[VARX_{}]", hex_encode(expression))
+                        }
+                    }
+                }
+            })
+            .to_string();
+
+        // Universal patterns go last, so they are restored first
+        UniversalFormatter::prepare_for_translation(&result)
+    }
+
+    /// Restore Ren'Py text after translation
+    fn restore_after_translation(text: &str) -> String {
+        if !Self::has_placeholder_codes(text) {
+            return text.to_string();
+        }
+
+        let result = UniversalFormatter::restore_after_translation(text);
+        RENPY_PLACEHOLDER_REGEX
+            .replace_all(&result, |caps: &Captures| {
+                let placeholder = &caps[0];
+                if let (Some(kind), Some(name)) = (caps.get(1), caps.get(2)) {
+                    return match kind.as_str() {
+                        "END" => format!("{{/{}}}", name.as_str()),
+                        _ => format!("{{{}}}", name.as_str()),
+                    };
+                }
+                if let (Some(kind), Some(value)) = (caps.get(3), caps.get(4)) {
+                    let value = value.as_str();
+                    return match kind.as_str() {
+                        "VAR" => format!("[{}]", value),
+                        "TAGX" => match hex_decode(value) {
+                            Some(tag) => format!("{{{}}}", tag),
+                            None => placeholder.to_string(),
+                        },
+                        _ => match hex_decode(value) {
+                            Some(expression) => format!("[{}]", expression),
+                            None => placeholder.to_string(),
+                        },
+                    };
+                }
+                match placeholder {
+                    "[LITERAL_BRACE]" => "{{".to_string(),
+                    _ => "[[".to_string(),
+                }
+            })
+            .to_string()
+    }
+
+    fn has_formatting_codes(text: &str) -> bool {
+        text.contains('{') || text.contains('[') || UniversalFormatter::has_formatting_codes(text)
+    }
+
+    fn has_placeholder_codes(text: &str) -> bool {
+        UniversalFormatter::has_placeholder_codes(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renpy_tags_round_trip() {
+        let input =
+            "{b}[player_name]{/b}, {color=#f00}look{/color}! [mc.name!t] costs {{5}} [[sic]{w=0.5}";
+        let prepared = RenPyFormatter::prepare_for_translation(input);
+        assert!(prepared.starts_with("[TAG_b][VAR_player_name][END_b], [TAGX_"));
+        assert!(prepared.contains("look[END_color]! [VARX_"));
+        assert!(prepared.contains("costs [LITERAL_BRACE]5}} [LITERAL_BRACKET]sic]"));
+        assert!(!prepared.contains('{'));
+        assert_eq!(RenPyFormatter::restore_after_translation(&prepared), input);
+    }
+
+    #[test]
+    fn test_plain_brackets_restore_unchanged() {
+        // Text already looking like a placeholder is an interpolation in Ren'Py
+        let input = "[VAR_x] and [TAG_b]";
+        let prepared = RenPyFormatter::prepare_for_translation(input);
+        assert_eq!(prepared, "[VAR_VAR_x] and [VAR_TAG_b]");
+        assert_eq!(RenPyFormatter::restore_after_translation(&prepared), input);
+    }
+}
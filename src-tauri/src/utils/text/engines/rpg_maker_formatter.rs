@@ -1,34 +1,8 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
 use super::formatter_trait::EngineFormatter;
 use super::universal_formatter::UniversalFormatter;
+use crate::utils::text::rules::{self, RuleSet};
 
-// to_ascii_digits is now handled by UniversalFormatter
-
-// === PRE-COMPILED RPG MAKER REGEXES ===
-
-// RPG Maker specific regexes only
-static COLOR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\C\[(\d+)\]").unwrap());
-static COLOR_REGEX_LOWER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\c\[(\d+)\]").unwrap());
-// Simple \C without brackets is handled with string replacement
-static NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\N\[(\d+)\]").unwrap());
-static NEWLINE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\n\[(\d+)\]").unwrap());
-static CONDITIONAL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"en\(v\[(\d+)\]>(\d+)\)").unwrap());
-static F_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\F([A-Za-z0-9]*)\[(\d+)\]").unwrap());
-static AA_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\AA\[(\d+)\]").unwrap());
-static CLOSE_BRACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\}").unwrap());
-
-// RPG Maker specific regexes only
-
-// Restoration regexes
-static COLOR_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[COLOR_(\d+)\]").unwrap());
-// Simple \C restoration is handled with string replacement
-static NAME_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[NAME_(\d+)\]").unwrap());
-static NEWLINE_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[NEWLINE_(\d+)\]").unwrap());
-static CONDITIONAL_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[CONDITIONAL_v(\d+)>(\d+)\]").unwrap());
-static F_CODE_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[F_([A-Za-z0-9]*)_(\d+)\]|\[F_(\d+)\]").unwrap());
-static AA_CODE_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[AA_(\d+)\]").unwrap());
-static CLOSE_BRACE_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[CLOSE_BRACE\]").unwrap());
+// RPG Maker codes are defined as data in `rules/rpg_maker.json`
 
 /// RPG Maker specific text formatter
 /// 
@@ -44,44 +18,8 @@ impl EngineFormatter for RpgMakerFormatter {
             return text.to_string();
         }
         
-        let mut result = text.to_string();
-
-        // Numeric prefixes are now handled by UniversalFormatter
-
-        // === RPG MAKER CODES ONLY (using pre-compiled regexes) ===
-        result = COLOR_REGEX.replace_all(&result, "[COLOR_$1]").to_string();
-        result = COLOR_REGEX_LOWER.replace_all(&result, "[COLOR_$1]").to_string();
-        // Handle simple \C without brackets (must be done after bracketed versions)
-        result = result.replace("\\C", "[COLOR_SIMPLE]");
-        result = NAME_REGEX.replace_all(&result, "[NAME_$1]").to_string();
-        result = NEWLINE_REGEX.replace_all(&result, "[NEWLINE_$1]").to_string();
-        result = F_CODE_REGEX.replace_all(&result, |caps: &regex::Captures| {
-            let letters = caps.get(1).map_or("", |m| m.as_str());
-            let number = caps.get(2).map_or("", |m| m.as_str());
-            if letters.is_empty() {
-                format!("[F_{}]", number)
-            } else {
-                format!("[F_{}_{}]", letters, number)
-            }
-        }).to_string();
-        result = AA_CODE_REGEX.replace_all(&result, "[AA_$1]").to_string();
-        result = CLOSE_BRACE_REGEX.replace_all(&result, "[CLOSE_BRACE]").to_string();
-
-        // Other RPG Maker codes (simple string replacements)
-        result = result.replace("\\V[", "[VARIABLE_");
-        result = result.replace("\\v[", "[variable_");
-        result = result.replace("\\S[", "[SWITCH_");
-        result = result.replace("\\I[", "[ITEM_");
-        result = result.replace("\\W[", "[WEAPON_");
-        result = result.replace("\\A[", "[ARMOR_");
-        result = result.replace("\\P[", "[ACTOR_");
-        result = result.replace("\\G", "[GOLD]");
-        result = result.replace("\\$", "[CURRENCY]");
-
-        // RPG Maker conditional expressions
-        result = CONDITIONAL_REGEX
-            .replace_all(&result, "[CONDITIONAL_v$1>$2]")
-            .to_string();
+        // === RPG MAKER CODES ONLY (built-in rule preset) ===
+        let mut result = Self::rules().prepare(text);
 
         // === UNIVERSAL PATTERNS (delegate to UniversalFormatter) ===
         result = UniversalFormatter::prepare_for_translation(&result);
@@ -96,50 +34,8 @@ impl EngineFormatter for RpgMakerFormatter {
             return text.to_string();
         }
         
-        let mut result = text.to_string();
-
-        // Whitespace decoding is now handled by UniversalFormatter
-
-        // === RPG MAKER CODES ONLY (using pre-compiled regexes) ===
-        result = COLOR_RESTORE_REGEX.replace_all(&result, "\\C[$1]").to_string();
-        // Handle simple \C without brackets
-        result = result.replace("[COLOR_SIMPLE]", "\\C");
-        result = NAME_RESTORE_REGEX.replace_all(&result, "\\N[$1]").to_string();
-        result = NEWLINE_RESTORE_REGEX.replace_all(&result, "\\n[$1]").to_string();
-        result = F_CODE_RESTORE_REGEX.replace_all(&result, |caps: &regex::Captures| {
-            // Check if it's the pattern with letters: [F_letters_number]
-            if let Some(letters_match) = caps.get(1) {
-                let letters = letters_match.as_str();
-                let number = caps.get(2).map_or("", |m| m.as_str());
-                if letters.is_empty() {
-                    format!("\\F[{}]", number)
-                } else {
-                    format!("\\F{}[{}]", letters, number)
-                }
-            } else {
-                // Pattern without letters: [F_number]
-                let number = caps.get(3).map_or("", |m| m.as_str());
-                format!("\\F[{}]", number)
-            }
-        }).to_string();
-        result = AA_CODE_RESTORE_REGEX.replace_all(&result, "\\AA[$1]").to_string();
-        result = CLOSE_BRACE_RESTORE_REGEX.replace_all(&result, "\\}").to_string();
-
-        // Other RPG Maker codes
-        result = result.replace("[VARIABLE_", "\\V[");
-        result = result.replace("[variable_", "\\v[");
-        result = result.replace("[SWITCH_", "\\S[");
-        result = result.replace("[ITEM_", "\\I[");
-        result = result.replace("[WEAPON_", "\\W[");
-        result = result.replace("[ARMOR_", "\\A[");
-        result = result.replace("[ACTOR_", "\\P[");
-        result = result.replace("[GOLD]", "\\G");
-        result = result.replace("[CURRENCY]", "\\$");
-
-        // Restore RPG Maker conditional expressions
-        result = CONDITIONAL_RESTORE_REGEX
-            .replace_all(&result, "en(v[$1]>$2)")
-            .to_string();
+        // === RPG MAKER CODES ONLY (built-in rule preset) ===
+        let mut result = Self::rules().restore(text);
 
         // === UNIVERSAL PATTERNS (delegate to UniversalFormatter) ===
         result = UniversalFormatter::restore_after_translation(&result);
@@ -177,6 +73,10 @@ impl EngineFormatter for RpgMakerFormatter {
 
 impl RpgMakerFormatter {
     // All universal patterns are now handled by UniversalFormatter
+
+    fn rules() -> &'static RuleSet {
+        rules::preset("rpg_maker").expect("rpg_maker rule preset is built in")
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,67 @@
+use super::formatter_trait::EngineFormatter;
+use super::rpg_maker_formatter::RpgMakerFormatter;
+use crate::utils::text::rules::{self, RuleSet};
+
+// XP-only codes are defined as data in `rules/rpg_maker_xp.json`
+
+/// RPG Maker XP specific text formatter
+///
+/// XP reads most RPG Maker codes, but `\n[n]` is an actor name rather than a
+/// newline, `\g` opens the gold window and `\\` is an escaped backslash. These
+/// are replaced first, then the text goes through the RPG Maker formatter.
+pub struct RpgMakerXpFormatter;
+
+impl EngineFormatter for RpgMakerXpFormatter {
+    /// Prepare XP text for translation: XP codes, then RPG Maker codes
+    fn prepare_for_translation(text: &str) -> String {
+        if !Self::has_formatting_codes(text) {
+            return text.to_string();
+        }
+
+        let result = Self::rules().prepare(text);
+        RpgMakerFormatter::prepare_for_translation(&result)
+    }
+
+    /// Restore XP text after translation, in the reverse order of preparation
+    fn restore_after_translation(text: &str) -> String {
+        if !Self::has_placeholder_codes(text) {
+            return text.to_string();
+        }
+
+        let result = RpgMakerFormatter::restore_after_translation(text);
+        Self::rules().restore(&result)
+    }
+
+    fn has_formatting_codes(text: &str) -> bool {
+        RpgMakerFormatter::has_formatting_codes(text)
+    }
+
+    fn has_placeholder_codes(text: &str) -> bool {
+        RpgMakerFormatter::has_placeholder_codes(text)
+    }
+}
+
+impl RpgMakerXpFormatter {
+    fn rules() -> &'static RuleSet {
+        rules::preset("rpg_maker_xp").expect("rpg_maker_xp rule preset is built in")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xp_codes_round_trip() {
+        let input = "\\c[2]\\n[1]\\c[0]「\\v[3]円だ」\\g\\\\";
+        let prepared = RpgMakerXpFormatter::prepare_for_translation(input);
+        assert_eq!(
+            prepared,
+            "[color_2][name_1][color_0]「[variable_3]円だ」[GOLD_WINDOW][BACKSLASH]"
+        );
+        assert_eq!(
+            RpgMakerXpFormatter::restore_after_translation(&prepared),
+            input
+        );
+    }
+}
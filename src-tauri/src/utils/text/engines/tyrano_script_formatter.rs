@@ -0,0 +1,93 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::formatter_trait::{hex_decode, hex_encode, EngineFormatter};
+use super::universal_formatter::UniversalFormatter;
+
+/// Inline TyranoScript tags (`[l]`, `[r]`, `[emb exp="f.name"]`); quoted
+/// attribute values may contain `]`
+static TYRANO_TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\[(?:[^\[\]"'\n]|"[^"\n]*"|'[^'\n]*')*\]"#).unwrap());
+
+/// Placeholders produced by `prepare_for_translation`
+static TYRANO_PLACEHOLDER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[(TYRANO|TYRANOX)_([A-Za-z0-9]+(?:_[A-Za-z0-9]+)*)\]").unwrap());
+
+/// Tags without attributes whose name can be shown as it is (`l`, `cm`)
+static TAG_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9]+(?:_[A-Za-z0-9]+)*$").unwrap());
+
+/// TyranoScript specific text formatter
+///
+/// Every `[...]` in a scenario line is a tag. Bare tags keep their name in
+/// the placeholder (`[l]` → `[TYRANO_l]`, `[r]` → `[TYRANO_r]`), tags with
+/// attributes are stored hex-encoded (`[emb exp="f.name"]` → `[TYRANOX_…]`)
+/// so they restore exactly.
+pub struct TyranoScriptFormatter;
+
+impl EngineFormatter for TyranoScriptFormatter {
+    /// Prepare TyranoScript text for translation
+    fn prepare_for_translation(text: &str) -> String {
+        if !Self::has_formatting_codes(text) {
+            return text.to_string();
+        }
+
+        let result = TYRANO_TAG_REGEX
+            .replace_all(text, |caps: &Captures| {
+                let tag = &caps[0][1..caps[0].len() - 1];
+                if TAG_NAME_REGEX.is_match(tag) {
+                    format!("[TYRANO_{}]", tag)
+                } else {
+                    format!("[TYRANOX_{}]", hex_encode(tag))
+                }
+            })
+            .to_string();
+
+        // Universal patterns go last, so they are restored first
+        UniversalFormatter::prepare_for_translation(&result)
+    }
+
+    /// Restore TyranoScript text after translation
+    fn restore_after_translation(text: &str) -> String {
+        if !Self::has_placeholder_codes(text) {
+            return text.to_string();
+        }
+
+        let result = UniversalFormatter::restore_after_translation(text);
+        TYRANO_PLACEHOLDER_REGEX
+            .replace_all(&result, |caps: &Captures| match &caps[1] {
+                "TYRANO" => format!("[{}]", &caps[2]),
+                _ => match hex_decode(&caps[2]) {
+                    Some(tag) => format!("[{}]", tag),
+                    None => caps[0].to_string(),
+                },
+            })
+            .to_string()
+    }
+
+    fn has_formatting_codes(text: &str) -> bool {
+        text.contains('[') || UniversalFormatter::has_formatting_codes(text)
+    }
+
+    fn has_placeholder_codes(text: &str) -> bool {
+        UniversalFormatter::has_placeholder_codes(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tyrano_tags_round_trip() {
+        let input = "こんにちは[l][r][emb exp=\"f.name\"]さん、[ruby text=\"かん]じ\"]漢字[p]";
+        let prepared = TyranoScriptFormatter::prepare_for_translation(input);
+        assert!(prepared.starts_with("こんにちは[TYRANO_l][TYRANO_r][TYRANOX_"));
+        assert!(prepared.ends_with("漢字[TYRANO_p]"));
+        assert!(!prepared.contains("f.name"));
+        assert_eq!(
+            TyranoScriptFormatter::restore_after_translation(&prepared),
+            input
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 use super::formatter_trait::EngineFormatter;
+use crate::utils::text::rules::{self, RuleSet};
 
 /// Convert full-width digits to ASCII digits within a string
 fn to_ascii_digits(s: &str) -> String {
@@ -12,11 +13,21 @@ fn to_ascii_digits(s: &str) -> String {
         .collect()
 }
 
+/// Convert ASCII digits to full-width digits within a string
+fn to_full_width_digits(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '0'..='9' => char::from_u32('０' as u32 + (c as u32 - '0' as u32)).unwrap(),
+            d => d,
+        })
+        .collect()
+}
+
 // === PRE-COMPILED UNIVERSAL REGEXES ===
 
 // Universal regexes (needed by all engines)
-static ARG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[%％]([0-9０-９]+)").unwrap());
-static NUM_PREFIX_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([0-9０-９]{3})[＿_](.+)$").unwrap());
+// Parameters and control codes are defined as data in `rules/universal.json`
+static NUM_PREFIX_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([0-9]{3}|[０-９]{3})([＿_])(.+)$").unwrap());
 
 // Whitespace regexes (needed by all engines)
 static FW_SPACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(　+)").unwrap());
@@ -26,8 +37,8 @@ static MULTI_SPACES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"( {2,})").unw
 static TABS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\t+)").unwrap());
 
 // Restoration regexes
-static ARG_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[ARG_(\d+)\]").unwrap());
-static NUM_PREFIX_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[NUM_PREFIX_(\d{3})\]").unwrap());
+static NUM_PREFIX_RESTORE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[NUM_PREFIX_([0-9]{3})(_FW)?(_ASCII)?\]").unwrap());
 static FW_SPACE_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[FWSPC_(\d+)\]").unwrap());
 static SPC_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[SPC_(\d+)\]").unwrap());
 static TAB_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[TAB_(\d+)\]").unwrap());
@@ -35,7 +46,7 @@ static TAB_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[TAB_(\d+)\]"
 /// Universal text formatter for common patterns
 /// 
 /// This formatter handles universal patterns that are common across all engines:
-/// - Parameter placeholders: %n, ％n
+/// - Parameter placeholders: %n
 /// - Numeric prefixes: 100＿text, ２００_text
 /// - Whitespace encoding: spaces, tabs, full-width spaces
 /// - Control codes: \., \|, \^, \!
@@ -54,25 +65,24 @@ impl EngineFormatter for UniversalFormatter {
         let mut result = text.to_string();
 
         // Preserve numeric map/area prefixes
+        // (`100＿` -> `[NUM_PREFIX_100]`; full-width digits and an ASCII
+        // underscore are marked so they restore exactly as written)
         if let Some(caps) = NUM_PREFIX_REGEX.captures(&result) {
-            let prefix_ascii = to_ascii_digits(&caps[1]);
-            let tail = caps[2].to_string();
-            result = format!("[NUM_PREFIX_{}]{}", prefix_ascii, tail);
+            let digits = &caps[1];
+            let width = if digits.is_ascii() { "" } else { "_FW" };
+            let separator = if &caps[2] == "_" { "_ASCII" } else { "" };
+            result = format!(
+                "[NUM_PREFIX_{}{}{}]{}",
+                to_ascii_digits(digits),
+                width,
+                separator,
+                &caps[3]
+            );
         }
 
-        // === UNIVERSAL CODES (using pre-compiled regexes) ===
-        result = ARG_REGEX
-            .replace_all(&result, |caps: &Captures| {
-                format!("[ARG_{}]", to_ascii_digits(&caps[1]))
-            })
-            .to_string();
-
-        // Control codes
-        result = result.replace("\\.", "[CTRL_DOT]");
-        result = result.replace("\\|", "[CTRL_WAIT]");
-        result = result.replace("\\^", "[CTRL_INSTANT]");
-        result = result.replace("\\!", "[CTRL_INPUT]");
-        result = result.replace("\\{", "[CTRL_OPEN_BRACE]");
+        // === UNIVERSAL CODES (built-in rule preset) ===
+        // Only ASCII `%n` is substituted by the engines; `％１` is plain text
+        result = Self::rules().prepare(&result);
 
         // === WHITESPACE ENCODING ===
         result = Self::encode_whitespace_placeholders(&result);
@@ -92,21 +102,22 @@ impl EngineFormatter for UniversalFormatter {
         // === WHITESPACE DECODING ===
         result = Self::decode_whitespace_placeholders(&result);
 
-        // === UNIVERSAL CODES ===
-        result = ARG_RESTORE_REGEX.replace_all(&result, "%$1").to_string();
+        // === UNIVERSAL CODES (built-in rule preset) ===
+        result = Self::rules().restore(&result);
 
         // Restore numeric prefix placeholders
         result = NUM_PREFIX_RESTORE_REGEX
-            .replace_all(&result, |caps: &Captures| format!("{}＿", &caps[1]))
+            .replace_all(&result, |caps: &Captures| {
+                let digits = if caps.get(2).is_some() {
+                    to_full_width_digits(&caps[1])
+                } else {
+                    caps[1].to_string()
+                };
+                let separator = if caps.get(3).is_some() { "_" } else { "＿" };
+                format!("{}{}", digits, separator)
+            })
             .to_string();
 
-        // Restore control codes
-        result = result.replace("[CTRL_DOT]", "\\.");
-        result = result.replace("[CTRL_WAIT]", "\\|");
-        result = result.replace("[CTRL_INSTANT]", "\\^");
-        result = result.replace("[CTRL_INPUT]", "\\!");
-        result = result.replace("[CTRL_OPEN_BRACE]", "\\{");
-
         result
     }
 
@@ -139,6 +150,10 @@ impl EngineFormatter for UniversalFormatter {
 }
 
 impl UniversalFormatter {
+    fn rules() -> &'static RuleSet {
+        rules::preset("universal").expect("universal rule preset is built in")
+    }
+
     /// Encode significant whitespace using pre-compiled regexes
     fn encode_whitespace_placeholders(input: &str) -> String {
         let mut result = input.to_string();
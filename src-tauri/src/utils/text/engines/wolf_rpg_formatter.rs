@@ -1,27 +1,8 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
 use super::formatter_trait::EngineFormatter;
 use super::universal_formatter::UniversalFormatter;
+use crate::utils::text::rules::{self, RuleSet};
 
-// to_ascii_digits is now handled by UniversalFormatter
-
-// === PRE-COMPILED WOLF RPG REGEXES ===
-
-// Wolf RPG specific regexes only
-static ICON_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\i\[(\d+)\]").unwrap());
-static FONT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\f\[(\d+)\]").unwrap());
-static AT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(\d+)").unwrap());
-static SLOT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\s\[(\d+)\]").unwrap());
-static CSELF_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\cself\[(\d+)\]").unwrap());
-
-// Wolf RPG specific regexes only
-
-// Restoration regexes
-static ICON_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[ICON_(\d+)\]").unwrap());
-static FONT_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[FONT_(\d+)\]").unwrap());
-static AT_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[AT_(\d+)\]").unwrap());
-static SLOT_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[SLOT_(\d+)\]").unwrap());
-static CSELF_RESTORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[CSELF_(\d+)\]").unwrap());
+// Wolf RPG codes are defined as data in `rules/wolf_rpg.json`
 
 /// Wolf RPG specific text formatter
 /// 
@@ -37,26 +18,8 @@ impl EngineFormatter for WolfRpgFormatter {
             return text.to_string();
         }
         
-        let mut result = text.to_string();
-
-        // Numeric prefixes are now handled by UniversalFormatter
-
-        // === WOLF RPG CODES ONLY (using pre-compiled regexes) ===
-        result = result.replace("\\E", "[WOLF_END]");
-        result = ICON_REGEX.replace_all(&result, "[ICON_$1]").to_string();
-        result = FONT_REGEX.replace_all(&result, "[FONT_$1]").to_string();
-        result = AT_REGEX.replace_all(&result, "[AT_$1]").to_string();
-        result = SLOT_REGEX.replace_all(&result, "[SLOT_$1]").to_string();
-        result = CSELF_REGEX.replace_all(&result, "[CSELF_$1]").to_string();
-
-        // Other Wolf RPG codes
-        result = result.replace("\\r", "[RUBY_START]");
-        result = result.replace('\r', "[CARRIAGE_RETURN]");
-        result = result.replace('\n', "[NEWLINE]");
-
-        // Japanese quotation marks
-        result = result.replace('「', "\"");
-        result = result.replace('」', "\"");
+        // === WOLF RPG CODES ONLY (built-in rule preset) ===
+        let mut result = Self::rules().prepare(text);
 
         // === UNIVERSAL PATTERNS (delegate to UniversalFormatter) ===
         result = UniversalFormatter::prepare_for_translation(&result);
@@ -71,22 +34,8 @@ impl EngineFormatter for WolfRpgFormatter {
             return text.to_string();
         }
         
-        let mut result = text.to_string();
-
-        // Whitespace decoding is now handled by UniversalFormatter
-
-        // === WOLF RPG CODES ONLY (using pre-compiled regexes) ===
-        result = result.replace("[WOLF_END]", "\\E");
-        result = ICON_RESTORE_REGEX.replace_all(&result, "\\i[$1]").to_string();
-        result = FONT_RESTORE_REGEX.replace_all(&result, "\\f[$1]").to_string();
-        result = AT_RESTORE_REGEX.replace_all(&result, "@$1").to_string();
-        result = SLOT_RESTORE_REGEX.replace_all(&result, "\\s[$1]").to_string();
-        result = CSELF_RESTORE_REGEX.replace_all(&result, "\\cself[$1]").to_string();
-
-        // Other Wolf RPG codes
-        result = result.replace("[RUBY_START]", "\\r");
-        result = result.replace("[CARRIAGE_RETURN]", "\\r");
-        result = result.replace("[NEWLINE]", "\n");
+        // === WOLF RPG CODES ONLY (built-in rule preset) ===
+        let mut result = Self::rules().restore(text);
 
         // === UNIVERSAL PATTERNS (delegate to UniversalFormatter) ===
         result = UniversalFormatter::restore_after_translation(&result);
@@ -130,6 +79,10 @@ impl EngineFormatter for WolfRpgFormatter {
 
 impl WolfRpgFormatter {
     // All universal patterns are now handled by UniversalFormatter
+
+    fn rules() -> &'static RuleSet {
+        rules::preset("wolf_rpg").expect("wolf_rpg rule preset is built in")
+    }
 }
 
 #[cfg(test)]
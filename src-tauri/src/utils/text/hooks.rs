@@ -0,0 +1,443 @@
+//! Per-project script hooks.
+//!
+//! A project can point `ScriptSettings::file` at a Rhai script inside its folder to
+//! handle game quirks (plugin codes, names that stay romanized, line-break
+//! styles) without code changes. The script defines any of these functions,
+//! each called with the text and a map describing the unit (`id`,
+//! `field_type`, `prompt_type`, `source_text`):
+//!
+//! - `on_extract(text, unit)`: raw source text at extraction; return `false`
+//!   to drop the unit, which is then listed as skipped by the script
+//! - `before_prompt(text, unit)`: source text shown to the model
+//! - `after_clean(text, unit)`: model output once cleaned
+//! - `before_inject(text, unit)`: final translation written to the game files
+//!
+//! A hook returns the new text, or `()` to keep it unchanged.
+//!
+//! Scripts run sandboxed: Rhai has no file or network access, `import` and
+//! `eval` are disabled, and every call is bounded in operations, call depth
+//! and data sizes. `print` and `debug` go to the log. Errors name the script,
+//! the hook and the unit that triggered them. A script is compiled once and
+//! reused until the file changes.
+//!
+//! ```rhai
+//! fn on_extract(text, unit) {
+//!     if text.starts_with("EV") { return false; }
+//! }
+//!
+//! fn before_inject(text, unit) {
+//!     text.replace("Kyouko", "Kyōko");
+//!     text
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+
+use crate::core::error::{AppError, AppResult};
+use crate::models::settings::ScriptSettings;
+use crate::models::translation::{PromptType, SkipReason, SkippedText, TextUnit};
+use crate::utils::text::types::RawTextUnit;
+
+/// Operations one hook call may run before it is aborted
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 1 << 20;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+const ON_EXTRACT: &str = "on_extract";
+const BEFORE_PROMPT: &str = "before_prompt";
+const AFTER_CLEAN: &str = "after_clean";
+const BEFORE_INJECT: &str = "before_inject";
+
+/// Compiled scripts by canonical path, with the modification time they were
+/// compiled at
+type CompiledScripts = HashMap<PathBuf, (SystemTime, Arc<ScriptHooks>)>;
+
+static COMPILED: Lazy<Mutex<CompiledScripts>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A compiled project script; without one every hook leaves text unchanged
+#[derive(Default)]
+pub struct ScriptHooks {
+    script: Option<Script>,
+}
+
+struct Script {
+    /// Script file, as shown in errors
+    name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+/// What a hook returned
+enum HookResult {
+    Keep,
+    Drop,
+    Replace(String),
+}
+
+impl ScriptHooks {
+    /// Hooks of a project, compiled from its script file if it has one.
+    ///
+    /// `file` is resolved against the project root and must stay inside it.
+    /// The compiled script is cached until the file is modified.
+    pub fn for_project(settings: &ScriptSettings, project_root: &Path) -> AppResult<Arc<Self>> {
+        let Some(file) = &settings.file else {
+            return Ok(Arc::new(Self::default()));
+        };
+        let path = Self::script_path(file, project_root)?;
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| {
+                AppError::FileSystem(format!("Failed to read script {}: {}", path.display(), e))
+            })?;
+
+        let mut compiled = COMPILED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((compiled_at, hooks)) = compiled.get(&path) {
+            if *compiled_at == modified {
+                return Ok(hooks.clone());
+            }
+        }
+        let source = std::fs::read_to_string(&path).map_err(|e| {
+            AppError::FileSystem(format!("Failed to read script {}: {}", path.display(), e))
+        })?;
+        let hooks = Arc::new(Self::compile(file, &source)?);
+        compiled.insert(path, (modified, hooks.clone()));
+        Ok(hooks)
+    }
+
+    /// Canonical path of a project script, refusing files outside the project
+    fn script_path(file: &str, project_root: &Path) -> AppResult<PathBuf> {
+        let canonical = |path: &Path| {
+            path.canonicalize().map_err(|e| {
+                AppError::FileSystem(format!("Failed to resolve {}: {}", path.display(), e))
+            })
+        };
+        let root = canonical(project_root)?;
+        let path = canonical(&root.join(file))?;
+        if !path.starts_with(&root) {
+            return Err(AppError::FileSystem(format!(
+                "Script {} is outside the project folder",
+                file
+            )));
+        }
+        Ok(path)
+    }
+
+    /// Compile a script; `name` identifies it in errors and log lines
+    pub fn compile(name: &str, source: &str) -> AppResult<Self> {
+        let engine = sandboxed_engine(name);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| AppError::Parsing(format!("Failed to compile script {}: {}", name, e)))?;
+        Ok(Self {
+            script: Some(Script {
+                name: name.to_string(),
+                engine,
+                ast,
+            }),
+        })
+    }
+
+    /// Apply `on_extract` to the raw units; the ones it rejects are returned
+    /// as skipped
+    pub fn filter_extracted(
+        &self,
+        raw_units: Vec<RawTextUnit>,
+    ) -> AppResult<(Vec<RawTextUnit>, Vec<SkippedText>)> {
+        let Some(script) = self.script_with(ON_EXTRACT) else {
+            return Ok((raw_units, Vec::new()));
+        };
+        let mut kept = Vec::with_capacity(raw_units.len());
+        let mut skipped = Vec::new();
+        for mut raw_unit in raw_units {
+            let unit = unit_map(
+                &raw_unit.id,
+                &raw_unit.field_type,
+                raw_unit.prompt_type,
+                &raw_unit.source_text,
+            );
+            match script.call(ON_EXTRACT, &raw_unit.id, &raw_unit.source_text, unit)? {
+                HookResult::Keep => kept.push(raw_unit),
+                HookResult::Drop => skipped.push(SkippedText {
+                    id: raw_unit.id,
+                    field_type: raw_unit.field_type,
+                    source_text: raw_unit.source_text,
+                    reason: SkipReason::ScriptHook,
+                }),
+                HookResult::Replace(text) => {
+                    raw_unit.source_text = text;
+                    kept.push(raw_unit);
+                }
+            }
+        }
+        Ok((kept, skipped))
+    }
+
+    /// Source text of a unit as it goes into the prompt
+    pub fn before_prompt(&self, text_unit: &TextUnit) -> AppResult<String> {
+        self.apply_to_unit(BEFORE_PROMPT, text_unit, &text_unit.source_text)
+    }
+
+    /// Cleaned model output for a unit
+    pub fn after_clean(&self, text_unit: &TextUnit, translation: &str) -> AppResult<String> {
+        self.apply_to_unit(AFTER_CLEAN, text_unit, translation)
+    }
+
+    /// Whether the script defines `before_inject`, which needs the source texts
+    pub fn runs_before_inject(&self) -> bool {
+        self.script_with(BEFORE_INJECT).is_some()
+    }
+
+    /// Apply `before_inject` to the units about to be written; `sources` maps
+    /// unit ids to their original text
+    pub fn before_inject(
+        &self,
+        raw_units: &mut [RawTextUnit],
+        sources: &HashMap<String, String>,
+    ) -> AppResult<()> {
+        let Some(script) = self.script_with(BEFORE_INJECT) else {
+            return Ok(());
+        };
+        for raw_unit in raw_units {
+            let source_text = sources.get(&raw_unit.id).map_or("", String::as_str);
+            let unit = unit_map(
+                &raw_unit.id,
+                &raw_unit.field_type,
+                raw_unit.prompt_type,
+                source_text,
+            );
+            if let HookResult::Replace(text) =
+                script.call(BEFORE_INJECT, &raw_unit.id, &raw_unit.source_text, unit)?
+            {
+                raw_unit.source_text = text;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_to_unit(&self, hook: &str, text_unit: &TextUnit, text: &str) -> AppResult<String> {
+        let Some(script) = self.script_with(hook) else {
+            return Ok(text.to_string());
+        };
+        let unit = unit_map(
+            &text_unit.id,
+            &text_unit.field_type,
+            text_unit.prompt_type,
+            &text_unit.source_text,
+        );
+        match script.call(hook, &text_unit.id, text, unit)? {
+            HookResult::Replace(text) => Ok(text),
+            _ => Ok(text.to_string()),
+        }
+    }
+
+    /// The script, if it defines `hook`
+    fn script_with(&self, hook: &str) -> Option<&Script> {
+        self.script.as_ref().filter(|script| {
+            script
+                .ast
+                .iter_functions()
+                .any(|f| f.name == hook && f.params.len() == 2)
+        })
+    }
+}
+
+impl Script {
+    fn call(&self, hook: &str, unit_id: &str, text: &str, unit: Map) -> AppResult<HookResult> {
+        let error = |message: String| {
+            AppError::Other(format!(
+                "Script {} failed in {} for unit {}: {}",
+                self.name, hook, unit_id, message
+            ))
+        };
+        let result: Dynamic = self
+            .engine
+            .call_fn_with_options(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &self.ast,
+                hook,
+                (text.to_string(), unit),
+            )
+            .map_err(|e| error(e.to_string()))?;
+
+        if result.is_unit() {
+            return Ok(HookResult::Keep);
+        }
+        if hook == ON_EXTRACT {
+            if let Ok(keep) = result.as_bool() {
+                return Ok(if keep {
+                    HookResult::Keep
+                } else {
+                    HookResult::Drop
+                });
+            }
+        }
+        let type_name = result.type_name();
+        result
+            .into_string()
+            .map(HookResult::Replace)
+            .map_err(|_| error(format!("expected a string or (), got {}", type_name)))
+    }
+}
+
+/// Rhai engine without module loading or `eval`, with bounded resources
+fn sandboxed_engine(name: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE);
+    engine.disable_symbol("eval");
+
+    let print_name = name.to_string();
+    engine.on_print(move |message| log::info!("[{}] {}", print_name, message));
+    let debug_name = name.to_string();
+    engine.on_debug(move |message, _, position| {
+        log::debug!("[{} {}] {}", debug_name, position, message)
+    });
+    engine
+}
+
+/// The unit as seen by a hook
+fn unit_map(id: &str, field_type: &str, prompt_type: PromptType, source_text: &str) -> Map {
+    let mut unit = Map::new();
+    unit.insert("id".into(), id.into());
+    unit.insert("field_type".into(), field_type.into());
+    unit.insert("prompt_type".into(), format!("{:?}", prompt_type).into());
+    unit.insert("source_text".into(), source_text.into());
+    unit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::TempDir;
+
+    fn raw_unit(id: &str, text: &str) -> RawTextUnit {
+        RawTextUnit {
+            id: id.to_string(),
+            source_text: text.to_string(),
+            field_type: "message:www/data/Map001.json:0".to_string(),
+            prompt_type: PromptType::Dialogue,
+            position: None,
+        }
+    }
+
+    #[test]
+    fn test_extract_and_inject_hooks() {
+        let hooks = ScriptHooks::compile(
+            "test.rhai",
+            r#"
+            fn on_extract(text, unit) {
+                if text.starts_with("EV") { return false; }
+                if unit.prompt_type == "Dialogue" { text.replace("\\n", " "); return text; }
+            }
+            fn before_inject(text, unit) { text + " <" + unit.source_text + ">" }
+            "#,
+        )
+        .unwrap();
+
+        let (extracted, skipped) = hooks
+            .filter_extracted(vec![raw_unit("a", "EV001"), raw_unit("b", "Hello\\nthere")])
+            .unwrap();
+        assert_eq!(extracted.len(), 1);
+        assert_eq!(extracted[0].source_text, "Hello there");
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            (skipped[0].id.as_str(), skipped[0].reason),
+            ("a", SkipReason::ScriptHook)
+        );
+
+        let mut injected = vec![raw_unit("b", "Bonjour")];
+        let sources = HashMap::from([("b".to_string(), "Hello".to_string())]);
+        hooks.before_inject(&mut injected, &sources).unwrap();
+        assert_eq!(injected[0].source_text, "Bonjour <Hello>");
+
+        // Hooks the script does not define leave text alone
+        let unit = TextUnit {
+            id: "b".to_string(),
+            source_text: "Hello".to_string(),
+            translated_text: String::new(),
+            field_type: String::new(),
+            status: crate::models::translation::TranslationStatus::NotTranslated,
+            prompt_type: PromptType::Dialogue,
+            position: None,
+            detected_language: None,
+        };
+        assert_eq!(hooks.before_prompt(&unit).unwrap(), "Hello");
+    }
+
+    #[test]
+    fn test_errors_name_the_unit_and_sandbox_limits_apply() {
+        let hooks = ScriptHooks::compile(
+            "test.rhai",
+            r#"
+            fn on_extract(text, unit) { if unit.id == "bad" { throw "no"; } }
+            fn before_inject(text, unit) { loop {} }
+            "#,
+        )
+        .unwrap();
+
+        let error = hooks
+            .filter_extracted(vec![raw_unit("ok", "a"), raw_unit("bad", "b")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("on_extract for unit bad"), "{}", error);
+
+        let error = hooks
+            .before_inject(&mut [raw_unit("loop", "a")], &HashMap::new())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("for unit loop"), "{}", error);
+
+        assert!(ScriptHooks::compile(
+            "import.rhai",
+            r#"fn on_extract(t, u) { import "fs" as fs; }"#
+        )
+        .unwrap()
+        .filter_extracted(vec![raw_unit("a", "a")])
+        .is_err());
+        assert!(ScriptHooks::compile("eval.rhai", r#"fn on_extract(t, u) { eval("1") }"#).is_err());
+    }
+
+    #[test]
+    fn test_project_script_is_confined_and_cached() {
+        let temp = TempDir::new("script_hooks_test");
+        let dir = temp.path();
+        let project = dir.join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(dir.join("outside.rhai"), "").unwrap();
+        std::fs::write(
+            project.join("hooks.rhai"),
+            "fn after_clean(text, unit) { text }",
+        )
+        .unwrap();
+
+        let settings = |file: &str| ScriptSettings {
+            file: Some(file.to_string()),
+        };
+        let first = ScriptHooks::for_project(&settings("hooks.rhai"), &project).unwrap();
+        let second = ScriptHooks::for_project(&settings("./hooks.rhai"), &project).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let outside = dir.join("outside.rhai").to_string_lossy().to_string();
+        for file in ["../outside.rhai", outside.as_str()] {
+            let error = ScriptHooks::for_project(&settings(file), &project)
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(error.contains("outside the project folder"), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Offline language identification for extracted text.
+//!
+//! Scripts decide most cases on their own: kana means Japanese, Hangul Korean,
+//! Cyrillic Russian, and Arabic script is split between Arabic and Persian by
+//! the letters only one of them uses. Han characters without kana are reported
+//! as Chinese with low confidence, since Japanese item names are often written
+//! in kanji only.
+//!
+//! Latin-script text is scored against small per-language profiles of frequent
+//! character trigrams and function words, plus letters specific to one
+//! language. Confidence grows with the margin over the runner-up and with the
+//! amount of text, so single words rarely reach the auto-ignore threshold.
+//!
+//! Mixed text is attributed to the script holding most letters, with the
+//! confidence scaled by that script's share.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::models::translation::DetectedLanguage;
+
+/// Writing system of a character or language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Han,
+    Kana,
+    Hangul,
+    Cyrillic,
+    Arabic,
+}
+
+/// Confidence reported for Han-only text (Japanese or Chinese)
+const HAN_ONLY_CONFIDENCE: f32 = 0.4;
+
+/// Trigrams needed before a Latin-script result is fully trusted
+const FULL_EVIDENCE_TRIGRAMS: f32 = 10.0;
+
+/// Frequency profile of a Latin-script language
+struct LatinProfile {
+    language: &'static str,
+
+    /// Frequent trigrams of space-padded lowercase words, most frequent first
+    trigrams: &'static [&'static str],
+
+    /// Frequent function words
+    words: &'static [&'static str],
+
+    /// Letters (almost) only this language uses
+    letters: &'static [char],
+}
+
+const LATIN_PROFILES: &[LatinProfile] = &[
+    LatinProfile {
+        language: "en",
+        trigrams: &[
+            " th", "the", "he ", "ing", "nd ", " an", "and", "ng ", " of", "of ", " to", "ed ",
+            "to ", " in", "ion", "er ", "in ", "is ", " is", "ent", "tio", "re ", "es ", " be",
+            "on ", "at ", "hat", "tha", " wh", "ou ", "you", "for", " yo", "or ", "her", "his",
+            "ll ", "was", " wa", "ve ", " it", "it ", "ere", " ha", "hav", "ave", "ly ", "all",
+            "not", " no", "ter", "are", " ar", "wit", "ith", " wi", "me ", "an ",
+        ],
+        words: &[
+            "the", "and", "you", "to", "of", "is", "it", "that", "in", "i", "me", "my", "your",
+            "for", "this", "was", "are", "what", "not", "be", "have", "with", "do", "we", "can",
+        ],
+        letters: &[],
+    },
+    LatinProfile {
+        language: "fr",
+        trigrams: &[
+            " de", "de ", "es ", "le ", " le", "ent", "ion", " la", "la ", "nt ", " et", "et ",
+            "re ", "les", " co", "tio", "on ", "e d", " pa", "que", "ue ", " qu", "des", "ne ",
+            "s d", " un", "ans", "men", "ait", "ous", "our", " po", "est", " es", "st ", "eur",
+            "un ", "ur ", " en", "en ", "ell", "e l", "pas", "par", "vou", " vo", " je", "je ",
+            "ais", "oir", "tre", " ce", "ce ", "ain", "mai", "qui",
+        ],
+        words: &[
+            "le", "la", "les", "de", "des", "et", "est", "je", "tu", "vous", "nous", "il", "elle",
+            "une", "que", "qui", "pas", "ne", "ce", "pour", "dans", "sur", "au", "avec", "mais",
+        ],
+        letters: &['è', 'ê', 'œ', 'â', 'î', 'û', 'ë', 'ï', 'ç'],
+    },
+    LatinProfile {
+        language: "de",
+        trigrams: &[
+            "en ", "er ", " de", "der", "ie ", "ich", "ein", "sch", " di", "die", "che", "den",
+            "ch ", " un", "und", "nd ", "gen", "ine", " ei", "cht", "in ", "ist", " is", "st ",
+            "te ", "ten", "es ", "nic", " ni", "ht ", "n d", "das", " da", "ung", "ng ", "auf",
+            "mit", " mi", "ber", "sie", " si", "ach", "nde", "ier", "ges", "ent", "lic", "eit",
+            "hen", "bei", "ter", "wir", "ihr", " ih", "ne ", "nen", "ben",
+        ],
+        words: &[
+            "der", "die", "das", "und", "ist", "ich", "du", "sie", "wir", "nicht", "ein", "eine",
+            "zu", "mit", "den", "dem", "es", "auf", "für", "was", "wie", "ja", "nein", "ihr",
+            "bin",
+        ],
+        letters: &['ä', 'ö', 'ü', 'ß'],
+    },
+    LatinProfile {
+        language: "es",
+        trigrams: &[
+            " de", "de ", "os ", " la", "la ", "el ", " el", "es ", " qu", "que", "ue ", " en",
+            "en ", "as ", "ent", " co", "con", "ón ", "ión", "ado", "los", " lo", "nte", "ar ",
+            "par", " pa", "por", " po", "do ", "una", "un ", " un", "est", " es", "ra ", "ero",
+            "ien", "sta", "tra", " se", "se ", "res", "er ", "mos", "no ", " no", "ida", "men",
+            "ame", "te ", "ía ", "ues", "ndo", " me", "me ",
+        ],
+        words: &[
+            "el", "la", "los", "las", "de", "y", "que", "en", "un", "una", "es", "no", "por",
+            "para", "con", "se", "lo", "me", "te", "yo", "tú", "qué", "está", "pero", "muy",
+        ],
+        letters: &['ñ', '¿', '¡'],
+    },
+    LatinProfile {
+        language: "pt",
+        trigrams: &[
+            " de", "de ", "os ", "do ", " do", "da ", " da", " qu", "que", "ue ", " co", "ão ",
+            "ção", "ent", " se", "em ", " e ", "com", " pa", "par", "ra ", "as ", "es ", "to ",
+            "não", " nã", "ar ", "um ", "uma", " um", "men", "nte", "est", " es", "ado", "ter",
+            "era", "dos", "das", "nho", "ou ", "voc", " vo", "ndo", " po", "por", "mos", " me",
+            "me ", "o d", "eu ", "mai", "ela", "lhe",
+        ],
+        words: &[
+            "o", "a", "os", "as", "de", "e", "que", "em", "um", "uma", "é", "não", "por", "para",
+            "com", "se", "eu", "você", "do", "da", "isso", "está", "mas", "muito", "ele",
+        ],
+        letters: &['ã', 'õ'],
+    },
+    LatinProfile {
+        language: "it",
+        trigrams: &[
+            " di", "di ", "che", " ch", "he ", "la ", " la", "to ", " co", "re ", "ell", "lla",
+            "one", "no ", " il", "il ", "del", " de", "ent", " pe", "per", "er ", "ato", "zio",
+            "ion", " un", "una", "na ", "non", " no", "ere", "i d", " in", "are", "gli", "sta",
+            "ess", "tto", "cos", "ono", "mi ", "ti ", "ami", " mi", "lo ", "eri", "ett", "anc",
+            "nto",
+        ],
+        words: &[
+            "il", "lo", "la", "i", "gli", "le", "di", "e", "che", "è", "un", "una", "non", "per",
+            "con", "sono", "io", "tu", "mi", "ti", "ci", "questo", "ma", "molto", "del",
+        ],
+        letters: &['ì', 'ò'],
+    },
+];
+
+/// Trigram weights per language: the most frequent trigram weighs 1, the least 0.5
+static TRIGRAM_WEIGHTS: Lazy<Vec<HashMap<&'static str, f32>>> = Lazy::new(|| {
+    LATIN_PROFILES
+        .iter()
+        .map(|profile| {
+            let count = profile.trigrams.len() as f32;
+            profile
+                .trigrams
+                .iter()
+                .enumerate()
+                .map(|(rank, trigram)| (*trigram, 1.0 - rank as f32 / (2.0 * count)))
+                .collect()
+        })
+        .collect()
+});
+
+/// Script of a letter, `None` for anything else (digits, punctuation, symbols)
+pub fn script_of_char(c: char) -> Option<Script> {
+    match c {
+        '\u{3040}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9D}' => {
+            Some(Script::Kana)
+        }
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}' => {
+            Some(Script::Han)
+        }
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+            Some(Script::Hangul)
+        }
+        '\u{0400}'..='\u{04FF}' => Some(Script::Cyrillic),
+        '\u{0600}'..='\u{06FF}'
+        | '\u{0750}'..='\u{077F}'
+        | '\u{FB50}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}' => Some(Script::Arabic),
+        // Full-width Latin letters
+        '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}' => Some(Script::Latin),
+        c if c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{024F}').contains(&c)) => {
+            Some(Script::Latin)
+        }
+        _ => None,
+    }
+}
+
+/// Scripts a language is written in
+pub fn language_scripts(language: &str) -> &'static [Script] {
+    match language {
+        "ja" => &[Script::Kana, Script::Han],
+        "zh" => &[Script::Han],
+        "ko" => &[Script::Hangul, Script::Han],
+        "ru" | "uk" | "bg" => &[Script::Cyrillic],
+        "ar" | "fa" => &[Script::Arabic],
+        _ => &[Script::Latin],
+    }
+}
+
+/// Identify the language of `text`; `None` when it has no letters.
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let mut script_counts: HashMap<Script, usize> = HashMap::new();
+    for script in text.chars().filter_map(script_of_char) {
+        *script_counts.entry(script).or_default() += 1;
+    }
+    let letters: usize = script_counts.values().sum();
+    if letters == 0 {
+        return None;
+    }
+    let count = |script| script_counts.get(&script).copied().unwrap_or(0);
+
+    // Kana and Han together make up Japanese text
+    let japanese = if count(Script::Kana) > 0 {
+        count(Script::Kana) + count(Script::Han)
+    } else {
+        0
+    };
+    let candidates = [
+        (Script::Kana, japanese),
+        (Script::Han, count(Script::Han)),
+        (Script::Hangul, count(Script::Hangul)),
+        (Script::Cyrillic, count(Script::Cyrillic)),
+        (Script::Arabic, count(Script::Arabic)),
+        (Script::Latin, count(Script::Latin)),
+    ];
+    let (script, script_letters) = candidates
+        .iter()
+        .copied()
+        .max_by_key(|(_, n)| *n)
+        .unwrap_or((Script::Latin, 0));
+    let share = script_letters as f32 / letters as f32;
+
+    let (language, confidence) = match script {
+        Script::Kana => ("ja", 1.0),
+        Script::Han => ("zh", HAN_ONLY_CONFIDENCE),
+        Script::Hangul => ("ko", 1.0),
+        Script::Cyrillic => ("ru", 0.9),
+        Script::Arabic => detect_arabic_script(text),
+        Script::Latin => detect_latin(text)?,
+    };
+    Some(DetectedLanguage {
+        language: language.to_string(),
+        confidence: (confidence * share).clamp(0.0, 1.0),
+    })
+}
+
+/// Arabic or Persian, by the letters specific to each
+fn detect_arabic_script(text: &str) -> (&'static str, f32) {
+    let persian = text
+        .chars()
+        .filter(|c| matches!(c, 'پ' | 'چ' | 'ژ' | 'گ' | 'ی' | 'ک'))
+        .count();
+    let arabic = text
+        .chars()
+        .filter(|c| matches!(c, 'ة' | 'ي' | 'ى' | 'ك'))
+        .count();
+    match persian.cmp(&arabic) {
+        std::cmp::Ordering::Greater => ("fa", 0.9),
+        std::cmp::Ordering::Less => ("ar", 0.9),
+        std::cmp::Ordering::Equal => ("ar", 0.5),
+    }
+}
+
+/// Best Latin-script language and its confidence
+fn detect_latin(text: &str) -> Option<(&'static str, f32)> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<String> = lowercase
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .map(|word| {
+            word.chars()
+                .map(|c| match c {
+                    // Full-width letters score like their ASCII forms
+                    '\u{FF41}'..='\u{FF5A}' => {
+                        char::from_u32(c as u32 - 0xFF41 + 0x61).unwrap_or(c)
+                    }
+                    _ => c,
+                })
+                .collect::<String>()
+        })
+        .filter(|word| {
+            word.chars()
+                .any(|c| script_of_char(c) == Some(Script::Latin))
+        })
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut trigram_count = 0usize;
+    let mut scores = vec![0.0f32; LATIN_PROFILES.len()];
+    for word in &words {
+        let padded: Vec<char> = format!(" {} ", word).chars().collect();
+        for window in padded.windows(3) {
+            let trigram: String = window.iter().collect();
+            trigram_count += 1;
+            for (score, weights) in scores.iter_mut().zip(TRIGRAM_WEIGHTS.iter()) {
+                if let Some(weight) = weights.get(trigram.as_str()) {
+                    *score += weight;
+                }
+            }
+        }
+        for (score, profile) in scores.iter_mut().zip(LATIN_PROFILES) {
+            if profile.words.contains(&word.as_str()) {
+                *score += 3.0;
+            }
+            *score += 2.0 * word.chars().filter(|c| profile.letters.contains(c)).count() as f32;
+        }
+    }
+
+    let mut ranked: Vec<(usize, f32)> = scores.into_iter().enumerate().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let (best_index, best) = ranked[0];
+    let runner_up = ranked.get(1).map(|(_, score)| *score).unwrap_or(0.0);
+    if best <= 0.0 {
+        // Latin letters without any profile evidence
+        return Some(("en", 0.0));
+    }
+
+    let margin = (best - runner_up) / best;
+    let evidence = (trigram_count as f32 / FULL_EVIDENCE_TRIGRAMS).min(1.0);
+    Some((LATIN_PROFILES[best_index].language, margin * evidence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> DetectedLanguage {
+        detect_language(text).unwrap()
+    }
+
+    #[test]
+    fn test_scripts() {
+        assert_eq!(detect("薬草を使った！").language, "ja");
+        assert_eq!(detect("안녕하세요").language, "ko");
+        assert_eq!(detect("Привет, как дела?").language, "ru");
+        assert_eq!(detect("این کتاب من است").language, "fa");
+        let han_only = detect("薬草");
+        assert_eq!(han_only.language, "zh");
+        assert!(han_only.confidence < 0.5);
+        assert!(detect_language("123 !?").is_none());
+    }
+
+    #[test]
+    fn test_latin_languages() {
+        let cases = [
+            ("Where are you going with the sword?", "en"),
+            ("Je ne sais pas où est le château.", "fr"),
+            ("Ich weiß nicht, wo die Burg ist.", "de"),
+            ("No sé dónde está el castillo, señor.", "es"),
+            ("Não sei onde está o castelo, você sabe?", "pt"),
+            ("Non so dove sia il castello, mi dispiace.", "it"),
+        ];
+        for (text, language) in cases {
+            let detected = detect(text);
+            assert_eq!(detected.language, language, "{}", text);
+            assert!(detected.confidence >= 0.6, "{}: {:?}", text, detected);
+        }
+    }
+
+    #[test]
+    fn test_mixed_text_scales_confidence() {
+        let detected = detect("HPが回復した");
+        assert_eq!(detected.language, "ja");
+        assert!(detected.confidence < 1.0);
+        assert!(detect("Potion").confidence < 0.6);
+    }
+}
@@ -1,5 +1,10 @@
 pub mod engine_processor;
 pub mod engines;
+pub mod hooks;
+pub mod language_detection;
 pub mod llm_output;
+pub mod placeholders;
+pub mod rules;
 pub mod types;
+pub mod typography;
 pub mod validation;
@@ -0,0 +1,480 @@
+//! Placeholder integrity between a prepared source and its translation.
+//!
+//! The formatters replace engine codes with bracketed placeholders such as
+//! `[COLOR_2]`, `[NAME_1]` or `[GOLD]` before text reaches the model. If the
+//! model drops, duplicates or rewrites one of them, restoring the codes
+//! silently produces a broken line, so translations are checked here first.
+//!
+//! `check_placeholders` compares the placeholder multisets. `repair_placeholders`
+//! fixes the simple cases: changed case or spacing (`[color_2]`, `[ COLOR 2 ]`,
+//! full-width brackets), a translated token name that keeps its number
+//! (`[COULEUR_2]`), and extra copies of a placeholder.
+//!
+//! `PlaceholderTable` implements the opaque mode: every placeholder of a unit is
+//! swapped for a numbered token (`⟦1⟧`, `⟦2⟧`, ...) that carries no meaning a
+//! model could translate, and the table maps the tokens back afterwards. A token
+//! still present after decoding (unknown number or repeated token) counts as an
+//! unexpected placeholder.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::utils::text::rules;
+
+/// Placeholders the Ren'Py, TyranoScript and whitespace formatters build in code
+/// rather than from rule presets.
+const FORMATTER_PLACEHOLDERS: &[&str] = &[
+    "LITERAL_BRACE",
+    "LITERAL_BRACKET",
+    "(?:TAG|TAGX|END|VAR|VARX|TYRANO|TYRANOX)_[A-Za-z0-9_]+",
+    "(?:NUM_PREFIX|FWSPC|SPC|TAB)_[A-Za-z0-9_]+",
+];
+
+/// A placeholder as produced by the formatters: only names they emit match,
+/// so bracketed game text such as `[SIC]` is left alone.
+static PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    let mut names = rules::builtin_placeholder_names();
+    names.extend(FORMATTER_PLACEHOLDERS.iter().map(|name| name.to_string()));
+    Regex::new(&format!(r"\[(?:{})\]", names.join("|"))).unwrap()
+});
+
+/// Any token shaped like a placeholder, including names the model translated (`[OR]`).
+static PLACEHOLDER_SHAPE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\[(?:[A-Z][A-Z0-9]*(?:_[A-Za-z0-9]+)*|(?:variable|color)_\d+|CONDITIONAL_v\d+>\d+)\]",
+    )
+    .unwrap()
+});
+
+/// Anything in (half- or full-width) square brackets that could be a mangled placeholder.
+static LOOSE_TOKEN_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\[［]([^\[\]［］\n]{1,40})[\]］]").unwrap());
+
+static DIGITS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Opaque token, tolerating spaces the model may add inside the brackets.
+static OPAQUE_TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"⟦\s*(\d+)\s*⟧").unwrap());
+
+/// Result of comparing the placeholders of a source and its translation.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlaceholderCheck {
+    /// Placeholders of the source that the translation lacks (one entry per missing copy)
+    pub missing: Vec<String>,
+
+    /// Placeholders in the translation that the source does not have (one entry per extra copy)
+    pub unexpected: Vec<String>,
+}
+
+impl PlaceholderCheck {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// All placeholders in `text`, in order of appearance.
+pub fn extract_placeholders(text: &str) -> Vec<String> {
+    PLACEHOLDER_REGEX
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Byte ranges of the placeholders in `text`, in order of appearance.
+pub fn placeholder_ranges(text: &str) -> Vec<Range<usize>> {
+    PLACEHOLDER_REGEX
+        .find_iter(text)
+        .map(|m| m.range())
+        .collect()
+}
+
+/// `text` without its placeholders.
+pub fn strip_placeholders(text: &str) -> String {
+    PLACEHOLDER_REGEX.replace_all(text, "").to_string()
+}
+
+/// Compare the placeholder multisets of `source` and `translation`.
+///
+/// Opaque tokens left in the translation are always unexpected.
+pub fn check_placeholders(source: &str, translation: &str) -> PlaceholderCheck {
+    let mut remaining = count(&extract_placeholders(source));
+    let mut unexpected: Vec<String> = OPAQUE_TOKEN_REGEX
+        .find_iter(translation)
+        .map(|m| m.as_str().to_string())
+        .collect();
+    for token in extract_placeholders(translation) {
+        match remaining.get_mut(&token) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => unexpected.push(token),
+        }
+    }
+
+    let mut missing = Vec::new();
+    for token in extract_placeholders(source) {
+        if let Some(n) = remaining.get_mut(&token) {
+            if *n > 0 {
+                *n -= 1;
+                missing.push(token);
+            }
+        }
+    }
+    PlaceholderCheck {
+        missing,
+        unexpected,
+    }
+}
+
+/// Try to repair the placeholders of `translation` so they match `source`.
+///
+/// Returns the repaired text when every source placeholder is accounted for
+/// exactly once afterwards, `None` when the damage is beyond simple fixes
+/// (e.g. a placeholder was dropped entirely).
+pub fn repair_placeholders(source: &str, translation: &str) -> Option<String> {
+    if check_placeholders(source, translation).is_ok() {
+        return Some(translation.to_string());
+    }
+
+    let mut remaining = count(&extract_placeholders(source));
+    let source_tokens: Vec<String> = remaining.keys().cloned().collect();
+
+    // Bracketed segments of the translation and what to do with each one
+    let segments: Vec<(usize, usize, String)> = LOOSE_TOKEN_REGEX
+        .captures_iter(translation)
+        .map(|caps| {
+            let m = caps.get(0).unwrap();
+            (m.start(), m.end(), caps[1].to_string())
+        })
+        .collect();
+    let mut resolutions: Vec<Option<Resolution>> = vec![None; segments.len()];
+
+    // Pass 1: placeholders that are already correct
+    for (i, (start, end, _)) in segments.iter().enumerate() {
+        let token = &translation[*start..*end];
+        if let Some(n) = remaining.get_mut(token) {
+            if *n > 0 {
+                *n -= 1;
+                resolutions[i] = Some(Resolution::Keep);
+            } else {
+                // Extra copy of a placeholder the source has
+                resolutions[i] = Some(Resolution::Remove);
+            }
+        }
+    }
+
+    // Pass 2: same token with different case, spacing or bracket width
+    for (i, (_, _, inner)) in segments.iter().enumerate() {
+        if resolutions[i].is_some() {
+            continue;
+        }
+        let normalized = normalize_inner(inner);
+        let found = take_matching(&mut remaining, &source_tokens, |token| {
+            token_inner(token) == normalized
+        })
+        .or_else(|| {
+            take_matching(&mut remaining, &source_tokens, |token| {
+                token_inner(token).eq_ignore_ascii_case(&normalized)
+            })
+        });
+        if let Some(token) = found {
+            resolutions[i] = Some(Resolution::Replace(token));
+        }
+    }
+
+    // Pass 3: translated token name that kept its number(s)
+    for (i, (_, _, inner)) in segments.iter().enumerate() {
+        if resolutions[i].is_some() {
+            continue;
+        }
+        let digits = digit_runs(inner);
+        if digits.is_empty() {
+            continue;
+        }
+        let candidates: Vec<&String> = source_tokens
+            .iter()
+            .filter(|t| remaining.get(*t).copied().unwrap_or(0) > 0)
+            .filter(|t| digit_runs(token_inner(t)) == digits)
+            .collect();
+        if candidates.len() == 1 {
+            let token = candidates[0].clone();
+            *remaining.get_mut(&token).unwrap() -= 1;
+            resolutions[i] = Some(Resolution::Replace(token));
+        }
+    }
+
+    // Pass 4: a single leftover placeholder and a single unexplained well-formed token
+    let leftover: Vec<&String> = source_tokens
+        .iter()
+        .filter(|t| remaining.get(*t).copied().unwrap_or(0) > 0)
+        .collect();
+    let unexplained: Vec<usize> = segments
+        .iter()
+        .enumerate()
+        .filter(|(i, (start, end, _))| {
+            resolutions[*i].is_none()
+                && PLACEHOLDER_SHAPE_REGEX.is_match(&translation[*start..*end])
+        })
+        .map(|(i, _)| i)
+        .collect();
+    if leftover.len() == 1 && remaining[leftover[0]] == 1 && unexplained.len() == 1 {
+        let token = leftover[0].clone();
+        *remaining.get_mut(&token).unwrap() -= 1;
+        resolutions[unexplained[0]] = Some(Resolution::Replace(token));
+    }
+
+    if remaining.values().any(|n| *n > 0) {
+        return None;
+    }
+
+    let mut repaired = String::with_capacity(translation.len());
+    let mut last = 0;
+    for ((start, end, _), resolution) in segments.iter().zip(resolutions) {
+        repaired.push_str(&translation[last..*start]);
+        match resolution {
+            Some(Resolution::Keep) | None => repaired.push_str(&translation[*start..*end]),
+            Some(Resolution::Replace(token)) => repaired.push_str(&token),
+            Some(Resolution::Remove) => {}
+        }
+        last = *end;
+    }
+    repaired.push_str(&translation[last..]);
+
+    if check_placeholders(source, &repaired).is_ok() {
+        Some(repaired)
+    } else {
+        None
+    }
+}
+
+/// Side table of one unit's opaque tokens; token `⟦n⟧` stands for `placeholders[n - 1]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaceholderTable {
+    placeholders: Vec<String>,
+}
+
+impl PlaceholderTable {
+    /// Replace every placeholder of `text` with a numbered opaque token.
+    ///
+    /// Repeated placeholders get separate numbers so their positions round-trip exactly.
+    pub fn encode(text: &str) -> (String, Self) {
+        let mut table = Self::default();
+        let encoded = PLACEHOLDER_REGEX
+            .replace_all(text, |caps: &regex::Captures| {
+                table.placeholders.push(caps[0].to_string());
+                Self::token(table.placeholders.len())
+            })
+            .to_string();
+        (encoded, table)
+    }
+
+    /// Replace opaque tokens with the placeholders they stand for.
+    ///
+    /// Tokens with unknown numbers and repeats of a token already decoded are
+    /// left as they are so the placeholder check reports them.
+    pub fn decode(&self, text: &str) -> String {
+        let mut decoded = vec![false; self.placeholders.len()];
+        OPAQUE_TOKEN_REGEX
+            .replace_all(text, |caps: &regex::Captures| {
+                let index = caps[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .filter(|&i| i < decoded.len() && !decoded[i]);
+                match index {
+                    Some(i) => {
+                        decoded[i] = true;
+                        self.placeholders[i].clone()
+                    }
+                    None => caps[0].to_string(),
+                }
+            })
+            .to_string()
+    }
+
+    /// Rewrite other text shown with the unit (examples, context lines,
+    /// glossary entries) in the unit's tokens.
+    ///
+    /// Placeholders of the unit become their first token; others are removed,
+    /// so the prompt never mixes named placeholders with opaque ones.
+    pub fn encode_related(&self, text: &str) -> String {
+        PLACEHOLDER_REGEX
+            .replace_all(text, |caps: &regex::Captures| {
+                match self.placeholders.iter().position(|p| p == &caps[0]) {
+                    Some(i) => Self::token(i + 1),
+                    None => String::new(),
+                }
+            })
+            .to_string()
+    }
+
+    /// The opaque tokens in use, in order.
+    pub fn tokens(&self) -> Vec<String> {
+        (1..=self.placeholders.len()).map(Self::token).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placeholders.is_empty()
+    }
+
+    fn token(n: usize) -> String {
+        format!("⟦{}⟧", n)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Resolution {
+    Keep,
+    Replace(String),
+    Remove,
+}
+
+fn count(tokens: &[String]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for token in tokens {
+        *counts.entry(token.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Take one copy of the first remaining source token accepted by `pred`.
+fn take_matching(
+    remaining: &mut HashMap<String, usize>,
+    source_tokens: &[String],
+    pred: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let token = source_tokens
+        .iter()
+        .find(|t| remaining.get(*t).copied().unwrap_or(0) > 0 && pred(t))?
+        .clone();
+    *remaining.get_mut(&token).unwrap() -= 1;
+    Some(token)
+}
+
+/// `[COLOR_2]` -> `COLOR_2`
+fn token_inner(token: &str) -> &str {
+    &token[1..token.len() - 1]
+}
+
+/// Collapse whitespace and dashes into single underscores: ` color - 2 ` -> `color_2`.
+fn normalize_inner(inner: &str) -> String {
+    let mut out = String::with_capacity(inner.len());
+    for c in inner.trim().chars() {
+        let c = if c.is_whitespace() || c == '-' || c == '＿' {
+            '_'
+        } else {
+            c
+        };
+        if c == '_' && out.ends_with('_') {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn digit_runs(text: &str) -> Vec<&str> {
+    DIGITS_REGEX.find_iter(text).map(|m| m.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_placeholders() {
+        let source = "[COLOR_2]勇者[COLOR_0]は[ITEM_5]を使った";
+        assert!(check_placeholders(source, "[COLOR_2]Hero[COLOR_0] used [ITEM_5]").is_ok());
+
+        let check = check_placeholders(source, "[COLOR_2]Hero used [ITEM_5][ITEM_5]");
+        assert_eq!(check.missing, vec!["[COLOR_0]".to_string()]);
+        assert_eq!(check.unexpected, vec!["[ITEM_5]".to_string()]);
+    }
+
+    #[test]
+    fn test_only_formatter_names_are_placeholders() {
+        assert_eq!(
+            extract_placeholders(
+                "[SIC][COLOR_2][GOLD][GOLDEN][variable_3][NAMEBOX_START]Harold[NAMEBOX_END][VAR_name][X]"
+            ),
+            vec![
+                "[COLOR_2]",
+                "[GOLD]",
+                "[variable_3]",
+                "[NAMEBOX_START]",
+                "[NAMEBOX_END]",
+                "[VAR_name]"
+            ]
+        );
+        assert!(check_placeholders("[COLOR_2]勇者[X]", "[COLOR_2]Hero").is_ok());
+    }
+
+    #[test]
+    fn test_repair_case_and_spacing() {
+        let source = "[COLOR_2]勇者[COLOR_0]と[variable_3]";
+        let repaired = repair_placeholders(source, "[color_2]Hero［COLOR 0］ and [variable_3]");
+        assert_eq!(
+            repaired.as_deref(),
+            Some("[COLOR_2]Hero[COLOR_0] and [variable_3]")
+        );
+    }
+
+    #[test]
+    fn test_repair_translated_name_and_duplicates() {
+        let source = "[NAME_1]は[GOLD]を手に入れた";
+        assert_eq!(
+            repair_placeholders(source, "[NOM_1] obtient [GOLD][GOLD]").as_deref(),
+            Some("[NAME_1] obtient [GOLD]")
+        );
+        assert_eq!(
+            repair_placeholders("[GOLD]を得た", "Got [OR]").as_deref(),
+            Some("Got [GOLD]")
+        );
+    }
+
+    #[test]
+    fn test_unrepairable_drop() {
+        let source = "[COLOR_2]勇者[COLOR_0]";
+        assert_eq!(repair_placeholders(source, "[COLOR_2]Hero"), None);
+        // Unrelated brackets are left alone
+        assert_eq!(
+            repair_placeholders("[WOLF_END]", "[sic] text").as_deref(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_opaque_round_trip() {
+        let source = "[COLOR_2]勇者[COLOR_0]は[GOLD]を[GOLD]拾った";
+        let (encoded, table) = PlaceholderTable::encode(source);
+        assert_eq!(encoded, "⟦1⟧勇者⟦2⟧は⟦3⟧を⟦4⟧拾った");
+        assert_eq!(table.tokens().len(), 4);
+        assert_eq!(table.decode(&encoded), source);
+
+        // Reordered tokens and stray spacing from the model still decode
+        assert_eq!(
+            table.decode("⟦2⟧Hero⟦1⟧ picked up ⟦ 3 ⟧ and ⟦4⟧"),
+            "[COLOR_0]Hero[COLOR_2] picked up [GOLD] and [GOLD]"
+        );
+    }
+
+    #[test]
+    fn test_stray_opaque_tokens_fail_the_check() {
+        let source = "[COLOR_2]勇者[COLOR_0]";
+        let (_, table) = PlaceholderTable::encode(source);
+
+        let unknown = table.decode("⟦1⟧Hero⟦2⟧ ⟦9⟧");
+        assert_eq!(unknown, "[COLOR_2]Hero[COLOR_0] ⟦9⟧");
+        assert_eq!(check_placeholders(source, &unknown).unexpected, ["⟦9⟧"]);
+        assert_eq!(repair_placeholders(source, &unknown), None);
+
+        let repeated = table.decode("⟦1⟧Hero⟦2⟧⟦1⟧");
+        assert_eq!(repeated, "[COLOR_2]Hero[COLOR_0]⟦1⟧");
+        assert!(!check_placeholders(source, &repeated).is_ok());
+
+        assert_eq!(
+            table.encode_related("[COLOR_0]Slime[GOLD][COLOR_2]"),
+            "⟦2⟧Slime⟦1⟧"
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Declarative formatter rules.
+//!
+//! Every engine code the formatters protect is described by a rule: a regex
+//! `pattern` matching the code in game text, a `placeholder` template the match
+//! is replaced with before translation, and a `restore` template that turns the
+//! placeholder back into the code afterwards. Templates reference capture groups
+//! as `$1`..`$9` or `${n}`; any other `$` is literal.
+//!
+//! The placeholder is found again after translation with `restore_pattern`.
+//! When a rule has none, it is derived from the placeholder template with every
+//! capture reference matching a number, which covers indexed codes such as
+//! `[COLOR_2]`. Rules capturing anything else must give the pattern explicitly.
+//!
+//! Built-in presets live in `rules/*.json`: one per engine plus optional presets
+//! for common message plugins. Projects enable plugin presets and add their own
+//! rules file through `FormatterSettings`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::{AppError, AppResult};
+use crate::models::settings::FormatterSettings;
+
+/// A formatter rule as written in a preset or project rules file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatterRule {
+    pub name: String,
+
+    /// Regex matching the code in game text
+    pub pattern: String,
+
+    /// Replacement used before translation
+    pub placeholder: String,
+
+    /// Replacement used when restoring the placeholder
+    pub restore: String,
+
+    /// Regex matching the placeholder (derived from `placeholder` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restore_pattern: Option<String>,
+}
+
+/// A named list of rules shipped with the application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePreset {
+    pub name: String,
+    pub description: String,
+    pub rules: Vec<FormatterRule>,
+}
+
+/// Presets holding the codes of each engine, applied without opting in
+const ENGINE_PRESETS: &[&str] = &["universal", "rpg_maker", "rpg_maker_xp", "wolf_rpg"];
+
+static PRESETS: Lazy<Vec<RulePreset>> = Lazy::new(|| {
+    [
+        include_str!("../../../rules/universal.json"),
+        include_str!("../../../rules/rpg_maker.json"),
+        include_str!("../../../rules/rpg_maker_xp.json"),
+        include_str!("../../../rules/wolf_rpg.json"),
+        include_str!("../../../rules/yanfly_message_core.json"),
+        include_str!("../../../rules/visustella_message_core.json"),
+    ]
+    .iter()
+    .map(|json| serde_json::from_str(json).expect("built-in rule preset is valid JSON"))
+    .collect()
+});
+
+static COMPILED_PRESETS: Lazy<HashMap<String, RuleSet>> = Lazy::new(|| {
+    PRESETS
+        .iter()
+        .map(|preset| {
+            let rules = RuleSet::compile(&preset.rules)
+                .unwrap_or_else(|e| panic!("built-in rule preset {}: {}", preset.name, e));
+            (preset.name.clone(), rules)
+        })
+        .collect()
+});
+
+/// Template capture reference: `$1` or `${1}`
+static TEMPLATE_REF_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$(?:\{([0-9]+)\}|([0-9]))").unwrap());
+
+/// Name of a placeholder in a template, with the closing bracket when nothing follows it
+static PLACEHOLDER_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([A-Za-z][A-Za-z_]*)(\])?").unwrap());
+
+/// Compiled rules, applied in order before translation and in reverse order
+/// when restoring.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    pattern: Regex,
+    placeholder: String,
+    restore_pattern: Regex,
+    restore: String,
+
+    /// For derived restore patterns: the template reference each group stands for
+    derived_groups: Option<Vec<usize>>,
+}
+
+impl RuleSet {
+    pub fn compile(rules: &[FormatterRule]) -> AppResult<Self> {
+        let rules = rules
+            .iter()
+            .map(CompiledRule::compile)
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Append the rules of `other` after these ones.
+    pub fn extend(&mut self, other: &RuleSet) {
+        self.rules.extend(other.rules.iter().cloned());
+    }
+
+    /// Replace every code with its placeholder.
+    pub fn prepare(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for rule in &self.rules {
+            if !rule.pattern.is_match(&result) {
+                continue;
+            }
+            result = rule
+                .pattern
+                .replace_all(&result, |caps: &Captures| {
+                    expand(&rule.placeholder, |n| caps.get(n).map(|m| m.as_str()))
+                })
+                .to_string();
+        }
+        result
+    }
+
+    /// Turn every placeholder back into its code.
+    pub fn restore(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for rule in self.rules.iter().rev() {
+            if !rule.restore_pattern.is_match(&result) {
+                continue;
+            }
+            result = rule
+                .restore_pattern
+                .replace_all(&result, |caps: &Captures| {
+                    expand(&rule.restore, |n| match &rule.derived_groups {
+                        Some(groups) => groups
+                            .iter()
+                            .position(|&group| group == n)
+                            .and_then(|index| caps.get(index + 1))
+                            .map(|m| m.as_str()),
+                        None => caps.get(n).map(|m| m.as_str()),
+                    })
+                })
+                .to_string();
+        }
+        result
+    }
+}
+
+impl CompiledRule {
+    fn compile(rule: &FormatterRule) -> AppResult<Self> {
+        let invalid = |e: regex::Error| {
+            AppError::Parsing(format!("Invalid pattern in rule '{}': {}", rule.name, e))
+        };
+        let pattern = Regex::new(&rule.pattern).map_err(invalid)?;
+        let (restore_pattern, derived_groups) = match &rule.restore_pattern {
+            Some(restore_pattern) => (Regex::new(restore_pattern).map_err(invalid)?, None),
+            None => {
+                let (derived, groups) = derive_restore_pattern(&rule.placeholder);
+                (Regex::new(&derived).map_err(invalid)?, Some(groups))
+            }
+        };
+        if rule.placeholder.is_empty() {
+            return Err(AppError::Parsing(format!(
+                "Rule '{}' has an empty placeholder",
+                rule.name
+            )));
+        }
+        Ok(Self {
+            pattern,
+            placeholder: rule.placeholder.clone(),
+            restore_pattern,
+            restore: rule.restore.clone(),
+            derived_groups,
+        })
+    }
+}
+
+/// Build a regex matching the output of a placeholder template.
+///
+/// Returns the pattern and, per capture group, the template reference it matches.
+fn derive_restore_pattern(placeholder: &str) -> (String, Vec<usize>) {
+    let mut pattern = String::new();
+    let mut groups = Vec::new();
+    let mut last = 0;
+    for caps in TEMPLATE_REF_REGEX.captures_iter(placeholder) {
+        let whole = caps.get(0).unwrap();
+        pattern.push_str(&regex::escape(&placeholder[last..whole.start()]));
+        let n: usize = caps
+            .get(1)
+            .or(caps.get(2))
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+        pattern.push_str("([0-9]+)");
+        groups.push(n);
+        last = whole.end();
+    }
+    pattern.push_str(&regex::escape(&placeholder[last..]));
+    (pattern, groups)
+}
+
+/// Fill capture references of a template.
+fn expand<'a>(template: &str, group: impl Fn(usize) -> Option<&'a str>) -> String {
+    TEMPLATE_REF_REGEX
+        .replace_all(template, |caps: &Captures| {
+            let n: usize = caps
+                .get(1)
+                .or(caps.get(2))
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(0);
+            group(n).unwrap_or("").to_string()
+        })
+        .to_string()
+}
+
+/// Compiled built-in preset by name.
+pub fn preset(name: &str) -> Option<&'static RuleSet> {
+    COMPILED_PRESETS.get(name)
+}
+
+/// Placeholder names used by the built-in presets, as regex fragments.
+///
+/// A name directly closed by `]` in the template (`[GOLD]`) must match exactly;
+/// any other name is a prefix followed by the captured values (`[COLOR_$1]`).
+pub fn builtin_placeholder_names() -> Vec<String> {
+    let mut names: Vec<String> = PRESETS
+        .iter()
+        .flat_map(|preset| &preset.rules)
+        .flat_map(|rule| PLACEHOLDER_NAME_REGEX.captures_iter(&rule.placeholder))
+        .map(|caps| match caps.get(2) {
+            Some(_) => regex::escape(&caps[1]),
+            None => format!("{}[A-Za-z0-9_>]+", regex::escape(&caps[1])),
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Optional presets projects can enable (message plugins).
+pub fn plugin_presets() -> Vec<RulePreset> {
+    PRESETS
+        .iter()
+        .filter(|preset| !ENGINE_PRESETS.contains(&preset.name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Project rules: enabled plugin presets, then the project's rules file.
+///
+/// A relative `rules_file` is resolved against the project root.
+pub fn project_rules(settings: &FormatterSettings, project_root: &Path) -> AppResult<RuleSet> {
+    let mut rules = RuleSet::default();
+    for name in &settings.presets {
+        match preset(name) {
+            Some(preset) if !ENGINE_PRESETS.contains(&name.as_str()) => rules.extend(preset),
+            _ => log::warn!("Unknown formatter rule preset: {}", name),
+        }
+    }
+
+    if let Some(rules_file) = &settings.rules_file {
+        let path = project_root.join(rules_file);
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            AppError::FileSystem(format!(
+                "Failed to read rules file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let custom: Vec<FormatterRule> = serde_json::from_str(&content).map_err(|e| {
+            AppError::Parsing(format!(
+                "Failed to parse rules file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        rules.extend(&RuleSet::compile(&custom)?);
+    }
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, placeholder: &str, restore: &str) -> FormatterRule {
+        FormatterRule {
+            name: "test".to_string(),
+            pattern: pattern.to_string(),
+            placeholder: placeholder.to_string(),
+            restore: restore.to_string(),
+            restore_pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_builtin_presets_compile() {
+        for preset_def in PRESETS.iter() {
+            assert!(preset(&preset_def.name).is_some(), "{}", preset_def.name);
+        }
+        assert_eq!(plugin_presets().len(), 2);
+    }
+
+    #[test]
+    fn test_derived_restore_pattern() {
+        let rules =
+            RuleSet::compile(&[rule(r"\\px\[([0-9]+)\]", "[POS_X_$1]", r"\px[$1]")]).unwrap();
+        let prepared = rules.prepare(r"\px[120]Hello");
+        assert_eq!(prepared, "[POS_X_120]Hello");
+        assert_eq!(rules.restore("Bonjour [POS_X_120]"), r"Bonjour \px[120]");
+    }
+
+    #[test]
+    fn test_explicit_restore_pattern_keeps_text_translatable() {
+        let rules = RuleSet::compile(&[FormatterRule {
+            restore_pattern: Some(r"\[NAMEBOX_START\](.*?)\[NAMEBOX_END\]".to_string()),
+            ..rule(
+                r"\\n<([^<>]*)>",
+                "[NAMEBOX_START]$1[NAMEBOX_END]",
+                r"\n<$1>",
+            )
+        }])
+        .unwrap();
+        assert_eq!(
+            rules.prepare(r"\n<ハロルド>行くぞ"),
+            "[NAMEBOX_START]ハロルド[NAMEBOX_END]行くぞ"
+        );
+        assert_eq!(
+            rules.restore("[NAMEBOX_START]Harold[NAMEBOX_END]Let's go"),
+            r"\n<Harold>Let's go"
+        );
+    }
+
+    #[test]
+    fn test_plugin_preset_round_trip() {
+        let rules = preset("yanfly_message_core").unwrap();
+        let text = r"\n<勇者>\fb\px[40]<WordWrap>\ii[3]を拾った";
+        let prepared = rules.prepare(text);
+        assert!(!prepared.contains('\\'), "{}", prepared);
+        assert_eq!(rules.restore(&prepared), text);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        assert!(RuleSet::compile(&[rule("(", "[X]", "x")]).is_err());
+    }
+}
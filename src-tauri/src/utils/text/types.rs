@@ -8,4 +8,5 @@ pub struct RawTextUnit {
     pub source_text: String,
     pub field_type: String,
     pub prompt_type: crate::models::translation::PromptType,
+    pub position: Option<crate::models::translation::UnitPosition>,
 }
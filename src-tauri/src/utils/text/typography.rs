@@ -0,0 +1,396 @@
+//! Target-language typography for translated text.
+//!
+//! Translations from Japanese often keep source punctuation (`「」`, `。`, `…`,
+//! full-width spaces) or mix quote styles. Before injection, `Typography` rewrites
+//! them for the target language:
+//!
+//! - full-width letters, digits and punctuation become half-width ones, and CJK
+//!   sentence punctuation (`。`, `、`) becomes `.` and `,` followed by a space
+//! - every quotation mark, including `「」` and `『』`, becomes the target style
+//! - runs of `…` become one ellipsis
+//! - repeated spaces and spaces before punctuation are removed (French gets a
+//!   no-break space before `;:!?` and inside guillemets instead)
+//! - the first letter after a sentence-ending `.`, `!` or `?` is capitalized
+//!
+//! Only the text between placeholders is rewritten; placeholders are copied
+//! unchanged. Full-width brackets are left alone, since turning `［GOLD］` into
+//! `[GOLD]` would create a placeholder. The start of a unit is never capitalized:
+//! engines prepend names to some messages (e.g. state messages), so a unit is
+//! not necessarily a sentence start.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::models::settings::{QuoteStyle, TypographySettings};
+use crate::utils::text::placeholders::placeholder_ranges;
+
+/// Target languages written without Latin-style punctuation
+const CJK_LANGUAGES: &[&str] = &["ja", "zh", "ko"];
+
+const NBSP: char = '\u{00A0}';
+
+static ELLIPSIS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[…‥]+").unwrap());
+static MULTI_SPACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r" {2,}").unwrap());
+static SPACE_BEFORE_NEWLINE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r" +\n").unwrap());
+static SPACE_BEFORE_PUNCT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([^\s]) +([,.!?;:)”»])").unwrap());
+static SPACE_AFTER_OPENING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"([(“«„]) +").unwrap());
+static SPACE_BEFORE_LOW_PUNCT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([^\s]) +([,.)])").unwrap());
+/// `;:!?` outside of numbers such as `12:30`
+static FRENCH_HIGH_PUNCT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([^\s;:!?«\u{00A0}])[ \u{00A0}]*([;:!?]+)([^0-9]|$)").unwrap());
+static FRENCH_CLOSING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([^\s\u{00A0}])[ \u{00A0}]*»").unwrap());
+static FRENCH_OPENING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"«[ \u{00A0}]*").unwrap());
+
+/// Typography rules for one target language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    quotes: QuoteStyle,
+    ascii_ellipsis: bool,
+    half_width: bool,
+    fix_spacing: bool,
+    capitalize_sentences: bool,
+    french_spacing: bool,
+}
+
+/// State carried from one text segment to the next across placeholders
+#[derive(Debug, Default)]
+struct ScanState {
+    /// Inside a quotation
+    quote_open: bool,
+
+    /// Last character written
+    previous: Option<char>,
+
+    /// A sentence ended and the next letter starts a new one
+    after_terminal: bool,
+    pending_capital: bool,
+
+    /// Whether the current word already contains a period (abbreviations)
+    word_has_period: bool,
+}
+
+/// Japanese corner brackets as straight quotes, the way Wolf RPG translations
+/// have always been written (applied whether or not the typography pass runs).
+pub fn corner_brackets_to_quotes(text: &str) -> String {
+    text.replace(['「', '」'], "\"")
+}
+
+impl Typography {
+    /// Typography for a target language, or `None` when nothing should be changed
+    /// (disabled, or a CJK target language keeping its own punctuation).
+    pub fn for_language(language_id: &str, settings: &TypographySettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let language = language_id
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if CJK_LANGUAGES.contains(&language.as_str()) {
+            return None;
+        }
+
+        Some(Self {
+            quotes: settings
+                .quotes
+                .unwrap_or_else(|| default_quote_style(&language)),
+            ascii_ellipsis: settings.ascii_ellipsis,
+            half_width: settings.half_width,
+            fix_spacing: settings.fix_spacing,
+            capitalize_sentences: settings.capitalize_sentences,
+            french_spacing: language == "fr",
+        })
+    }
+
+    /// Apply the typography rules to translated text containing placeholders.
+    pub fn apply(&self, text: &str) -> String {
+        let mut state = ScanState::default();
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+        for range in placeholder_ranges(text) {
+            result.push_str(&self.apply_segment(&text[last..range.start], &mut state));
+            result.push_str(&text[range.clone()]);
+            state.previous = Some(']');
+            state.after_terminal = false;
+            state.pending_capital = false;
+            state.word_has_period = false;
+            last = range.end;
+        }
+        result.push_str(&self.apply_segment(&text[last..], &mut state));
+        result
+    }
+
+    fn apply_segment(&self, segment: &str, state: &mut ScanState) -> String {
+        if segment.is_empty() {
+            return String::new();
+        }
+
+        let mut text = if self.half_width {
+            to_half_width(segment)
+        } else {
+            segment.to_string()
+        };
+        let ellipsis = if self.ascii_ellipsis { "..." } else { "…" };
+        text = ELLIPSIS_REGEX.replace_all(&text, ellipsis).to_string();
+        text = self.convert_quotes(&text, state);
+        if self.fix_spacing {
+            text = self.fix_spacing(&text);
+        }
+        if self.capitalize_sentences {
+            text = capitalize_sentences(&text, state);
+        }
+        text
+    }
+
+    fn convert_quotes(&self, text: &str, state: &mut ScanState) -> String {
+        let (open, close, secondary_open, secondary_close) = match self.quotes {
+            QuoteStyle::Straight => ('"', '"', '\'', '\''),
+            QuoteStyle::Curly => ('“', '”', '‘', '’'),
+            QuoteStyle::Guillemets => ('«', '»', '“', '”'),
+            QuoteStyle::Low => ('„', '“', '‚', '‘'),
+        };
+
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            let converted = match c {
+                '「' | '«' | '„' => {
+                    state.quote_open = true;
+                    open
+                }
+                '」' | '»' => {
+                    state.quote_open = false;
+                    close
+                }
+                '"' | '“' | '”' => {
+                    let opens = !state.quote_open && state.previous.map_or(true, is_boundary);
+                    state.quote_open = opens;
+                    if opens {
+                        open
+                    } else {
+                        close
+                    }
+                }
+                '『' => secondary_open,
+                '』' => secondary_close,
+                other => other,
+            };
+            result.push(converted);
+            state.previous = Some(converted);
+        }
+        result
+    }
+
+    fn fix_spacing(&self, text: &str) -> String {
+        let mut text = MULTI_SPACE_REGEX.replace_all(text, " ").to_string();
+        text = SPACE_BEFORE_NEWLINE_REGEX
+            .replace_all(&text, "\n")
+            .to_string();
+        if self.french_spacing {
+            text = SPACE_BEFORE_LOW_PUNCT_REGEX
+                .replace_all(&text, "$1$2")
+                .to_string();
+            text = FRENCH_HIGH_PUNCT_REGEX
+                .replace_all(&text, format!("$1{}$2$3", NBSP).as_str())
+                .to_string();
+            text = FRENCH_CLOSING_REGEX
+                .replace_all(&text, format!("$1{}»", NBSP).as_str())
+                .to_string();
+            text = FRENCH_OPENING_REGEX
+                .replace_all(&text, format!("«{}", NBSP).as_str())
+                .to_string();
+        } else {
+            text = SPACE_BEFORE_PUNCT_REGEX
+                .replace_all(&text, "$1$2")
+                .to_string();
+            text = SPACE_AFTER_OPENING_REGEX
+                .replace_all(&text, "$1")
+                .to_string();
+        }
+        text
+    }
+}
+
+/// Quote style used by a target language when the project does not set one
+fn default_quote_style(language: &str) -> QuoteStyle {
+    match language {
+        "fr" | "ru" => QuoteStyle::Guillemets,
+        "de" => QuoteStyle::Low,
+        _ => QuoteStyle::Straight,
+    }
+}
+
+/// Whether a quotation mark after `c` opens a quotation
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | '[' | '—' | '-' | NBSP)
+}
+
+/// Convert full-width characters; CJK sentence punctuation gains a trailing
+/// space when text follows it directly.
+fn to_half_width(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let (converted, spaced) = match c {
+            '。' | '．' => ('.', true),
+            '、' | '，' => (',', true),
+            '！' => ('!', true),
+            '？' => ('?', true),
+            '：' => (':', true),
+            '；' => (';', true),
+            '　' => (' ', false),
+            '〜' => ('~', false),
+            // Full-width brackets could form placeholders
+            '［' | '］' => (c, false),
+            '\u{FF01}'..='\u{FF5E}' => {
+                (char::from_u32(c as u32 - 0xFF01 + 0x21).unwrap_or(c), false)
+            }
+            _ => (c, false),
+        };
+        result.push(converted);
+        if spaced {
+            if let Some(&next) = chars.peek() {
+                if !next.is_whitespace() && !is_closing_punctuation(next) {
+                    result.push(' ');
+                }
+            }
+        }
+    }
+    result
+}
+
+fn is_closing_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '。' | '．'
+            | '、'
+            | '，'
+            | '！'
+            | '？'
+            | '.'
+            | ','
+            | '!'
+            | '?'
+            | '」'
+            | '』'
+            | '"'
+            | '”'
+            | '»'
+            | ')'
+            | '）'
+            | '…'
+            | '‥'
+    )
+}
+
+fn capitalize_sentences(text: &str, state: &mut ScanState) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        // No-break spaces bind French punctuation to its word
+        if c == NBSP {
+            result.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            if state.after_terminal {
+                state.pending_capital = true;
+            }
+            state.after_terminal = false;
+            state.word_has_period = false;
+            result.push(c);
+            previous = Some(c);
+            continue;
+        }
+
+        match c {
+            '.' => {
+                // Ellipses and abbreviations such as "e.g." do not end a sentence
+                state.after_terminal = previous != Some('.') && !state.word_has_period;
+                state.word_has_period = true;
+                state.pending_capital = false;
+            }
+            '!' | '?' => {
+                state.after_terminal = true;
+                state.pending_capital = false;
+            }
+            // A sentence ending inside a quotation may continue after it
+            '"' | '\'' | '”' | '’' | '»' | '“' | ')' => state.after_terminal = false,
+            // Opening marks keep a pending capital
+            '«' | '„' | '‘' | '‚' | '(' => {}
+            _ => {
+                if state.pending_capital && c.is_lowercase() {
+                    result.extend(c.to_uppercase());
+                } else {
+                    result.push(c);
+                }
+                state.after_terminal = false;
+                state.pending_capital = false;
+                previous = Some(c);
+                continue;
+            }
+        }
+        result.push(c);
+        previous = Some(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english() -> Typography {
+        Typography::for_language("en", &TypographySettings::default()).unwrap()
+    }
+
+    #[test]
+    fn test_japanese_punctuation_to_english() {
+        assert_eq!(
+            english().apply("「Wait。I'm coming！」"),
+            "\"Wait. I'm coming!\""
+        );
+        assert_eq!(english().apply("Well……okay"), "Well...okay");
+        assert_eq!(english().apply("Ｈｅｌｌｏ　world ！"), "Hello world!");
+    }
+
+    #[test]
+    fn test_placeholders_are_untouched() {
+        let text = "[COLOR_2]Hello  ！[COLOR_0] [NAME_1]。 then[FWSPC_1]";
+        assert_eq!(
+            english().apply(text),
+            "[COLOR_2]Hello![COLOR_0] [NAME_1]. Then[FWSPC_1]"
+        );
+    }
+
+    #[test]
+    fn test_sentence_capitalization() {
+        assert_eq!(
+            english().apply("was poisoned! it hurts, e.g. this... and that. ok"),
+            "was poisoned! It hurts, e.g. this... and that. Ok"
+        );
+    }
+
+    #[test]
+    fn test_french_quotes_and_spacing() {
+        let french = Typography::for_language("fr", &TypographySettings::default()).unwrap();
+        assert_eq!(
+            french.apply("Il dit \"Attends!\" puis : rien à 12:30"),
+            "Il dit «\u{a0}Attends\u{a0}!\u{a0}» puis\u{a0}: rien à 12:30"
+        );
+    }
+
+    #[test]
+    fn test_cjk_targets_and_disabled_settings() {
+        assert!(Typography::for_language("ja", &TypographySettings::default()).is_none());
+        assert!(Typography::for_language("zh-TW", &TypographySettings::default()).is_none());
+        let disabled = TypographySettings {
+            enabled: false,
+            ..TypographySettings::default()
+        };
+        assert!(Typography::for_language("en", &disabled).is_none());
+    }
+}
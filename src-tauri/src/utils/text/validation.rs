@@ -1,3 +1,171 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::core::error::{AppError, AppResult};
+use crate::models::settings::ExtractionSettings;
+use crate::models::translation::{DetectedLanguage, SkipReason, TranslationStatus};
+use crate::utils::text::language_detection::{language_scripts, script_of_char, Script};
+
+/// Minimum language identification confidence for auto-ignoring a unit
+pub const AUTO_IGNORE_CONFIDENCE: f32 = 0.6;
+
+/// Below this confidence another detected language does not count against the
+/// target (single words are often valid in several languages)
+const INCONCLUSIVE_CONFIDENCE: f32 = 0.3;
+
+/// Base ISO 639-1 code of a language id ("zh-TW" -> "zh")
+fn base_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// File name with an extension and no spaces (e.g. "Actor1.png", "img/faces/Hero")
+static FILE_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[^\s]+\.[A-Za-z0-9]{2,4}$").unwrap());
+
+/// Extraction heuristics for games written in one source language.
+///
+/// Technical strings look different depending on the language a game is written
+/// in: in a Japanese game any period or Latin word hints at a file name or code,
+/// while an English game is full of both.
+#[derive(Debug)]
+pub struct ValidationProfile {
+    /// Scripts the source language is written in
+    pub scripts: &'static [Script],
+
+    /// Punctuation marking text as translatable on its own (e.g. a "……" line)
+    pub punctuation: &'static [char],
+
+    /// Quotation marks that are not translatable when they are all the text holds
+    pub quote_marks: &'static [char],
+
+    /// Whole strings used as technical markers
+    pub markers: &'static [&'static str],
+
+    /// Whether any period, slash or backslash marks a file name or path; only
+    /// for languages with their own sentence punctuation
+    pub periods_are_technical: bool,
+
+    /// Longest ASCII text still considered translatable (unlimited when unset)
+    pub max_ascii_len: Option<usize>,
+}
+
+pub const JAPANESE: ValidationProfile = ValidationProfile {
+    scripts: &[Script::Kana, Script::Han],
+    punctuation: &['「', '」', '、', '。', '・', '…'],
+    quote_marks: &['「', '」'],
+    markers: &["終わり"],
+    periods_are_technical: true,
+    max_ascii_len: Some(100),
+};
+
+pub const CHINESE: ValidationProfile = ValidationProfile {
+    scripts: &[Script::Han],
+    punctuation: &['「', '」', '『', '』', '，', '。', '、', '！', '？', '：', '；', '…', '《', '》'],
+    quote_marks: &['「', '」', '『', '』', '“', '”'],
+    markers: &["结束", "結束"],
+    periods_are_technical: true,
+    max_ascii_len: Some(100),
+};
+
+pub const KOREAN: ValidationProfile = ValidationProfile {
+    scripts: &[Script::Hangul, Script::Han],
+    punctuation: &['「', '」', '…', '『', '』'],
+    quote_marks: &['「', '」', '『', '』'],
+    markers: &[],
+    // Korean ends sentences with ASCII periods
+    periods_are_technical: false,
+    max_ascii_len: Some(100),
+};
+
+/// Languages written in alphabets with Latin punctuation (English, French, Russian...)
+pub const ALPHABETIC: ValidationProfile = ValidationProfile {
+    scripts: &[Script::Latin, Script::Cyrillic, Script::Arabic],
+    punctuation: &[],
+    quote_marks: &['"', '“', '”', '«', '»'],
+    markers: &[],
+    periods_are_technical: false,
+    max_ascii_len: None,
+};
+
+impl ValidationProfile {
+    /// Profile for games written in `source_language`
+    pub fn for_language(source_language: &str) -> &'static ValidationProfile {
+        match base_language(source_language).as_str() {
+            "ja" => &JAPANESE,
+            "zh" => &CHINESE,
+            "ko" => &KOREAN,
+            _ => &ALPHABETIC,
+        }
+    }
+
+    fn contains_punctuation(&self, content: &str) -> bool {
+        content.chars().any(|c| self.punctuation.contains(&c))
+    }
+
+    /// Whether the text is a file name or path rather than game text
+    fn is_file_reference(&self, content: &str) -> bool {
+        if self.periods_are_technical {
+            return content.contains('.')
+                || content.contains('/')
+                || (content.contains('\\')
+                    && !content.contains("\\n[")
+                    && !content.contains("\\C[")
+                    && !content.contains("\\N["));
+        }
+        // Sentences have spaces; file names and paths do not
+        !content.contains(char::is_whitespace)
+            && (FILE_NAME_REGEX.is_match(content) || content.contains('/'))
+    }
+}
+
+/// Project allow/deny patterns applied before the profile heuristics.
+///
+/// Patterns match the raw text as it appears in the game files.
+#[derive(Debug, Default)]
+pub struct ExtractionFilter {
+    allow: Vec<Regex>,
+    deny: Vec<Regex>,
+}
+
+impl ExtractionFilter {
+    /// Compile the project's extraction patterns
+    pub fn from_settings(settings: &ExtractionSettings) -> AppResult<Self> {
+        let compile = |patterns: &[String]| -> AppResult<Vec<Regex>> {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|e| {
+                        AppError::Parsing(format!(
+                            "Invalid extraction pattern '{}': {}",
+                            pattern, e
+                        ))
+                    })
+                })
+                .collect()
+        };
+        Ok(Self {
+            allow: compile(&settings.allow)?,
+            deny: compile(&settings.deny)?,
+        })
+    }
+
+    /// Rule rejecting the text: deny patterns first, then allow patterns, then
+    /// the profile heuristics
+    pub fn rejection(&self, content: &str, profile: &ValidationProfile) -> Option<SkipReason> {
+        if self.deny.iter().any(|pattern| pattern.is_match(content)) {
+            return Some(SkipReason::DenyRule);
+        }
+        if self.allow.iter().any(|pattern| pattern.is_match(content)) {
+            return None;
+        }
+        ContentValidator::rejection(content, profile)
+    }
+}
+
 /// Universal validation: common logic for all engines
 ///
 /// This struct provides unified validation logic that works for all engines
@@ -7,55 +175,61 @@ pub struct ContentValidator;
 impl ContentValidator {
     /// Determine the initial translation status based on content and translation context
     ///
-    /// This method determines if text should be marked as Ignored when translating
-    /// from CJK to ASCII and the text is already in ASCII form.
+    /// Text is marked as Ignored when it is already in the target language: either
+    /// the language identifier says so with enough confidence, or every letter is
+    /// written in a script of the target language that the source language does not
+    /// use (e.g. Latin text in a Japanese game translated to English).
     pub fn get_initial_status(
         content: &str,
+        detected: Option<&DetectedLanguage>,
+        source_language: &str,
         target_language: &str,
-    ) -> crate::models::translation::TranslationStatus {
-        let content = content.trim();
-
-        // Check if we're translating to English/ASCII
-        let is_ascii_target =
-            target_language.to_lowercase() == "en" || target_language.to_lowercase() == "english";
+    ) -> TranslationStatus {
+        let source_language = base_language(source_language);
+        let target_language = base_language(target_language);
+        if source_language == target_language {
+            return TranslationStatus::NotTranslated;
+        }
 
-        if is_ascii_target {
-            // Check if the text is already in ASCII form
-            let is_ascii_text = content.chars().all(|c| {
-                c.is_ascii_alphanumeric() || c.is_ascii_punctuation() || c.is_ascii_whitespace()
-            });
+        let detected_target = detected.map(|d| base_language(&d.language) == target_language);
+        if detected_target == Some(true)
+            && detected.is_some_and(|d| d.confidence >= AUTO_IGNORE_CONFIDENCE)
+        {
+            return TranslationStatus::Ignored;
+        }
 
-            // If text is already ASCII and we're translating to English, ignore it
-            // (no need to translate English to English)
-            if is_ascii_text && content.chars().any(|c| c.is_alphabetic()) {
-                return crate::models::translation::TranslationStatus::Ignored;
-            }
+        let target_scripts = language_scripts(&target_language);
+        let source_scripts = language_scripts(&source_language);
+        let scripts: Vec<Script> = content.trim().chars().filter_map(script_of_char).collect();
+        let only_target_script = !scripts.is_empty()
+            && scripts
+                .iter()
+                .all(|s| target_scripts.contains(s) && !source_scripts.contains(s));
+        let no_evidence_against = detected_target != Some(false)
+            || detected.is_some_and(|d| d.confidence < INCONCLUSIVE_CONFIDENCE);
+        if only_target_script && no_evidence_against {
+            return TranslationStatus::Ignored;
         }
 
         // Default to NotTranslated for all other cases
-        crate::models::translation::TranslationStatus::NotTranslated
+        TranslationStatus::NotTranslated
     }
 
-    /// Universal validation logic for text content
-    ///
-    /// This method determines if text should be translated based on common
-    /// validation rules that apply to all engines.
-    pub fn validate_text(content: &str) -> bool {
+    /// Rule of the profile rejecting the text, or `None` when it is translatable
+    pub fn rejection(content: &str, profile: &ValidationProfile) -> Option<SkipReason> {
         let content = content.trim();
 
         // Skip empty or whitespace-only content
         if content.is_empty() {
-            return false;
+            return Some(SkipReason::Empty);
         }
 
-        // Detect if the content visually looks like CJK (Han, Kana, Hangul) or uses JP punctuation
-        let looks_cjk = content.chars().any(|c| {
-            (c >= '\u{4E00}' && c <= '\u{9FFF}') || // CJK Unified Ideographs
-            (c >= '\u{3040}' && c <= '\u{309F}') || // Hiragana
-            (c >= '\u{30A0}' && c <= '\u{30FF}') || // Katakana
-            (c >= '\u{AC00}' && c <= '\u{D7AF}') || // Hangul Syllables
-            Self::contains_japanese_punctuation(content)
-        });
+        // Detect if the content visually looks like the source language: its
+        // (non-Latin) scripts or its punctuation
+        let looks_native = content.chars().any(|c| {
+            script_of_char(c)
+                .is_some_and(|script| script != Script::Latin && profile.scripts.contains(&script))
+        }) || profile.contains_punctuation(content);
 
         // Skip EVXXX event names (technical identifiers)
         // Skip any text that starts with "EV" followed by numbers (e.g., "EV0", "EV1", "EV123", "EV002物乞いＢ")
@@ -63,7 +237,7 @@ impl ContentValidator {
             let suffix = &content[2..];
             // Check if the suffix starts with numbers (allows for additional content after numbers)
             if suffix.chars().next().map_or(false, |c| c.is_ascii_digit()) {
-                return false;
+                return Some(SkipReason::EventName);
             }
         }
 
@@ -71,22 +245,16 @@ impl ContentValidator {
         if content.starts_with("MAP") && content.len() >= 4 {
             let suffix = &content[3..];
             if suffix.chars().all(|c| c.is_ascii_digit()) {
-                return false;
+                return Some(SkipReason::MapName);
             }
         }
 
-        // Skip text that contains only Japanese quotation marks without any actual content
-        // Handle cases: "「", "」", "「」", "「 ", " 」", "「 」", and variations with spaces
-        let trimmed = content.trim();
-        if trimmed == "「" || trimmed == "」" || trimmed == "「」" {
-            return false;
-        }
-        
-        // Also check for quotes with spaces between them (like "「 」")
-        // Check if the text contains only Japanese quotes and spaces
-        if trimmed.chars().all(|c| c == '「' || c == '」' || c == ' ') && 
-           trimmed.chars().any(|c| c == '「' || c == '」') {
-            return false;
+        // Skip text that contains only quotation marks (and spaces) without any actual content
+        // Handle cases: "「", "」", "「」", "「 ", " 」", "「 」"
+        if content.chars().all(|c| profile.quote_marks.contains(&c) || c == ' ')
+            && content.chars().any(|c| profile.quote_marks.contains(&c))
+        {
+            return Some(SkipReason::QuotesOnly);
         }
 
         // Skip pure formatting codes (like "\\n[2]" alone)
@@ -96,18 +264,12 @@ impl ContentValidator {
             || content == "\\n[4]"
             || content == "\\n[5]"
         {
-            return false;
+            return Some(SkipReason::FormattingCode);
         }
 
         // Skip file names and extensions (images, sounds, etc.)
-        if content.contains('.')
-            || content.contains('/')
-            || (content.contains('\\')
-                && !content.contains("\\n[")
-                && !content.contains("\\C[")
-                && !content.contains("\\N["))
-        {
-            return false;
+        if profile.is_file_reference(content) {
+            return Some(SkipReason::FileName);
         }
 
         // Skip JavaScript code and expressions
@@ -116,27 +278,27 @@ impl ContentValidator {
             || content.contains("&&")
             || content.contains("==")
         {
-            return false;
+            return Some(SkipReason::Script);
         }
 
         // Skip technical markers
-        if content == "終わり" || content == "==" || content.starts_with("==") {
-            return false;
+        if profile.markers.contains(&content) || content.starts_with("==") {
+            return Some(SkipReason::TechnicalMarker);
         }
 
         // Skip any text with pipe characters (e.g., "はい|262|380", "戻る|492|380", "text|more|text")
         if content.contains('|') {
-            return false;
+            return Some(SkipReason::PipeSeparated);
         }
 
-        // Skip sound effect-like short ASCII words only when embedded in CJK-looking content
-        if looks_cjk && content.chars().all(|c| c.is_ascii_alphabetic()) && content.len() <= 20 {
-            return false;
+        // Skip sound effect-like short ASCII words only when embedded in native-looking content
+        if looks_native && content.chars().all(|c| c.is_ascii_alphabetic()) && content.len() <= 20 {
+            return Some(SkipReason::ForeignWord);
         }
 
-        // Skip pure ASCII/Latin text only when content overall looks CJK
-        if looks_cjk && content.chars().all(Self::is_ascii_or_fullwidth_latin) {
-            return false;
+        // Skip pure ASCII/Latin text only when content overall looks native
+        if looks_native && content.chars().all(Self::is_ascii_or_fullwidth_latin) {
+            return Some(SkipReason::ForeignText);
         }
 
         // Skip technical variable names and identifiers
@@ -145,47 +307,47 @@ impl ContentValidator {
                 .chars()
                 .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == 'x' || c == 'X')
         {
-            return false;
+            return Some(SkipReason::Identifier);
         }
 
         // Skip numeric-only content
         if content.chars().all(|c| c.is_ascii_digit()) {
-            return false;
+            return Some(SkipReason::Numeric);
         }
 
-        // Skip very short content only in CJK-looking context
-        // But allow Japanese/Chinese characters even if short
-        if looks_cjk && content.len() <= 3 {
-            // If it contains non-ASCII characters or Japanese punctuation, it might be translatable
+        // Skip very short content only in native-looking context
+        // But allow native characters even if short
+        if looks_native && content.len() <= 3 {
+            // If it contains non-ASCII characters or native punctuation, it might be translatable
             if content.chars().any(|c| c.is_alphabetic() && !c.is_ascii())
-                || Self::contains_japanese_punctuation(content)
+                || profile.contains_punctuation(content)
             {
-                return true;
+                return None;
             }
-            return false;
+            return Some(SkipReason::TooShort);
         }
 
-        // If content contains Japanese characters or other translatable text, allow it
+        // If content contains non-ASCII letters or other translatable text, allow it
         if content.chars().any(|c| c.is_alphabetic() && !c.is_ascii()) {
-            return true;
+            return None;
         }
 
-        // If content contains common Japanese punctuation or quotes, allow it
-        if Self::contains_japanese_punctuation(content) {
-            return true;
+        // If content contains the source language's punctuation or quotes, allow it
+        if profile.contains_punctuation(content) {
+            return None;
         }
 
         // Allow reasonable ASCII text that looks like translatable content
         // (alphabetic characters, reasonable length, not just technical identifiers)
         if content.chars().any(|c| c.is_alphabetic())
             && content.len() >= 2
-            && content.len() <= 100
+            && profile.max_ascii_len.map_or(true, |max| content.len() <= max)
             && !content.chars().all(|c| c.is_ascii_digit())
         {
-            return true;
+            return None;
         }
 
-        false
+        Some(SkipReason::NotTranslatable)
     }
 
     /// Optional warnings for text that passed validation
@@ -232,22 +394,16 @@ impl ContentValidator {
         (c >= '\u{FF41}' && c <= '\u{FF5A}') || // Full-width lowercase letters  
         (c >= '\u{FF10}' && c <= '\u{FF19}') // Full-width digits
     }
-
-    /// Helper function to check if content contains Japanese punctuation
-    fn contains_japanese_punctuation(content: &str) -> bool {
-        content.contains('「')
-            || content.contains('」')
-            || content.contains('、')
-            || content.contains('。')
-            || content.contains('・')
-            || content.contains('…')
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn translatable(text: &str, profile: &ValidationProfile) -> bool {
+        ContentValidator::rejection(text, profile).is_none()
+    }
+
     #[test]
     fn test_japanese_quotation_marks_validation() {
         // Test cases for Japanese quotation marks that should be filtered out
@@ -265,7 +421,7 @@ mod tests {
 
         println!("Testing Japanese quotation marks (should be filtered out):");
         for text in quotation_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (should be filtered)" } else { "FILTERED (correct)" });
             assert!(!result, 
                 "Text '{}' should be filtered out because it contains only Japanese quotation marks", text);
@@ -282,7 +438,7 @@ mod tests {
 
         println!("\nTesting legitimate texts with quotes (should NOT be filtered out):");
         for text in legitimate_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (correct)" } else { "FILTERED (should not be)" });
             assert!(result, 
                 "Text '{}' should NOT be filtered out", text);
@@ -304,7 +460,7 @@ mod tests {
 
         println!("Testing EV texts (should be filtered out):");
         for text in ev_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (should be filtered)" } else { "FILTERED (correct)" });
             assert!(!result, 
                 "Text '{}' should be filtered out because it starts with EV followed by numbers", text);
@@ -321,7 +477,7 @@ mod tests {
 
         println!("\nTesting legitimate texts (should NOT be filtered out):");
         for text in legitimate_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (correct)" } else { "FILTERED (should not be)" });
             assert!(result, 
                 "Text '{}' should NOT be filtered out", text);
@@ -345,7 +501,7 @@ mod tests {
 
         println!("Testing texts with pipes (should be filtered out):");
         for text in pipe_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (should be filtered)" } else { "FILTERED (correct)" });
             assert!(!result, 
                 "Text '{}' should be filtered out because it contains pipe characters", text);
@@ -361,10 +517,98 @@ mod tests {
 
         println!("\nTesting legitimate texts (should NOT be filtered out):");
         for text in legitimate_texts {
-            let result = ContentValidator::validate_text(text);
+            let result = translatable(text, &JAPANESE);
             println!("  '{}' -> {}", text, if result { "PASSED (correct)" } else { "FILTERED (should not be)" });
             assert!(result, 
                 "Text '{}' should NOT be filtered out", text);
         }
     }
+
+    #[test]
+    fn test_initial_status_for_language_pairs() {
+        use crate::utils::text::language_detection::detect_language;
+
+        let status = |text: &str, source: &str, target: &str| {
+            ContentValidator::get_initial_status(
+                text,
+                detect_language(text).as_ref(),
+                source,
+                target,
+            )
+        };
+
+        // Already in the target language
+        assert_eq!(status("Potion", "ja", "en"), TranslationStatus::Ignored);
+        assert_eq!(
+            status("Je ne sais pas où est le château.", "ja", "fr"),
+            TranslationStatus::Ignored
+        );
+        assert_eq!(status("薬草を使った", "en", "ja"), TranslationStatus::Ignored);
+
+        // Needs translation
+        assert_eq!(status("薬草を使った", "ja", "en"), TranslationStatus::NotTranslated);
+        assert_eq!(
+            status("Where are you going with the sword?", "ja", "fr"),
+            TranslationStatus::NotTranslated
+        );
+        assert_eq!(status("HPが回復した", "ja", "en"), TranslationStatus::NotTranslated);
+        assert_eq!(status("薬草", "ja", "zh"), TranslationStatus::NotTranslated);
+    }
+
+    #[test]
+    fn test_source_language_profiles() {
+        let english = ValidationProfile::for_language("en");
+        assert!(translatable("Hello. Where are you going?", english));
+        assert!(translatable(
+            "I found it under the bridge, near the old mill, and brought it back here so you could see it for yourself.",
+            english
+        ));
+        assert!(!translatable("Actor1.png", english));
+        assert!(!translatable("img/faces/Hero", english));
+        assert!(!translatable("switch_01", english));
+
+        let chinese = ValidationProfile::for_language("zh-CN");
+        assert!(translatable("你好，勇者。", chinese));
+        assert!(translatable("……", chinese));
+        assert!(!translatable("『』", chinese));
+        assert!(!translatable("结束", chinese));
+
+        let korean = ValidationProfile::for_language("ko");
+        assert!(translatable("안녕하세요. 용사님.", korean));
+        assert!(!translatable("Actor1.png", korean));
+    }
+
+    #[test]
+    fn test_extraction_filter_overrides_heuristics() {
+        assert_eq!(
+            ContentValidator::rejection("「A/B」", &JAPANESE),
+            Some(SkipReason::FileName)
+        );
+        assert_eq!(
+            ContentValidator::rejection("はい|262|380", &JAPANESE),
+            Some(SkipReason::PipeSeparated)
+        );
+
+        let filter = ExtractionFilter::from_settings(&ExtractionSettings {
+            allow: vec!["^「.*」$".to_string()],
+            deny: vec!["DEBUG".to_string()],
+        })
+        .unwrap();
+        assert_eq!(filter.rejection("「A/B」", &JAPANESE), None);
+        assert_eq!(
+            filter.rejection("「DEBUG」", &JAPANESE),
+            Some(SkipReason::DenyRule)
+        );
+        assert_eq!(filter.rejection("勇者", &JAPANESE), None);
+        assert_eq!(
+            filter.rejection("Actor1.png", &JAPANESE),
+            Some(SkipReason::FileName)
+        );
+
+        let invalid = ExtractionSettings {
+            allow: vec!["(".to_string()],
+            deny: Vec::new(),
+        };
+        assert!(ExtractionFilter::from_settings(&invalid).is_err());
+    }
 }